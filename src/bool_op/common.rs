@@ -183,7 +183,7 @@ fn second_classification(t: &Triangle, tdesc: &mut TDesc) -> Marker {
     return Marker::Unclassified;
 }
 
-fn triangulate_all(it_to_desc: HashMap<usize, TDesc>) -> HashMap<Triangle, Marker> {
+fn triangulate_all(it_to_desc: HashMap<usize, TDesc>) -> Result<HashMap<Triangle, Marker>, &'static str> {
     let mut new_ts : HashMap<Triangle, Marker>  = HashMap::new();
     for (_, mut tdesc) in it_to_desc.into_iter() {
         let mut points : Vec<Point> = Vec::new();
@@ -191,7 +191,7 @@ fn triangulate_all(it_to_desc: HashMap<usize, TDesc>) -> HashMap<Triangle, Marke
             points.push(p);
         }
 
-        let ts = triangulate3d(points, tdesc.plane.clone(), TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(points, tdesc.plane.clone(), TriangulationAlgorithm::Incremental)?;
 
         for mut t in ts {
             let m1 = first_classification(&t, &mut tdesc);
@@ -204,7 +204,7 @@ fn triangulate_all(it_to_desc: HashMap<usize, TDesc>) -> HashMap<Triangle, Marke
             new_ts.insert(t, res_marker);
         }
     }
-    return new_ts;
+    return Ok(new_ts);
 }
 
 #[allow(dead_code)]
@@ -432,8 +432,8 @@ impl BoolOpPerformer {
 
         let triangulation_start = PreciseTime::now();
         info!("<triangulate_all> has been started ...");
-        let new_ts_a : HashMap<Triangle, Marker> = triangulate_all(a_it_to_tdec);
-        let new_ts_b : HashMap<Triangle, Marker> = triangulate_all(b_it_to_tdec);
+        let new_ts_a : HashMap<Triangle, Marker> = triangulate_all(a_it_to_tdec)?;
+        let new_ts_b : HashMap<Triangle, Marker> = triangulate_all(b_it_to_tdec)?;
         info!("Triangulated intersection area, for model A, contains {0} triangles.", new_ts_a.len());
         info!("Triangulated intersection area, for model B, contains {0} triangles.", new_ts_b.len());
         info!("<triangulate_all> has been performed in {0} seconds.\n", triangulation_start.to(PreciseTime::now()));
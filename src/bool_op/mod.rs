@@ -1,4 +1,7 @@
 mod common;
 mod robust_bool_ops;
+mod polygon_bool_ops;
 pub(crate) use self::common::BoolOpPerformer;
-pub use self::robust_bool_ops::BoolOpResult;
\ No newline at end of file
+pub use self::robust_bool_ops::BoolOpResult;
+pub use self::polygon_bool_ops::{PolygonBoolOp, polygon_bool_op, union2d, intersection2d, difference2d, xor2d};
+pub(crate) use self::polygon_bool_ops::resolve_positive_winding;
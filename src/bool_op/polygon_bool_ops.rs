@@ -0,0 +1,322 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use primitives::*;
+use primitives::predicates::orient2d;
+use triangulation::constrained_delaunay_triangulation::ConstrainedTriangulation;
+use primitives::polygon::build_trees;
+
+/// Boolean operations on planar regions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolygonBoolOp {
+    Union,
+    Intersection,
+    Difference,
+    Xor
+}
+
+/// This function performs the exact boolean operation on two planar regions in the plane xy.
+/// Each region is a forest of `PolygonTreeNode`: children of a polygon are holes, children of holes are islands.
+/// The result is a forest with counterclockwise outer loops and clockwise holes (looking from +z),
+/// regions, touching at a point, are separate trees.
+/// # Arguments
+///
+/// * `a` - The first region.
+/// * `b` - The second region.
+/// * `op` - An operation, `Difference` is `a` minus `b`.
+pub fn polygon_bool_op(a: &[PolygonTreeNode], b: &[PolygonTreeNode], op: PolygonBoolOp) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    let loops_a = collect_loops(a)?;
+    let loops_b = collect_loops(b)?;
+
+    // правило чет-нечет: точка внутри области, если число оборотов контуров вокруг нее нечетно
+    return build_regions(&vec![loops_a, loops_b], |windings| {
+        let (in_a, in_b) = (windings[0] % 2 != 0, windings[1] % 2 != 0);
+        match op {
+            PolygonBoolOp::Union => in_a || in_b,
+            PolygonBoolOp::Intersection => in_a && in_b,
+            PolygonBoolOp::Difference => in_a && !in_b,
+            PolygonBoolOp::Xor => in_a != in_b
+        }
+    });
+}
+
+// область, в которой сумма оборотов контуров положительна; контуры могут самопересекаться
+pub(crate) fn resolve_positive_winding(loops: &Vec<Vec<Point>>) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    return build_regions(&vec![loops.clone()], |windings| windings[0] > 0);
+}
+
+// треугольники триангуляции не пересекают границ, поэтому области выбираются по числам оборотов
+// каждой группы контуров вокруг треугольников
+fn build_regions<F>(groups: &Vec<Vec<Vec<Point>>>, is_selected: F) -> Result<Vec<PolygonTreeNode>, &'static str>
+    where F: Fn(&Vec<i64>) -> bool
+{
+    let mut points: Vec<Point> = Vec::new();
+    let mut constraints: Vec<Segment> = Vec::new();
+    let mut owners: Vec<usize> = Vec::new();
+    for (g, loops) in groups.iter().enumerate() {
+        for l in loops.iter() {
+            for i in 0..l.len() {
+                constraints.push(Segment::new(l[i].clone(), l[(i + 1) % l.len()].clone()));
+                owners.push(g);
+            }
+            points.extend(l.iter().cloned());
+        }
+    }
+
+    let ct = ConstrainedTriangulation::new(points, constraints)?;
+    let windings: Vec<Vec<i64>> = (0..groups.len())
+        .map(|g| {
+            let mut multiplicities: HashMap<(usize, usize), i64> = HashMap::new();
+            for i in (0..owners.len()).filter(|&i| owners[i] == g) {
+                for &(a, b) in ct.get_constraint_edges(i) {
+                    *multiplicities.entry((a, b)).or_insert(0) += 1;
+                    *multiplicities.entry((b, a)).or_insert(0) -= 1;
+                }
+            }
+            return get_winding_numbers(&ct, &multiplicities);
+        })
+        .collect();
+    let selected: Vec<bool> = (0..ct.get_index_triangles().len())
+        .map(|i| is_selected(&windings.iter().map(|w| w[i]).collect()))
+        .collect();
+
+    let loops = extract_boundary(&ct, &selected);
+    return Ok(build_trees(loops, &Vector::new_from_f64(0., 0., 1.)));
+}
+
+/// This function returns the union of two planar regions. See `polygon_bool_op`.
+pub fn union2d(a: &[PolygonTreeNode], b: &[PolygonTreeNode]) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    return polygon_bool_op(a, b, PolygonBoolOp::Union);
+}
+
+/// This function returns the intersection of two planar regions. See `polygon_bool_op`.
+pub fn intersection2d(a: &[PolygonTreeNode], b: &[PolygonTreeNode]) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    return polygon_bool_op(a, b, PolygonBoolOp::Intersection);
+}
+
+/// This function returns the difference `a` minus `b` of two planar regions. See `polygon_bool_op`.
+pub fn difference2d(a: &[PolygonTreeNode], b: &[PolygonTreeNode]) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    return polygon_bool_op(a, b, PolygonBoolOp::Difference);
+}
+
+/// This function returns the symmetric difference of two planar regions. See `polygon_bool_op`.
+pub fn xor2d(a: &[PolygonTreeNode], b: &[PolygonTreeNode]) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    return polygon_bool_op(a, b, PolygonBoolOp::Xor);
+}
+
+fn collect_loops(forest: &[PolygonTreeNode]) -> Result<Vec<Vec<Point>>, &'static str> {
+    let mut loops: Vec<Vec<Point>> = Vec::new();
+    let mut nodes: Vec<&PolygonTreeNode> = forest.iter().collect();
+    while let Some(node) = nodes.pop() {
+        let mut points = node.get_cur_polygon().get_points();
+        points.dedup();
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 3 {
+            return Err("Polygon has less than 3 points!");
+        }
+        loops.push(points);
+        nodes.extend(node.get_children_ref().iter());
+    }
+    return Ok(loops);
+}
+
+// обход в ширину от треугольников оболочки, снаружи которой число оборотов равно нулю: при переходе через ребро a->b
+// с левой стороны на правую число оборотов уменьшается на число ребер контуров, идущих из a в b,
+// за вычетом числа ребер, идущих из b в a
+fn get_winding_numbers(ct: &ConstrainedTriangulation, multiplicities: &HashMap<(usize, usize), i64>) -> Vec<i64> {
+    let ts = ct.get_index_triangles();
+    let get_multiplicity = |a: usize, b: usize| multiplicities.get(&(a, b)).cloned().unwrap_or(0);
+
+    let mut windings: Vec<Option<i64>> = vec![None; ts.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for (i, t) in ts.iter().enumerate() {
+        for j in 0..3 {
+            let (a, b) = (t[j], t[(j + 1) % 3]);
+            if windings[i].is_none() && ct.get_edge_triangle(b, a).is_none() {
+                windings[i] = Some(get_multiplicity(a, b));
+                queue.push_back(i);
+            }
+        }
+    }
+
+    while let Some(i) = queue.pop_front() {
+        let t = ts[i];
+        for j in 0..3 {
+            let (a, b) = (t[j], t[(j + 1) % 3]);
+            if let Some(n) = ct.get_edge_triangle(b, a) {
+                if windings[n].is_none() {
+                    windings[n] = Some(windings[i].unwrap() - get_multiplicity(a, b));
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+    return windings.into_iter().map(|w| w.unwrap_or(0)).collect();
+}
+
+fn extract_boundary(ct: &ConstrainedTriangulation, selected: &Vec<bool>) -> Vec<Vec<Point>> {
+    let ps = ct.get_points();
+    let ts = ct.get_index_triangles();
+    let is_selected = |a: usize, b: usize| -> bool {
+        return ct.get_edge_triangle(a, b).map_or(false, |i| selected[i]);
+    };
+
+    let mut boundary: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for (i, t) in ts.iter().enumerate() {
+        if !selected[i] {
+            continue;
+        }
+        for j in 0..3 {
+            let (a, b) = (t[j], t[(j + 1) % 3]);
+            if !is_selected(b, a) {
+                boundary.insert((a, b));
+            }
+        }
+    }
+
+    // следующее ребро ищется обходом веера выбранных треугольников вокруг вершины,
+    // поэтому контуры, касающиеся в точке, разделяются
+    let mut next: BTreeMap<(usize, usize), (usize, usize)> = BTreeMap::new();
+    for &(a, v) in boundary.iter() {
+        let mut t = ts[ct.get_edge_triangle(a, v).unwrap()];
+        loop {
+            let k = t.iter().position(|&x| x == v).unwrap();
+            let w = t[(k + 1) % 3];
+            if !is_selected(w, v) {
+                next.insert((a, v), (v, w));
+                break;
+            }
+            t = ts[ct.get_edge_triangle(w, v).unwrap()];
+        }
+    }
+
+    let mut loops: Vec<Vec<Point>> = Vec::new();
+    while let Some(&start) = boundary.iter().next() {
+        let mut indices: Vec<usize> = Vec::new();
+        let mut e = start;
+        loop {
+            boundary.remove(&e);
+            indices.push(e.0);
+            e = next[&e];
+            if e == start {
+                break;
+            }
+        }
+
+        // вершины на прямых участках (точки разбиения ограничений) удаляются
+        let n = indices.len();
+        let points: Vec<Point> = (0..n)
+            .filter(|&i| orient2d(&ps[indices[(i + n - 1) % n]], &ps[indices[i]], &ps[indices[(i + 1) % n]]) != Ordering::Equal)
+            .map(|i| ps[indices[i]].clone())
+            .collect();
+        loops.push(points);
+    }
+    return loops;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use bool_op::*;
+
+    fn square(x0: f64, y0: f64, x1: f64, y1: f64) -> PolygonTreeNode {
+        let points = vec![
+            Point::new_from_f64(x0, y0, 0.),
+            Point::new_from_f64(x1, y0, 0.),
+            Point::new_from_f64(x1, y1, 0.),
+            Point::new_from_f64(x0, y1, 0.)
+        ];
+        return PolygonTreeNode::new(Polygon::new(points, Vector::new_from_f64(0., 0., 1.)));
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = vec![square(0., 0., 2., 2.)];
+        let b = vec![square(1., 1., 3., 3.)];
+
+        let union = union2d(&a, &b).unwrap();
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].get_cur_polygon().get_points_ref().len(), 8);
+        assert_eq!(forest_area(&union), Number::new(7.));
+
+        let intersection = intersection2d(&a, &b).unwrap();
+        assert_eq!(intersection.len(), 1);
+        assert_eq!(intersection[0].get_cur_polygon().get_points_ref().len(), 4);
+        assert_eq!(forest_area(&intersection), Number::new(1.));
+
+        let difference = difference2d(&a, &b).unwrap();
+        assert_eq!(difference.len(), 1);
+        assert_eq!(forest_area(&difference), Number::new(3.));
+
+        // две части касаются в точках (1, 2) и (2, 1)
+        let xor = xor2d(&a, &b).unwrap();
+        assert_eq!(xor.len(), 2);
+        assert_eq!(forest_area(&xor), Number::new(6.));
+    }
+
+    #[test]
+    fn holes_and_islands() {
+        let a = vec![square(0., 0., 4., 4.)];
+        let b = vec![square(1., 1., 3., 3.)];
+
+        let difference = difference2d(&a, &b).unwrap();
+        assert_eq!(difference.len(), 1);
+        assert_eq!(difference[0].get_children_ref().len(), 1);
+        assert!(difference[0].get_cur_polygon().signed_area().is_it_positive());
+        assert!(difference[0].get_child_polygons()[0].signed_area().is_it_negative());
+        assert_eq!(forest_area(&difference), Number::new(12.));
+
+        let island = vec![square(1.5, 1.5, 2.5, 2.5)];
+        let with_island = union2d(&difference, &island).unwrap();
+        assert_eq!(with_island.len(), 1);
+        assert_eq!(with_island[0].get_children_ref()[0].get_children_ref().len(), 1);
+        assert_eq!(forest_area(&with_island), Number::new(13.));
+
+        let filled = union2d(&difference, &b).unwrap();
+        assert_eq!(filled.len(), 1);
+        assert!(filled[0].get_children_ref().is_empty());
+        assert_eq!(forest_area(&filled), Number::new(16.));
+    }
+
+    #[test]
+    fn disjoint_and_empty_regions() {
+        let a = vec![square(0., 0., 1., 1.)];
+        let b = vec![square(2., 0., 3., 1.)];
+
+        assert_eq!(union2d(&a, &b).unwrap().len(), 2);
+        assert!(intersection2d(&a, &b).unwrap().is_empty());
+        assert_eq!(forest_area(&difference2d(&a, &b).unwrap()), Number::new(1.));
+        assert_eq!(forest_area(&union2d(&a, &vec![]).unwrap()), Number::new(1.));
+
+        // общая сторона исчезает при объединении
+        let c = vec![square(1., 0., 2., 1.)];
+        let union = union2d(&a, &c).unwrap();
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].get_cur_polygon().get_points_ref().len(), 4);
+    }
+
+    #[test]
+    fn many_sided_polygons() {
+        let circle = |cx: f64, n: usize| -> Vec<PolygonTreeNode> {
+            let points: Vec<Point> = (0..n)
+                .map(|i| {
+                    let phi = 2. * ::std::f64::consts::PI * (i as f64) / (n as f64);
+                    Point::new_from_f64(cx + phi.cos(), phi.sin(), 0.)
+                })
+                .collect();
+            return vec![PolygonTreeNode::new(Polygon::new(points, Vector::new_from_f64(0., 0., 1.)))];
+        };
+        let a = circle(0., 200);
+        let b = circle(1., 200);
+
+        let union = union2d(&a, &b).unwrap();
+        let intersection = intersection2d(&a, &b).unwrap();
+        assert_eq!(union.len(), 1);
+        assert!(union[0].get_children_ref().is_empty());
+        assert_eq!(intersection.len(), 1);
+        assert_eq!(forest_area(&union) + forest_area(&intersection), forest_area(&a) + forest_area(&b));
+    }
+}
@@ -792,6 +792,18 @@ impl BoolOpResult {
     /// * `mesh_a_ref` - A reference to the first mesh.
     /// * `mesh_a_ref` - A reference to the second mesh.
     pub fn new(mesh_a_ref: &Mesh, mesh_b_ref: &Mesh) -> Result<BoolOpResult, &'static str> {
+        return BoolOpResult::new_with_algorithm(mesh_a_ref, mesh_b_ref, TriangulationAlgorithm::EarClipping);
+    }
+
+    /// This method is the same as `new`, but triangles cut by intersection curves are re-triangulated with `alg`.
+    /// `ConstrainedDelaunay` gives better shaped triangles than `EarClipping`, which is used by `new`.
+    /// `Incremental` doesn't support segments, so the `Err` is returned for it.
+    /// # Arguments
+    ///
+    /// * `mesh_a_ref` - A reference to the first mesh.
+    /// * `mesh_a_ref` - A reference to the second mesh.
+    /// * `alg` - An algorithm of re-triangulation.
+    pub fn new_with_algorithm(mesh_a_ref: &Mesh, mesh_b_ref: &Mesh, alg: TriangulationAlgorithm) -> Result<BoolOpResult, &'static str> {
         let start = PreciseTime::now();
 
         if !mesh_a_ref.geometry_check()  {
@@ -868,13 +880,15 @@ impl BoolOpResult {
 
         let re_triangulated_mesh_a = BoolOpResult::re_triangulate_mesh(
             it_to_ss_for_mesh_a.clone(),
-            &mesh_a
-        );
+            &mesh_a,
+            alg
+        )?;
 
         let re_triangulated_mesh_b = BoolOpResult::re_triangulate_mesh(
             it_to_ss_for_mesh_b.clone(),
-            &mesh_b
-        );
+            &mesh_b,
+            alg
+        )?;
         info!("Retriangulation is finished in {0} seconds.", retr_start.to(PreciseTime::now()));
 
         let build_blocks_start = PreciseTime::now();
@@ -898,18 +912,19 @@ impl BoolOpResult {
 
     fn re_triangulate_mesh(
         it_to_ss: HashMap<usize, Vec<Segment>>,
-        mesh: &Mesh
-    ) -> Mesh {
+        mesh: &Mesh,
+        alg: TriangulationAlgorithm
+    ) -> Result<Mesh, &'static str> {
         let mut new_mesh = mesh.clone();
 
         for (it, ss) in  it_to_ss {
             let t: Triangle = mesh.get_triangle(it.clone());
-            let ts : Vec<Triangle> = triangulate_ptree3d(t, ss);
+            let ts : Vec<Triangle> = triangulate_ptree3d(t, ss, alg)?;
             new_mesh.remove_triangle(&it);
             new_mesh.add_triangles(ts);
         }
 
-        return new_mesh;
+        return Ok(new_mesh);
     }
 
     pub(crate) fn get_intermidiate_meshes(&self) -> (Mesh, Mesh) {
@@ -942,6 +957,8 @@ impl BoolOpResult {
 mod tests {
     use primitives::*;
     use bool_op::BoolOpResult;
+    use modeling::{create_box, create_cylinder};
+    use triangulation::TriangulationAlgorithm;
     use std::fs::File;
     use env_logger::init  as env_logger_init;
     use std::path::Path;
@@ -1281,6 +1298,22 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn re_triangulation_algorithms() {
+        let cube = create_box(&Point::new_from_f64(-1., -1., 0.5), &Point::new_from_f64(1., 1., 2.)).unwrap();
+        let cylinder = create_cylinder(Number::new(0.5), Number::new(1.), 8).unwrap();
+        let volume = cube.get_volume() + cylinder.get_volume();
+
+        for alg in vec![TriangulationAlgorithm::EarClipping, TriangulationAlgorithm::ConstrainedDelaunay] {
+            let result = BoolOpResult::new_with_algorithm(&cube, &cylinder, alg).unwrap();
+            let union = result.union();
+            assert!(union.geometry_check());
+            let intersection = result.intersection().iter().fold(Number::new(0.), |v, m| v + m.get_volume());
+            assert_eq!(union.get_volume() + intersection, volume);
+        }
+        assert!(BoolOpResult::new_with_algorithm(&cube, &cylinder, TriangulationAlgorithm::Incremental).is_err());
+    }
 }
 
 /*
@@ -0,0 +1,133 @@
+use primitives::*;
+use modeling::Section;
+
+/// This structure collects 2D contours, polylines and triangles to write them to SVG or DXF.
+/// Only x and y coordinates of points are used, sections are projected to their planes first. Orders of points are preserved,
+/// so holes of polygon trees keep their orientation.
+#[derive(Clone, Debug)]
+pub struct Drawing {
+    pub(crate) contours: Vec<(Polygon, usize)>,
+    pub(crate) polylines: Vec<Vec<Point>>,
+    pub(crate) triangles: Vec<Vec<Point>>
+}
+
+impl Drawing {
+    /// This method creates an empty drawing.
+    pub fn new() -> Drawing {
+        return Drawing {
+            contours: Vec::new(),
+            polylines: Vec::new(),
+            triangles: Vec::new()
+        };
+    }
+
+    /// This method adds a closed polygon to the drawing.
+    /// # Arguments
+    ///
+    /// * `polygon` - A polygon.
+    pub fn add_polygon(&mut self, polygon: &Polygon) {
+        self.contours.push((polygon.clone(), 0));
+    }
+
+    /// This method adds all polygons of the tree to the drawing.
+    /// Polygons of odd depth are considered as holes.
+    /// # Arguments
+    ///
+    /// * `tree` - A tree of nested polygons.
+    pub fn add_polygon_tree(&mut self, tree: &PolygonTreeNode) {
+        let mut nodes: Vec<(&PolygonTreeNode, usize)> = vec![(tree, 0)];
+        while let Some((node, depth)) = nodes.pop() {
+            self.contours.push((node.get_cur_polygon(), depth));
+            for child in node.get_children_ref().iter().rev() {
+                nodes.push((child, depth + 1));
+            }
+        }
+    }
+
+    /// This method adds an open polyline to the drawing.
+    /// # Arguments
+    ///
+    /// * `points` - Points of the polyline.
+    pub fn add_polyline(&mut self, points: &[Point]) {
+        self.polylines.push(points.to_vec());
+    }
+
+    /// This method adds closed contours and open polylines of the section to the drawing.
+    /// Points are projected to a 2D frame of the section plane. For horizontal planes axes of the frame
+    /// are x and y (y is reversed if the normal looks down), otherwise the first axis is horizontal.
+    /// The frame is right-handed with respect to the normal, so outer boundaries stay counterclockwise.
+    /// # Arguments
+    ///
+    /// * `section` - A section of a mesh.
+    pub fn add_section(&mut self, section: &Section) {
+        let (u, v) = get_plane_frame(&section.plane);
+        let project = |p: &Point| -> Point {
+            let c = [p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64(), p.z.clone().convert_to_f64()];
+            return Point::new_from_f64(dot(&c, &u), dot(&c, &v), 0.);
+        };
+
+        for tree in section.contours.iter() {
+            let mut projected = tree.clone();
+            projected.map(&|p: &mut Point| *p = project(p));
+            self.add_polygon_tree(&projected);
+        }
+        for polyline in section.open_polylines.iter() {
+            let projected: Vec<Point> = polyline.iter().map(|p| project(p)).collect();
+            self.add_polyline(&projected);
+        }
+    }
+
+    /// This method adds triangles to the drawing, e.g. an output of a triangulation.
+    /// # Arguments
+    ///
+    /// * `triangles` - Triangles.
+    pub fn add_triangles(&mut self, triangles: &[Triangle]) {
+        self.triangles.extend(triangles.iter().map(|t| t.get_points_ref().clone()));
+    }
+
+    // возвращает (x_min, y_min, x_max, y_max) или None для пустого чертежа
+    pub(crate) fn get_bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        let contour_points = self.contours.iter().flat_map(|&(ref polygon, _)| polygon.get_points_ref().iter());
+        let polyline_points = self.polylines.iter().flat_map(|polyline| polyline.iter());
+        let triangle_points = self.triangles.iter().flat_map(|t| t.iter());
+
+        let mut res: Option<(f64, f64, f64, f64)> = None;
+        for p in contour_points.chain(polyline_points).chain(triangle_points) {
+            let (x, y) = get_xy(p);
+            res = match res {
+                Some((x_min, y_min, x_max, y_max)) => Some((x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y))),
+                None => Some((x, y, x, y))
+            };
+        }
+        return res;
+    }
+}
+
+pub(crate) fn get_xy(p: &Point) -> (f64, f64) {
+    return (p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64());
+}
+
+// ортонормированный базис (u, v) плоскости, такой что u x v сонаправлен нормали
+fn get_plane_frame(plane: &Plane) -> ([f64; 3], [f64; 3]) {
+    let normal = plane.get_ref_normal();
+    let n = [normal.x.clone().convert_to_f64(), normal.y.clone().convert_to_f64(), normal.z.clone().convert_to_f64()];
+    let n = normalize(&n);
+
+    let u = if normal.x.is_it_zero() && normal.y.is_it_zero() {
+        [1., 0., 0.]
+    } else {
+        // горизонтальное направление z x n
+        normalize(&[-n[1], n[0], 0.])
+    };
+    let v = [n[1] * u[2] - n[2] * u[1], n[2] * u[0] - n[0] * u[2], n[0] * u[1] - n[1] * u[0]];
+    return (u, v);
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    return a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+}
+
+fn normalize(a: &[f64; 3]) -> [f64; 3] {
+    let length = dot(a, a).sqrt();
+    return [a[0] / length, a[1] / length, a[2] / length];
+}
@@ -0,0 +1,143 @@
+use primitives::*;
+use export::drawing::{Drawing, get_xy};
+use std::io::{Result, Write};
+
+// слои и их цвета (номера цветов AutoCAD)
+const LAYERS: [(&'static str, u32); 4] = [("OUTER", 7), ("HOLES", 1), ("OPEN", 3), ("TRIANGLES", 5)];
+
+impl Drawing {
+    /// This method writes the drawing to the `out` in ASCII DXF format of AutoCAD R12 (AC1009).
+    /// Every contour, polyline and triangle is written as a POLYLINE entity with VERTEX entities in the original order.
+    /// Entities are placed on the layers `OUTER`, `HOLES`, `OPEN` and `TRIANGLES`, which are declared in the LAYER table:
+    /// polygons of odd depth in polygon trees are considered as holes.
+    /// # Arguments
+    ///
+    /// * `out` - A type, implementing Write.
+    pub fn write_dxf<T: Write>(&self, out: &mut T) -> Result<()> {
+        write_group(out, 0, "SECTION")?;
+        write_group(out, 2, "HEADER")?;
+        write_group(out, 9, "$ACADVER")?;
+        write_group(out, 1, "AC1009")?;
+        write_group(out, 0, "ENDSEC")?;
+
+        write_group(out, 0, "SECTION")?;
+        write_group(out, 2, "TABLES")?;
+        write_group(out, 0, "TABLE")?;
+        write_group(out, 2, "LTYPE")?;
+        write_group(out, 70, "1")?;
+        write_group(out, 0, "LTYPE")?;
+        write_group(out, 2, "CONTINUOUS")?;
+        write_group(out, 70, "0")?;
+        write_group(out, 3, "Solid line")?;
+        write_group(out, 72, "65")?;
+        write_group(out, 73, "0")?;
+        write_group(out, 40, "0.0")?;
+        write_group(out, 0, "ENDTAB")?;
+        write_group(out, 0, "TABLE")?;
+        write_group(out, 2, "LAYER")?;
+        write_group(out, 70, &LAYERS.len().to_string())?;
+        for &(name, color) in LAYERS.iter() {
+            write_group(out, 0, "LAYER")?;
+            write_group(out, 2, name)?;
+            write_group(out, 70, "0")?;
+            write_group(out, 62, &color.to_string())?;
+            write_group(out, 6, "CONTINUOUS")?;
+        }
+        write_group(out, 0, "ENDTAB")?;
+        write_group(out, 0, "ENDSEC")?;
+
+        write_group(out, 0, "SECTION")?;
+        write_group(out, 2, "ENTITIES")?;
+        for &(ref polygon, depth) in self.contours.iter() {
+            let layer = if depth % 2 == 0 {"OUTER"} else {"HOLES"};
+            write_polyline(out, layer, polygon.get_points_ref(), true)?;
+        }
+        for polyline in self.polylines.iter() {
+            write_polyline(out, "OPEN", polyline, false)?;
+        }
+        for t in self.triangles.iter() {
+            write_polyline(out, "TRIANGLES", t, true)?;
+        }
+        write_group(out, 0, "ENDSEC")?;
+
+        write_group(out, 0, "EOF")?;
+        Ok(())
+    }
+}
+
+fn write_group<T: Write>(out: &mut T, code: u32, value: &str) -> Result<()> {
+    writeln!(out, "{}", code)?;
+    writeln!(out, "{}", value)?;
+    Ok(())
+}
+
+fn write_polyline<T: Write>(out: &mut T, layer: &str, points: &[Point], closed: bool) -> Result<()> {
+    write_group(out, 0, "POLYLINE")?;
+    write_group(out, 8, layer)?;
+    // флаг наличия вершин и нулевая опорная точка обязательны в R12
+    write_group(out, 66, "1")?;
+    write_group(out, 10, "0.0")?;
+    write_group(out, 20, "0.0")?;
+    write_group(out, 30, "0.0")?;
+    write_group(out, 70, if closed {"1"} else {"0"})?;
+    for p in points.iter() {
+        let (x, y) = get_xy(p);
+        write_group(out, 0, "VERTEX")?;
+        write_group(out, 8, layer)?;
+        write_group(out, 10, &x.to_string())?;
+        write_group(out, 20, &y.to_string())?;
+        write_group(out, 30, "0.0")?;
+    }
+    write_group(out, 0, "SEQEND")?;
+    write_group(out, 8, layer)?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use export::*;
+
+    #[test]
+    fn dxf_with_holes() {
+        let mut tree = PolygonTreeNode::new(polygon(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]));
+        let mut hole = PolygonTreeNode::new(polygon(vec![(1., 1.), (1., 3.), (3., 3.), (3., 1.)]));
+        hole.add_child(PolygonTreeNode::new(polygon(vec![(1.5, 1.5), (2.5, 1.5), (2., 2.5)])));
+        tree.add_child(hole);
+
+        let mut drawing = Drawing::new();
+        drawing.add_polygon_tree(&tree);
+        drawing.add_polyline(&[Point::new_from_f64(0., 5., 0.), Point::new_from_f64(1., 6., 0.)]);
+
+        let mut out: Vec<u8> = Vec::new();
+        drawing.write_dxf(&mut out).unwrap();
+        let dxf = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = dxf.lines().collect();
+
+        assert_eq!(lines.len() % 2, 0);
+        assert_eq!(&lines[..8], &["0", "SECTION", "2", "HEADER", "9", "$ACADVER", "1", "AC1009"]);
+        assert_eq!(&lines[lines.len() - 2..], &["0", "EOF"]);
+        for layer in ["OUTER", "HOLES", "OPEN", "TRIANGLES"].iter() {
+            assert_eq!(dxf.matches(&format!("\n0\nLAYER\n2\n{}\n", layer)[..]).count(), 1);
+        }
+        assert_eq!(dxf.matches("\nPOLYLINE\n").count(), 4);
+        assert_eq!(dxf.matches("\nSEQEND\n").count(), 4);
+        assert_eq!(dxf.matches("\nVERTEX\n").count(), 13);
+        assert_eq!(dxf.matches("\nPOLYLINE\n8\nOUTER\n").count(), 2);
+        assert_eq!(dxf.matches("\nPOLYLINE\n8\nHOLES\n").count(), 1);
+        assert_eq!(dxf.matches("\nPOLYLINE\n8\nOPEN\n").count(), 1);
+
+        // порядок точек отверстия сохраняется (по часовой стрелке)
+        let hole_start = dxf.find("\nPOLYLINE\n8\nHOLES\n").unwrap();
+        assert!(dxf[hole_start..].starts_with(concat!(
+            "\nPOLYLINE\n8\nHOLES\n66\n1\n10\n0.0\n20\n0.0\n30\n0.0\n70\n1\n",
+            "0\nVERTEX\n8\nHOLES\n10\n1\n20\n1\n30\n0.0\n",
+            "0\nVERTEX\n8\nHOLES\n10\n1\n20\n3\n30\n0.0\n",
+            "0\nVERTEX\n8\nHOLES\n10\n3\n20\n3\n30\n0.0\n",
+            "0\nVERTEX\n8\nHOLES\n10\n3\n20\n1\n30\n0.0\n",
+            "0\nSEQEND\n8\nHOLES\n"
+        )));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod drawing;
+mod svg;
+mod dxf;
+
+pub use self::drawing::Drawing;
@@ -0,0 +1,140 @@
+use primitives::*;
+use export::drawing::{Drawing, get_xy};
+use std::io::{Result, Write};
+
+// цвета заливки соседних треугольников различаются
+const PALETTE: [&'static str; 6] = ["#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462"];
+
+impl Drawing {
+    /// This method writes the drawing to the `out` in SVG format.
+    /// The y axis is directed upwards. Every polygon tree is written as one path with the even-odd fill rule,
+    /// so holes are not filled. Open polylines are drawn in red.
+    /// # Arguments
+    ///
+    /// * `out` - A type, implementing Write.
+    /// * `fill_triangles` - If it's true, triangles are filled with different colors.
+    /// It's useful to inspect an output of a triangulation.
+    pub fn write_svg<T: Write>(&self, out: &mut T, fill_triangles: bool) -> Result<()> {
+        let (x_min, y_min, x_max, y_max) = self.get_bounds().unwrap_or((0., 0., 1., 1.));
+        let margin = 0.05 * (x_max - x_min).max(y_max - y_min).max(1e-9);
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(out, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+                 x_min - margin, -y_max - margin, x_max - x_min + 2. * margin, y_max - y_min + 2. * margin)?;
+
+        if !self.triangles.is_empty() {
+            writeln!(out, "<g class=\"triangles\" stroke=\"gray\">")?;
+            for (i, t) in self.triangles.iter().enumerate() {
+                let fill = if fill_triangles {PALETTE[i % PALETTE.len()]} else {"none"};
+                writeln!(out, "<polygon points=\"{}\" fill=\"{}\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>",
+                         get_svg_points(t), fill)?;
+            }
+            writeln!(out, "</g>")?;
+        }
+
+        if !self.contours.is_empty() {
+            writeln!(out, "<g class=\"contours\" fill=\"#cccccc\" fill-opacity=\"0.5\" fill-rule=\"evenodd\" stroke=\"black\">")?;
+            let mut path: Vec<String> = Vec::new();
+            for &(ref polygon, depth) in self.contours.iter() {
+                if depth == 0 && !path.is_empty() {
+                    write_path(out, &path)?;
+                    path.clear();
+                }
+                path.push(format!("M {} Z", get_svg_points(polygon.get_points_ref()).replace(" ", " L ")));
+            }
+            write_path(out, &path)?;
+            writeln!(out, "</g>")?;
+        }
+
+        if !self.polylines.is_empty() {
+            writeln!(out, "<g class=\"polylines\" fill=\"none\" stroke=\"red\">")?;
+            for polyline in self.polylines.iter() {
+                writeln!(out, "<polyline points=\"{}\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>",
+                         get_svg_points(polyline))?;
+            }
+            writeln!(out, "</g>")?;
+        }
+
+        writeln!(out, "</svg>")?;
+        Ok(())
+    }
+}
+
+fn write_path<T: Write>(out: &mut T, path: &Vec<String>) -> Result<()> {
+    writeln!(out, "<path d=\"{}\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>", path.join(" "))?;
+    Ok(())
+}
+
+// ось y в SVG направлена вниз
+fn get_svg_points(points: &[Point]) -> String {
+    let coordinates: Vec<String> = points.iter().map(|p| {
+        let (x, y) = get_xy(p);
+        format!("{},{}", x, 0. - y)
+    }).collect();
+    return coordinates.join(" ");
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use export::*;
+
+    #[test]
+    fn svg_with_holes() {
+        let mut tree = PolygonTreeNode::new(polygon(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]));
+        tree.add_child(PolygonTreeNode::new(polygon(vec![(1., 1.), (1., 3.), (3., 3.), (3., 1.)])));
+
+        let mut drawing = Drawing::new();
+        drawing.add_polygon_tree(&tree);
+        drawing.add_polygon(&polygon(vec![(5., 0.), (6., 0.), (6., 1.)]));
+        drawing.add_polyline(&[Point::new_from_f64(0., 5., 0.), Point::new_from_f64(1., 6., 0.)]);
+
+        let mut out: Vec<u8> = Vec::new();
+        drawing.write_svg(&mut out, false).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+
+        assert!(svg.contains("viewBox="));
+        assert_eq!(svg.matches("<path ").count(), 2);
+        assert!(svg.contains("d=\"M 0,0 L 4,0 L 4,-4 L 0,-4 Z M 1,-1 L 1,-3 L 3,-3 L 3,-1 Z\""));
+        assert_eq!(svg.matches("<polyline ").count(), 1);
+        assert!(!svg.contains("<polygon "));
+    }
+
+    #[test]
+    fn vertical_section() {
+        use modeling::{create_box, slice_mesh};
+
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 1., 3.)).unwrap();
+        let plane = Plane::new(Vector::new_from_f64(1., 0., 0.), Point::new_from_f64(1., 0., 0.));
+        let section = slice_mesh(&mesh, &plane).unwrap();
+
+        let mut drawing = Drawing::new();
+        drawing.add_section(&section);
+
+        // сечение плоскостью x = 1 отображается в осях y и z
+        assert_eq!(drawing.get_bounds(), Some((0., 0., 1., 3.)));
+        assert!(drawing.contours[0].0.signed_area().is_it_positive());
+    }
+
+    #[test]
+    fn svg_with_triangles() {
+        let triangles = vec![
+            Triangle::new(vec![Point::new_from_f64(0., 0., 0.), Point::new_from_f64(1., 0., 0.), Point::new_from_f64(0., 1., 0.)]),
+            Triangle::new(vec![Point::new_from_f64(1., 0., 0.), Point::new_from_f64(1., 1., 0.), Point::new_from_f64(0., 1., 0.)])
+        ];
+
+        let mut drawing = Drawing::new();
+        drawing.add_triangles(&triangles);
+
+        let mut out: Vec<u8> = Vec::new();
+        drawing.write_svg(&mut out, true).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert_eq!(svg.matches("<polygon ").count(), 2);
+        assert!(!svg.contains("fill=\"none\""));
+
+        let mut out: Vec<u8> = Vec::new();
+        Drawing::new().write_svg(&mut out, true).unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("</svg>\n"));
+    }
+}
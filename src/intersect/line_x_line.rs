@@ -12,12 +12,14 @@ pub enum InfoLxL {
 
 
 // http://mathhelpplanet.com/static.php?p=vzaimnoe-raspolozhenie-pryamyh-v-prostranstve
-pub fn intersect(a : &Line, b : &Line) -> (Option<Point>, InfoLxL) {
-    let m1 : &Point = &a.org;
-    let l1 : Vector = &a.dest - &a.org;
-    let m2 : &Point = &b.org;
-    let l2 : Vector = &b.dest - &b.org;
-    let mut m : Vector = &b.org - &a.org;
+pub fn intersect<T>(a : &Line<T>, b : &Line<T>) -> (Option<Point<T>>, InfoLxL)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let m1 : &Point<T> = &a.org;
+    let l1 : Vector<T> = &a.dest - &a.org;
+    let m2 : &Point<T> = &b.org;
+    let l2 : Vector<T> = &b.dest - &b.org;
+    let mut m : Vector<T> = &b.org - &a.org;
     if m.is_zero() {
         m = &b.org - &a.dest;
     }
@@ -25,7 +27,7 @@ pub fn intersect(a : &Line, b : &Line) -> (Option<Point>, InfoLxL) {
 
     //Are lines skew?
     let mp = m.mixed_product(&l1, &l2);
-    if mp != Number::zero() {
+    if mp != T::zero() {
         return (None, InfoLxL::Skew);
     }
 
@@ -61,12 +63,12 @@ pub fn intersect(a : &Line, b : &Line) -> (Option<Point>, InfoLxL) {
     //I can improve it!
     let nv = l1.cross_product(&l2);
 
-    let mut a : Matrix<Number> = Matrix::new_from_vector(
+    let a : Matrix<T> = Matrix::new_from_vector(
         vec![Row::new_from_vector(vec![l1.x.clone(), -l2.x.clone(), nv.x.clone()]),
              Row::new_from_vector(vec![l1.y.clone(), -l2.y.clone(), nv.y.clone()]),
              Row::new_from_vector(vec![l1.z.clone(), -l2.z.clone(), nv.z.clone()])]);
 
-    let y : Row<Number> = Row::new_from_vector(vec![&m2.x-&m1.x+&nv.x, &m2.y-&m1.y+&nv.y, &m2.z-&m1.z+&nv.z]);
+    let y : Row<T> = Row::new_from_vector(vec![&m2.x-&m1.x+&nv.x, &m2.y-&m1.y+&nv.y, &m2.z-&m1.z+&nv.z]);
 
 
     //println!("matrix:");
@@ -75,7 +77,7 @@ pub fn intersect(a : &Line, b : &Line) -> (Option<Point>, InfoLxL) {
 
 
 
-    let x = a.solve(y);
+    let x = a.solve(y).expect("Lines are parallel!");
     //println!("{}", x);
     let t = x.get(&0);
 
@@ -85,18 +87,20 @@ pub fn intersect(a : &Line, b : &Line) -> (Option<Point>, InfoLxL) {
 }
 
 // Дублирование во благо производительности
-pub fn intersect_p(a : &Line, b : &Line) -> Option<Number> {
-    let m1 : &Point = &a.org;
-    let l1 : Vector = &a.dest - &a.org;
-    let m2 : &Point = &b.org;
-    let l2 : Vector = &b.dest - &b.org;
-    let mut m : Vector = &b.org - &a.org;
+pub fn intersect_p<T>(a : &Line<T>, b : &Line<T>) -> Option<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let m1 : &Point<T> = &a.org;
+    let l1 : Vector<T> = &a.dest - &a.org;
+    let m2 : &Point<T> = &b.org;
+    let l2 : Vector<T> = &b.dest - &b.org;
+    let mut m : Vector<T> = &b.org - &a.org;
     if m.is_zero() {
         m = &b.org - &a.dest;
     }
 
     let mp = m.mixed_product(&l1, &l2);
-    if mp != Number::zero() {
+    if mp != T::zero() {
         return None;
     }
 
@@ -112,14 +116,14 @@ pub fn intersect_p(a : &Line, b : &Line) -> Option<Number> {
 
     let nv = l1.cross_product(&l2);
 
-    let mut a : Matrix<Number> =Matrix::new_from_vector(
+    let a : Matrix<T> =Matrix::new_from_vector(
         vec![Row::new_from_vector(vec![l1.x.clone(), -l2.x.clone(), nv.x.clone()]),
              Row::new_from_vector(vec![l1.y.clone(), -l2.y.clone(), nv.y.clone()]),
              Row::new_from_vector(vec![l1.z.clone(), -l2.z.clone(), nv.z.clone()])]);
 
-    let y : Row<Number> = Row::new_from_vector(vec![&m2.x-&m1.x+&nv.x, &m2.y-&m1.y+&nv.y, &m2.z-&m1.z+&nv.z]);
+    let y : Row<T> = Row::new_from_vector(vec![&m2.x-&m1.x+&nv.x, &m2.y-&m1.y+&nv.y, &m2.z-&m1.z+&nv.z]);
 
-    let x = a.solve(y);
+    let x = a.solve(y).expect("Lines are parallel!");
     //println!("{}", x);
     let t = x.get(&0);
 
@@ -8,7 +8,9 @@ pub enum InfoLxP {
 }
 
 #[allow(dead_code)]
-pub fn intersect(l : &Line, p : &Plane) -> (Option<Point>, InfoLxP) {
+pub fn intersect<T>(l : &Line<T>, p : &Plane<T>) -> (Option<Point<T>>, InfoLxP)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     let dir_v = l.get_dir_vector();
     let dp = dir_v.dot_product(&p.normal);
 
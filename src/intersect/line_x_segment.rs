@@ -13,8 +13,10 @@ pub enum InfoLxS {
 }
 
 
-pub fn intersect(line : &Line, segment : &Segment) -> (Option<Point>, Option<Segment>, InfoLxS) {
-    let line_of_segment : Line = segment.gen_line();
+pub fn intersect<T>(line : &Line<T>, segment : &Segment<T>) -> (Option<Point<T>>, Option<Segment<T>>, InfoLxS)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let line_of_segment : Line<T> = segment.gen_line();
 
     let (sp, info) = line_x_line::intersect(line, &line_of_segment);
     match info {
@@ -4,24 +4,28 @@ use intersect::tuple_iter::{/*TupleIter,*/ enumerate_simple, TreeAABT};
 // use log::LogLevel;
 use time::PreciseTime;
 
-pub struct IntersectionResult {
-    pub res_mxm_list : Vec<(usize, usize, triangle_x_triangle::ResTxT)>
+pub struct IntersectionResult<T = Number> {
+    pub res_mxm_list : Vec<(usize, usize, triangle_x_triangle::ResTxT<T>)>
 }
 
-impl IntersectionResult {
-    pub fn new(res_mxm_list : Vec<(usize, usize, triangle_x_triangle::ResTxT)>) -> IntersectionResult {
+impl<T> IntersectionResult<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(res_mxm_list : Vec<(usize, usize, triangle_x_triangle::ResTxT<T>)>) -> IntersectionResult<T> {
         IntersectionResult {
             res_mxm_list : res_mxm_list
         }
     }
 
-    pub fn get_res_list(self) -> Vec<(usize, usize, triangle_x_triangle::ResTxT)> {
+    pub fn get_res_list(self) -> Vec<(usize, usize, triangle_x_triangle::ResTxT<T>)> {
         self.res_mxm_list
     }
 }
 
 
-pub fn intersect(a : &Mesh, b : &Mesh, use_tree: usize) -> IntersectionResult {
+pub fn intersect<T>(a : &Mesh<T>, b : &Mesh<T>, use_tree: usize) -> IntersectionResult<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     info!("<mesh_x_mesh::intersect> was started!");
     let start = PreciseTime::now();
 
@@ -37,7 +41,7 @@ pub fn intersect(a : &Mesh, b : &Mesh, use_tree: usize) -> IntersectionResult {
     debug!("Number of pairs: {0}", triangles_enum.v.len());
 
 
-    let mut res_mxm_list : Vec<(usize, usize, triangle_x_triangle::ResTxT)> = Vec::new();
+    let mut res_mxm_list : Vec<(usize, usize, triangle_x_triangle::ResTxT<T>)> = Vec::new();
 
 
     info!("The triangles are intersecting ...");
@@ -9,7 +9,9 @@ pub enum InfoPxP {
     Intersecting
 }
 
-pub fn intersect(plane1 : &Plane, plane2 : &Plane) -> (Option<Line>, InfoPxP) {
+pub fn intersect<T>(plane1 : &Plane<T>, plane2 : &Plane<T>) -> (Option<Line<T>>, InfoPxP)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
 
     // (p - p0)*n = p*n + d => d = -p0*n
     let a = plane1.normal.cross_product(&plane2.normal);
@@ -54,17 +56,17 @@ pub fn intersect(plane1 : &Plane, plane2 : &Plane) -> (Option<Line>, InfoPxP) {
         _  =>  vec![-d1, -d2, plane1.point.x.clone()]
     };
 
-    let b : Row<Number> = Row::new_from_vector(bv);
+    let b : Row<T> = Row::new_from_vector(bv);
 
-    let last_row : Row<Number> = match 1 {
+    let last_row : Row<T> = match 1 {
         _ if !a.z.is_it_zero() =>
-            Row::new_from_vector(vec![Number::new(0.), Number::new(0.), Number::new(1.)]),
+            Row::new_from_vector(vec![T::new(0.), T::new(0.), T::new(1.)]),
 
         _ if !a.y.is_it_zero() =>
-            Row::new_from_vector(vec![Number::new(0.), Number::new(1.), Number::new(0.)]),
+            Row::new_from_vector(vec![T::new(0.), T::new(1.), T::new(0.)]),
 
         _  =>
-            Row::new_from_vector(vec![Number::new(1.), Number::new(0.), Number::new(0.)])
+            Row::new_from_vector(vec![T::new(1.), T::new(0.), T::new(0.)])
     };
 
     let mv = vec![
@@ -73,8 +75,8 @@ pub fn intersect(plane1 : &Plane, plane2 : &Plane) -> (Option<Line>, InfoPxP) {
         last_row
     ];
 
-    let mut m : Matrix<Number> = Matrix::new_from_vector(mv);
-    let u = m.solve(b);
+    let m : Matrix<T> = Matrix::new_from_vector(mv);
+    let u = m.solve(b).expect("Normals of planes are collinear!");
     let mut u_vec = u.convert_to_vec();
     let (u0, u1, u2) = (u_vec.remove(0), u_vec.remove(0), u_vec.remove(0));
 
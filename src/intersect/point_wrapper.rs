@@ -1,19 +1,29 @@
 use primitives::*;
 use std::cmp::Ordering;
+use std::fmt;
 
-#[derive(Debug)]
 #[derive(Clone)]
-pub struct PointWrapper {
-    point : Point,
-    t : Number
+pub struct PointWrapper<T = Number> {
+    point : Point<T>,
+    t : T
 }
 
-impl PointWrapper {
-    pub fn new(p : Point, s : &Segment) -> PointWrapper {
+impl<T> fmt::Debug for PointWrapper<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PointWrapper {{ point: {:?}, t: {:?} }}", self.point, self.t)
+    }
+}
+
+impl<T> PointWrapper<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(p : Point<T>, s : &Segment<T>) -> PointWrapper<T> {
         // p должна лежать внутри s, иначе сортировка не будет иметь смысла
         // сортируем по степени удаленности от начала отрезка
 
-        let t : Number;
+        let t : T;
         if s.dest.x != s.org.x {
             t = (&p.x - &s.org.x) / (&s.dest.x - &s.org.x);
         } else if s.dest.y != s.org.y {
@@ -30,27 +40,35 @@ impl PointWrapper {
         }
     }
 
-    pub fn extract_point(self) -> Point {
+    pub fn extract_point(self) -> Point<T> {
         self.point
     }
 }
 
-impl PartialEq for PointWrapper {
-    fn eq(&self, other: &PointWrapper) -> bool {
+impl<T> PartialEq for PointWrapper<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn eq(&self, other: &PointWrapper<T>) -> bool {
         self.point == other.point
     }
 }
 
-impl Eq for PointWrapper {}
+impl<T> Eq for PointWrapper<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{}
 
-impl Ord for PointWrapper {
-    fn cmp(&self, other: &PointWrapper) -> Ordering {
+impl<T> Ord for PointWrapper<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn cmp(&self, other: &PointWrapper<T>) -> Ordering {
         self.t.cmp(&other.t)
     }
 }
 
-impl PartialOrd for PointWrapper {
-    fn partial_cmp(&self, other: &PointWrapper) -> Option<Ordering> {
+impl<T> PartialOrd for PointWrapper<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn partial_cmp(&self, other: &PointWrapper<T>) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
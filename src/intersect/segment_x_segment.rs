@@ -16,7 +16,9 @@ pub enum InfoSxS {
 }
 
 // http://mathhelpplanet.com/static.php?p=vzaimnoe-raspolozhenie-pryamyh-v-prostranstve
-pub fn intersect(a : &Segment, b : &Segment) -> (Option<Point>, Option<Segment>, InfoSxS) {
+pub fn intersect<T>(a : &Segment<T>, b : &Segment<T>) -> (Option<Point<T>>, Option<Segment<T>>, InfoSxS)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     let la = if a.org >= a.dest  {
         Line {org: a.dest.clone(), dest: a.org.clone()}
     } else {
@@ -52,7 +54,9 @@ pub fn intersect(a : &Segment, b : &Segment) -> (Option<Point>, Option<Segment>,
     }
 }
 
-fn directed_segment(p1 : Point, p2 : Point, s : &Segment) -> Segment {
+fn directed_segment<T>(p1 : Point<T>, p2 : Point<T>, s : &Segment<T>) -> Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     let pw1 = PointWrapper::new(p1, s);
     let pw2 = PointWrapper::new(p2, s);
     if pw1 < pw2 {
@@ -63,7 +67,9 @@ fn directed_segment(p1 : Point, p2 : Point, s : &Segment) -> Segment {
 }
 
 // Intersect segments lying on the same line
-pub fn intersect_segments_on_the_line(arg_sa : &Segment, arg_sb : &Segment) -> (Option<Point>, Option<Segment>, InfoSxS) {
+pub fn intersect_segments_on_the_line<T>(arg_sa : &Segment<T>, arg_sb : &Segment<T>) -> (Option<Point<T>>, Option<Segment<T>>, InfoSxS)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     /*
     IMPORTANT: The result segment has the same direction as <arg_sb>
     */
@@ -1,8 +1,10 @@
 use primitives::*;
-use intersect::{plane_x_plane, line_x_segment, segment_x_segment};
+use primitives::predicates::orient3d;
+use intersect::segment_x_segment;
 use intersect::point_wrapper::PointWrapper;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::cmp::Ordering;
 
 #[derive(Clone)]
 #[derive(Debug)]
@@ -24,15 +26,17 @@ impl InfoTxT {
 }
 
 #[derive(Clone)]
-pub struct ResTxT {
-    point: Option<Point>,
-    segment: Option<Segment>,
-    polygon: Option<Polygon>,
+pub struct ResTxT<T = Number> {
+    point: Option<Point<T>>,
+    segment: Option<Segment<T>>,
+    polygon: Option<Polygon<T>>,
     info: InfoTxT
 }
 
-impl ResTxT {
-    pub fn new(point: Option<Point>, segment: Option<Segment>, polygon: Option<Polygon>, info: InfoTxT) -> ResTxT {
+impl<T> ResTxT<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(point: Option<Point<T>>, segment: Option<Segment<T>>, polygon: Option<Polygon<T>>, info: InfoTxT) -> ResTxT<T> {
         ResTxT {
             point: point,
             segment: segment,
@@ -42,20 +46,20 @@ impl ResTxT {
     }
 
     #[allow(dead_code)]
-    pub fn get_tuple(self) -> (Option<Point>, Option<Segment>, Option<Polygon>, InfoTxT) {
+    pub fn get_tuple(self) -> (Option<Point<T>>, Option<Segment<T>>, Option<Polygon<T>>, InfoTxT) {
         return (self.point, self.segment, self.polygon, self.info)
     }
 
     #[allow(dead_code)]
-    pub fn get_point(self) -> Point {
+    pub fn get_point(self) -> Point<T> {
         return self.point.unwrap();
     }
 
-    pub fn get_segment(self) -> Segment {
+    pub fn get_segment(self) -> Segment<T> {
         return self.segment.unwrap();
     }
 
-    pub fn get_polygon(self) -> Polygon {
+    pub fn get_polygon(self) -> Polygon<T> {
         return self.polygon.unwrap();
     }
 
@@ -65,18 +69,18 @@ impl ResTxT {
 }
 
 
-pub fn intersect(tr1 : &Triangle, tr2 : &Triangle) -> ResTxT {
+pub fn intersect<T>(tr1 : &Triangle<T>, tr2 : &Triangle<T>) -> ResTxT<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     let plane2 = tr2.gen_plane();
 
     if plane2.normal.is_zero() {
         panic!("plane normal is a zero vector!")
     }
 
-    let dist1 = signed_distance(tr1.get_ref(0), &plane2);
-    let dist2 = signed_distance(tr1.get_ref(1), &plane2);
-    let dist3 = signed_distance(tr1.get_ref(2), &plane2);
-
-    if dist1.is_it_zero() & dist2.is_it_zero() & dist3.is_it_zero() {
+    // положение вершин относительно плоскости другого треугольника определяется точным предикатом
+    let signs1 = get_signs(tr1, tr2);
+    if signs1.iter().all(|s| *s == Ordering::Equal) {
         let polygon = intersect_triangles_in_the_plane(tr1, tr2);
         if polygon.get_points_ref().len() == 0 {
             return ResTxT::new(None, None, None, InfoTxT::CoplanarNotIntersecting);
@@ -92,31 +96,29 @@ pub fn intersect(tr1 : &Triangle, tr2 : &Triangle) -> ResTxT {
         }
     }
 
+    let dist1 = signed_distance(tr1.get_ref(0), &plane2);
+    let dist2 = signed_distance(tr1.get_ref(1), &plane2);
+    let dist3 = signed_distance(tr1.get_ref(2), &plane2);
+
     if (dist1 == dist2) & (dist1 == dist3) {
         return ResTxT::new(None, None, None, InfoTxT::Collinear);
     }
 
-    if dist1.is_it_positive() & dist2.is_it_positive() & dist3.is_it_positive() |
-        dist1.is_it_negative() & dist2.is_it_negative() & dist3.is_it_negative() {
+    if is_on_one_side(&signs1) {
         return ResTxT::new(None, None, None, InfoTxT::NotIntersecting);
     }
 
     let plane1 = tr1.gen_plane();
 
-    let dist1 = signed_distance(tr2.get_ref(0), &plane1);
-    let dist2 = signed_distance(tr2.get_ref(1), &plane1);
-    let dist3 = signed_distance(tr2.get_ref(2), &plane1);
-
-    if dist1.is_it_positive() & dist2.is_it_positive() & dist3.is_it_positive() |
-        dist1.is_it_negative() & dist2.is_it_negative() & dist3.is_it_negative() {
+    let signs2 = get_signs(tr2, tr1);
+    if is_on_one_side(&signs2) {
         return ResTxT::new(None, None, None, InfoTxT::NotIntersecting);
     }
 
-    let (op_line, _) = plane_x_plane::intersect(&plane1, &plane2);
-    let line = op_line.unwrap();
-
-    let (op1, os1) = intersect_line_and_triangle(&line, &tr1);
-    let (op2, os2) = intersect_line_and_triangle(&line, &tr2);
+    // обе части лежат на прямой пересечения плоскостей и упорядочиваются вдоль ее направления
+    let dir = plane1.normal.cross_product(&plane2.normal);
+    let (op1, os1) = intersect_triangle_and_plane(tr1, &signs1, &plane2, &dir);
+    let (op2, os2) = intersect_triangle_and_plane(tr2, &signs2, &plane1, &dir);
 
 
     match (op1, os1, op2, os2) {
@@ -153,67 +155,82 @@ pub fn intersect(tr1 : &Triangle, tr2 : &Triangle) -> ResTxT {
                 return ResTxT::new(None, None, None, InfoTxT::NotIntersecting);
             }
         }
-        _ => panic!("Unexpected case!")
+        _ => return ResTxT::new(None, None, None, InfoTxT::NotIntersecting)
     }
 
 }
 
 
-fn signed_distance(point : &Point, plane : &Plane) -> Number {
+fn signed_distance<T>(point : &Point<T>, plane : &Plane<T>) -> T
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     plane.normal.dot_product(&point.get_vector()) + plane.get_ref_d()
 }
 
-pub fn intersect_line_and_triangle(line : &Line, tr : &Triangle) -> (Option<Point>, Option<Segment>) {
-    let ss : Vec<Segment> = tr.get_sides();
-    let segment_of_line = line.gen_segment();
+fn get_signs<T>(tr : &Triangle<T>, other : &Triangle<T>) -> Vec<Ordering>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    return tr.get_points_ref().iter()
+        .map(|p| orient3d(other.get_ref(0), other.get_ref(1), other.get_ref(2), p))
+        .collect();
+}
 
-    let mut set : BTreeSet<PointWrapper> = BTreeSet::new();
+fn is_on_one_side(signs : &Vec<Ordering>) -> bool {
+    return signs.iter().all(|s| *s == Ordering::Greater) || signs.iter().all(|s| *s == Ordering::Less);
+}
 
-    for s in ss {
-        let res = line_x_segment::intersect(line, &s);
-        match res {
-            (Some(point), None, line_x_segment::InfoLxS::IntersectingInAPoint) => {
-                set.insert(PointWrapper::new(point, &segment_of_line));
-            },
-            (None, Some(segment), line_x_segment::InfoLxS::IntersectingInASegment) => {
-                let Segment {org, dest} = segment;
-                set.insert(PointWrapper::new(org, &segment_of_line));
-                set.insert(PointWrapper::new(dest, &segment_of_line));
-            }
-            _ => {}
+fn intersect_triangle_and_plane<T>(tr : &Triangle<T>, signs : &Vec<Ordering>, plane : &Plane<T>, dir : &Vector<T>) -> (Option<Point<T>>, Option<Segment<T>>)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let mut points : Vec<Point<T>> = Vec::new();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        if signs[i] == Ordering::Equal {
+            points.push(tr.get(i));
+        } else if signs[j] != Ordering::Equal && signs[i] != signs[j] {
+            // концы ребра упорядочиваются, чтобы у соседних треугольников точка на общем ребре совпадала
+            let (p, q) = if tr.get_ref(i) < tr.get_ref(j) {(tr.get_ref(i), tr.get_ref(j))} else {(tr.get_ref(j), tr.get_ref(i))};
+            let (dp, dq) = (signed_distance(p, plane), signed_distance(q, plane));
+            let denom = &dp - &dq;
+            let t = if denom.is_it_zero() {T::new(0.5)} else {dp / denom};
+            let t = match 1 {
+                _ if t.is_it_negative() => T::new(0.),
+                _ if t > T::new(1.) => T::new(1.),
+                _ => t
+            };
+            points.push(p + &((q - p) * t));
         }
     }
-    if set.len() == 0{
+
+    let mut wrapped : Vec<(T, Point<T>)> = points.into_iter().map(|p| (dir.dot_product(&p.get_vector()), p)).collect();
+    wrapped.sort_by(|a, b| a.0.cmp(&b.0));
+    wrapped.dedup_by(|a, b| a.0 == b.0);
+
+    if wrapped.len() == 0 {
         return (None, None);
-    } else if set.len() == 1 {
-        let mut v : Vec<Point> = Vec::new();
-        for pw in set.into_iter() {
-            v.push(pw.extract_point())
-        }
-        return (Some(v.remove(0)), None);
+    } else if wrapped.len() == 1 {
+        return (Some(wrapped.remove(0).1), None);
     } else {
-        let mut v : Vec<Point> = Vec::new();
-        for pw in set.into_iter() {
-            let p = pw.extract_point();
-            v.push(p);
-        }
-        return (None, Some(Segment::new(v.remove(0), v.pop().unwrap())));
+        let dest = wrapped.pop().unwrap().1;
+        return (None, Some(Segment::new(wrapped.remove(0).1, dest)));
     }
 }
 
-struct PointDirGraph {
-    points : Vec<Point>,
+struct PointDirGraph<T = Number> {
+    points : Vec<Point<T>>,
     edges : Vec<BTreeSet<usize>>
 }
 
 
-impl PointDirGraph {
-    fn init_sets(ss : &Vec<Segment>) -> Vec<BTreeSet<PointWrapper>> {
+impl<T> PointDirGraph<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn init_sets(ss : &Vec<Segment<T>>) -> Vec<BTreeSet<PointWrapper<T>>> {
         // сортируем точки в порядке отдаления от org
 
-        let mut sets : Vec<BTreeSet<PointWrapper>> = Vec::new();
+        let mut sets : Vec<BTreeSet<PointWrapper<T>>> = Vec::new();
         for s in ss.iter() {
-            let mut set : BTreeSet<PointWrapper> = BTreeSet::new();
+            let mut set : BTreeSet<PointWrapper<T>> = BTreeSet::new();
             set.insert(PointWrapper::new(s.org.clone(), s));
             set.insert(PointWrapper::new(s.dest.clone(), s));
             sets.push(set);
@@ -221,9 +238,9 @@ impl PointDirGraph {
         return sets;
     }
 
-    fn add_edges_from_sets(&mut self, sets : Vec<BTreeSet<PointWrapper>>) {
+    fn add_edges_from_sets(&mut self, sets : Vec<BTreeSet<PointWrapper<T>>>) {
         for set in sets {
-            let mut previous_pw : Option<PointWrapper> = None;
+            let mut previous_pw : Option<PointWrapper<T>> = None;
             for pw in set {
 
                 if previous_pw.is_none() {
@@ -252,8 +269,8 @@ impl PointDirGraph {
         }
     }
 
-    pub fn new(tr1 : &Triangle, tr2 : &Triangle) -> PointDirGraph {
-        let points : Vec<Point> = Vec::new();
+    pub fn new(tr1 : &Triangle<T>, tr2 : &Triangle<T>) -> PointDirGraph<T> {
+        let points : Vec<Point<T>> = Vec::new();
         let edges : Vec<BTreeSet<usize>> = Vec::new();
 
         let mut pd_graph = PointDirGraph {
@@ -264,8 +281,8 @@ impl PointDirGraph {
         // если у треугольников разная ориентация, то сохраняется ориентация первого треугольника
         let dp = tr1.get_normal().dot_product(&tr2.get_normal());
 
-        let ss1 : Vec<Segment> = tr1.get_sides();;
-        let ss2 : Vec<Segment> = if dp.is_it_positive() {
+        let ss1 : Vec<Segment<T>> = tr1.get_sides();;
+        let ss2 : Vec<Segment<T>> = if dp.is_it_positive() {
             tr2.get_sides()
         } else {
             let new_tr2 = Triangle::new(vec![tr2.get(1), tr2.get(0), tr2.get(2)]);
@@ -303,7 +320,7 @@ impl PointDirGraph {
         return pd_graph;
     }
 
-    pub fn add_edge(&mut self, org : Point, dest : Point) {
+    pub fn add_edge(&mut self, org : Point<T>, dest : Point<T>) {
         // все ребра нужно задавать против часовой стрелки, так как граф ориентированный
 
         let mut org_index : Option<usize> = None;
@@ -335,21 +352,24 @@ impl PointDirGraph {
     }
 }
 
-fn update_map(point_to_verdict : &mut HashMap<Point, bool>, tr1 : &Triangle, tr2 : &Triangle, p : &Point) {
+fn update_map<T>(point_to_verdict : &mut HashMap<Point<T>, bool>, p : &Point<T>)
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    // вершин треугольников в таблице нет только у точек пересечения сторон,
+    // а они лежат на границах обоих треугольников
     if !point_to_verdict.contains_key(&p) {
-        point_to_verdict.insert(
-            p.clone(),
-            tr1.does_triangle_contain_point(&p, false) && tr2.does_triangle_contain_point(&p, false)
-        );
+        point_to_verdict.insert(p.clone(), true);
     }
 }
 
-pub fn intersect_triangles_in_the_plane(tr1: &Triangle, tr2: &Triangle) -> Polygon {
+pub fn intersect_triangles_in_the_plane<T>(tr1: &Triangle<T>, tr2: &Triangle<T>) -> Polygon<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     if tr1.degradation_level() != 0 || tr2.degradation_level() !=0 {
         panic!("Degradation detected:\n tr1 = {:?}\n tr2 = {:?}", tr1, tr2);
     }
 
-    let mut polygon : Polygon = Polygon::new(Vec::new(), tr1.get_normal());
+    let mut polygon : Polygon<T> = Polygon::new(Vec::new(), tr1.get_normal());
 
 
     let t1_p0_in_t2 = tr2.does_triangle_contain_point(tr1.get_ref(0), false);
@@ -375,7 +395,7 @@ pub fn intersect_triangles_in_the_plane(tr1: &Triangle, tr2: &Triangle) -> Polyg
         return polygon;
     }
 
-    let mut point_to_verdict: HashMap<Point, bool> = HashMap::new();
+    let mut point_to_verdict: HashMap<Point<T>, bool> = HashMap::new();
     point_to_verdict.insert(tr1.get(0), t1_p0_in_t2);
     point_to_verdict.insert(tr1.get(1), t1_p1_in_t2);
     point_to_verdict.insert(tr1.get(2), t1_p2_in_t2);
@@ -392,10 +412,10 @@ pub fn intersect_triangles_in_the_plane(tr1: &Triangle, tr2: &Triangle) -> Polyg
     //pd_graph.print_graph();
 
     // на каждом шаге выбираем точку лежащую внутри обоих треугольников
-    let mut get_next = || -> Option<Point> {
+    let mut get_next = || -> Option<Point<T>> {
         if index_of_prev.is_none() {
             for (i, p) in pd_graph.points.iter().enumerate() {
-                update_map(&mut point_to_verdict, tr1, tr2, p);
+                update_map(&mut point_to_verdict, p);
                 if point_to_verdict[p] {
                     index_of_first = Some(i);
                     index_of_prev = Some(i);
@@ -407,8 +427,8 @@ pub fn intersect_triangles_in_the_plane(tr1: &Triangle, tr2: &Triangle) -> Polyg
             return None;
         } else {
             for index_of_suc in pd_graph.edges[index_of_prev.unwrap()].iter() {
-                let p : &Point = pd_graph.points.get(*index_of_suc).unwrap();
-                update_map(&mut point_to_verdict, tr1, tr2, p);
+                let p : &Point<T> = pd_graph.points.get(*index_of_suc).unwrap();
+                update_map(&mut point_to_verdict, p);
                 if point_to_verdict[p] {
                     index_of_prev = Some(*index_of_suc);
                     if index_of_first == index_of_prev {
@@ -426,7 +446,7 @@ pub fn intersect_triangles_in_the_plane(tr1: &Triangle, tr2: &Triangle) -> Polyg
     };
 
     loop {
-        let op : Option<Point> = get_next();
+        let op : Option<Point<T>> = get_next();
         if op.is_some() {
             polygon.add_point(op.unwrap());
         } else {
@@ -444,6 +464,30 @@ mod tests {
     use intersect::*;
     use intersect::triangle_x_triangle::PointDirGraph;
 
+    fn intersect_crossing_triangles<T>() -> Segment<T>
+        where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+    {
+        let tr1 = Triangle::new(vec![Point::from_f64(0., 0., 0.), Point::from_f64(2., 0., 0.), Point::from_f64(0., 2., 0.)]);
+        let tr2 = Triangle::new(vec![Point::from_f64(0.5, 0.5, -1.), Point::from_f64(0.5, 0.5, 1.), Point::from_f64(3., 0.5, 0.)]);
+        let res = triangle_x_triangle::intersect(&tr1, &tr2);
+        assert_eq!(res.get_info(), triangle_x_triangle::InfoTxT::Intersecting);
+        return res.get_segment();
+    }
+
+    #[test]
+    fn intersect_with_other_number_types() {
+        let s = intersect_crossing_triangles::<Number>();
+        let s_f64 = intersect_crossing_triangles::<FloatNumber>();
+        let s_br = intersect_crossing_triangles::<BigRationalNumber>();
+
+        let expected = vec![Point::new_from_f64(0.5, 0.5, 0.), Point::new_from_f64(1.5, 0.5, 0.)];
+        for ps in vec![vec![s.org, s.dest], vec![s_f64.org.convert(), s_f64.dest.convert()], vec![s_br.org.convert(), s_br.dest.convert()]] {
+            let mut ps = ps;
+            ps.sort();
+            assert_eq!(ps, expected);
+        }
+    }
+
     #[test]
     fn points_graph_test1() {
         // треугольники должны лежать в одной плоскости
@@ -509,6 +553,16 @@ mod tests {
         assert!(polygon.get_points_ref().len() == 3);
     }
 
+    #[test]
+    fn intersect_triangles_in_the_plane_with_f64() {
+        // точки пересечения сторон, вычисленные в f64, считаются лежащими в обоих треугольниках
+        let tr1 : Triangle<FloatNumber> = Triangle::new(vec![Point::from_f64(0., 0., 0.), Point::from_f64(3., 0., 0.), Point::from_f64(0., 3., 0.)]);
+        let tr2 : Triangle<FloatNumber> = Triangle::new(vec![Point::from_f64(0., -1., 0.), Point::from_f64(1., -1., 0.), Point::from_f64(1., 3., 0.)]);
+
+        let polygon = triangle_x_triangle::intersect_triangles_in_the_plane(&tr1, &tr2);
+        assert_eq!(polygon.get_points_ref().len(), 4);
+    }
+
     #[test]
     fn intersect_triangles_in_the_plane2() {
         // треугольники должны лежать в одной плоскости
@@ -3,6 +3,8 @@ use std::slice::Iter;
 use std::collections::HashMap;
 use std::cmp::{max, min};
 use std::collections::BTreeSet;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
 use std::fmt;
 
 #[allow(dead_code)]
@@ -23,7 +25,9 @@ impl TupleIter {
 }
 
 
-pub fn enumerate_simple(a : &Mesh, b : &Mesh) -> TupleIter {
+pub fn enumerate_simple<T>(a : &Mesh<T>, b : &Mesh<T>) -> TupleIter
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     let mut pairs : Vec<(usize, usize)> = Vec::new();
 
     for index_a in 0..a.num_of_triangles() {
@@ -39,16 +43,18 @@ pub fn enumerate_simple(a : &Mesh, b : &Mesh) -> TupleIter {
 // оболочка
 #[derive(Clone)]
 #[derive(Debug)]
-struct AAB {
-    x_min : Number,
-    x_max : Number,
-    y_min : Number,
-    y_max : Number,
-    z_min : Number,
-    z_max : Number
+pub(crate) struct AAB<T = Number> {
+    x_min : T,
+    x_max : T,
+    y_min : T,
+    y_max : T,
+    z_min : T,
+    z_max : T
 }
 
-impl fmt::Display for AAB {
+impl<T> fmt::Display for AAB<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "x_min: {}, x_max: {}, y_min: {}, y_max: {}, z_min: {}, z_max: {}",
                self.x_min.clone().convert_to_f32(),
@@ -60,15 +66,17 @@ impl fmt::Display for AAB {
     }
 }
 
-impl AAB {
+impl<T> AAB<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     pub fn new(
-        x_min : Number,
-        x_max : Number,
-        y_min : Number,
-        y_max : Number,
-        z_min : Number,
-        z_max : Number
-    ) -> AAB {
+        x_min : T,
+        x_max : T,
+        y_min : T,
+        y_max : T,
+        z_min : T,
+        z_max : T
+    ) -> AAB<T> {
         AAB {
             x_min,
             x_max,
@@ -79,7 +87,7 @@ impl AAB {
         }
     }
 
-    pub fn union_boxes(box1: &AAB, box2: &AAB) -> AAB {
+    pub fn union_boxes(box1: &AAB<T>, box2: &AAB<T>) -> AAB<T> {
         AAB {
             x_min: min(box1.x_min.clone(), box2.x_min.clone()),
             x_max: max(box1.x_max.clone(), box2.x_max.clone()),
@@ -90,27 +98,106 @@ impl AAB {
         }
     }
 
-    fn overlay(min1: &Number, max1: &Number, min2: &Number, max2: &Number) -> bool {
+    fn overlay(min1: &T, max1: &T, min2: &T, max2: &T) -> bool {
         return (min1 >= min2) && (min1 <= max2) || (max1 >= min2) && (max1 <= max2) ||
             (min2 >= min1) && (min2 <= max1) || (max2 >= min1) && (max2 <= max1);
     }
 
-    pub fn do_boxes_intersect(box1: &AAB, box2: &AAB) -> bool {
+    pub fn do_boxes_intersect(box1: &AAB<T>, box2: &AAB<T>) -> bool {
         return AAB::overlay(&box1.x_min, &box1.x_max, &box2.x_min, &box2.x_max) &&
                AAB::overlay(&box1.y_min, &box1.y_max, &box2.y_min, &box2.y_max) &&
                AAB::overlay(&box1.z_min, &box1.z_max, &box2.z_min, &box2.z_max);
     }
 
-    pub fn wrap_triangle(t : Triangle) -> AAB {
+    pub fn does_box_contain_point(&self, p: &Point<T>) -> bool {
+        return (p.x >= self.x_min) && (p.x <= self.x_max) &&
+               (p.y >= self.y_min) && (p.y <= self.y_max) &&
+               (p.z >= self.z_min) && (p.z <= self.z_max);
+    }
+
+    // луч org + t*dir, t >= 0; пересечение ищется методом плит
+    pub fn does_ray_intersect_box(&self, org: &Point<T>, dir: &Vector<T>) -> bool {
+        let mut t_min : T = T::zero();
+        let mut o_t_max : Option<T> = None;
+
+        let slabs = [
+            (&org.x, &dir.x, &self.x_min, &self.x_max),
+            (&org.y, &dir.y, &self.y_min, &self.y_max),
+            (&org.z, &dir.z, &self.z_min, &self.z_max)
+        ];
+
+        for &(o, d, min_value, max_value) in slabs.iter() {
+            if d.is_it_zero() {
+                if o < min_value || o > max_value {
+                    return false;
+                }
+                continue;
+            }
+
+            let t1 = (min_value - o) / d;
+            let t2 = (max_value - o) / d;
+            let (t_near, t_far) = if t1 < t2 {(t1, t2)} else {(t2, t1)};
+
+            if t_near > t_min {
+                t_min = t_near;
+            }
+
+            if o_t_max.is_none() || t_far < o_t_max.clone().unwrap() {
+                o_t_max = Some(t_far);
+            }
+
+            if t_min > o_t_max.clone().unwrap() {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    // квадрат расстояния от точки до бокса (ноль, если точка внутри)
+    pub fn get_squared_distance_to_point(&self, p: &Point<T>) -> T {
+        let mut res = T::zero();
+        let axes = [
+            (&p.x, &self.x_min, &self.x_max),
+            (&p.y, &self.y_min, &self.y_max),
+            (&p.z, &self.z_min, &self.z_max)
+        ];
+
+        for &(value, min_value, max_value) in axes.iter() {
+            if value < min_value {
+                let d = min_value - value;
+                res = res + &d * &d;
+            } else if value > max_value {
+                let d = value - max_value;
+                res = res + &d * &d;
+            }
+        }
+
+        return res;
+    }
+
+    // значения n*p + d в вершинах коробки лежат между значениями в двух противоположных вершинах
+    pub fn does_plane_intersect_box(&self, plane: &Plane<T>) -> bool {
+        let n = &plane.normal;
+        let (x_lo, x_hi) = if n.x.is_it_negative() {(&self.x_max, &self.x_min)} else {(&self.x_min, &self.x_max)};
+        let (y_lo, y_hi) = if n.y.is_it_negative() {(&self.y_max, &self.y_min)} else {(&self.y_min, &self.y_max)};
+        let (z_lo, z_hi) = if n.z.is_it_negative() {(&self.z_max, &self.z_min)} else {(&self.z_min, &self.z_max)};
+
+        let min_value = &(&(&(&n.x * x_lo) + &(&n.y * y_lo)) + &(&n.z * z_lo)) + &plane.d;
+        let max_value = &(&(&(&n.x * x_hi) + &(&n.y * y_hi)) + &(&n.z * z_hi)) + &plane.d;
+        return !min_value.is_it_positive() && !max_value.is_it_negative();
+    }
+
+    pub fn wrap_triangle(t : Triangle<T>) -> AAB<T> {
         let mut ps = t.get_points();
         let first_p = ps.pop().unwrap();
 
-        let mut x_min : Number = first_p.x.clone();
-        let mut x_max : Number = first_p.x.clone();
-        let mut y_min : Number = first_p.y.clone();
-        let mut y_max : Number = first_p.y.clone();
-        let mut z_min : Number = first_p.z.clone();
-        let mut z_max : Number = first_p.z.clone();
+        let mut x_min : T = first_p.x.clone();
+        let mut x_max : T = first_p.x.clone();
+        let mut y_min : T = first_p.y.clone();
+        let mut y_max : T = first_p.y.clone();
+        let mut z_min : T = first_p.z.clone();
+        let mut z_max : T = first_p.z.clone();
         for t in ps {
             if t.x < x_min {
                 x_min = t.x.clone();
@@ -150,7 +237,9 @@ struct LayerAABT {
 }
 
 impl LayerAABT {
-    pub fn new(mesh : &Mesh, tree: &mut TreeAABT) -> LayerAABT {
+    pub fn new<T>(mesh : &Mesh<T>, tree: &mut TreeAABT<T>) -> LayerAABT
+        where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+    {
         let mut neighbours: HashMap<usize, BTreeSet<usize>> = HashMap::new();
 
         let (it_to_t, mut it_to_ns) = mesh.get_triangles_and_neighbours();
@@ -166,7 +255,9 @@ impl LayerAABT {
         return LayerAABT {neighbours, layer_level: 0};
     }
 
-    pub fn create_next_layer(&self, tree: &mut TreeAABT) -> LayerAABT {
+    pub fn create_next_layer<T>(&self, tree: &mut TreeAABT<T>) -> LayerAABT
+        where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+    {
         //println!("Layer {0} is creating!", self.layer_level + 1);
 
         // соседи следующего уровня! не нужно путать с соседями текущего!
@@ -174,26 +265,18 @@ impl LayerAABT {
 
         let mut stack: Vec<usize>  = Vec::new();
 
-        // кладем индекс случайного бокса в стэк
-        stack.push(self.neighbours.keys().next().unwrap().clone());
-
         let mut number_of_performed_elements = 0;
 
-        while !stack.is_empty() /*|| number_of_performed_elements < self.neighbours.len()*/ {
-            /*
-            let cur_box_index = if !stack.is_empty() {
-                stack.pop().unwrap()
-            } else {
-                // ищем необработанный элемент
-                for index in self.neighbours.keys() {
-                    if !tree.index_to_parent.contains_key(index) {
-                        stack.push(*index);
-                        break;
-                    }
+        loop {
+            if stack.is_empty() {
+                // ищем необработанный элемент, так как компонент связности может быть несколько
+                let opt_index = self.neighbours.keys().find(|index| !tree.index_to_parent.contains_key(index));
+                match opt_index {
+                    Some(index) => stack.push(*index),
+                    None => break
                 }
-                stack.pop().unwrap()
-            };
-            */
+            }
+
             let cur_box_index = stack.pop().unwrap();
 
             if tree.index_to_parent.contains_key(&cur_box_index) {
@@ -213,7 +296,7 @@ impl LayerAABT {
                     let parent_index = tree.max_index;
                     opt_parent_index = Some(parent_index);
 
-                    let parent_box: AAB;
+                    let parent_box: AAB<T>;
                     {
                         let first_nbox = tree.boxes.get(nbox_index).unwrap();
                         let cur_box = tree.boxes.get(&cur_box_index).unwrap();
@@ -275,6 +358,21 @@ impl LayerAABT {
         return LayerAABT {neighbours, layer_level: self.layer_level + 1};
     }
 
+    // если боксы слоя не имеют соседей (компоненты связности свернуты в отдельные боксы),
+    // то они связываются в цепочку, чтобы следующий слой мог их объединить
+    pub fn link_isolated_boxes(&mut self) {
+        if self.neighbours.len() < 2 || self.neighbours.values().any(|ns| !ns.is_empty()) {
+            return;
+        }
+
+        let mut indexes: Vec<usize> = self.neighbours.keys().cloned().collect();
+        indexes.sort();
+        for i in 1..indexes.len() {
+            self.neighbours.get_mut(&indexes[i-1]).unwrap().insert(indexes[i]);
+            self.neighbours.get_mut(&indexes[i]).unwrap().insert(indexes[i-1]);
+        }
+    }
+
     pub fn get_number_of_boxes_in_layer(&self) -> usize {
         return self.neighbours.len();
     }
@@ -289,8 +387,8 @@ impl LayerAABT {
 }
 
 // дерево выровненых по осям параллепипедов
-pub(crate) struct TreeAABT {
-    boxes: HashMap<usize, AAB>,
+pub(crate) struct TreeAABT<T = Number> {
+    boxes: HashMap<usize, AAB<T>>,
     // вертикальные соседи
     successors: HashMap<usize, Vec<usize>>,
     index_to_parent: HashMap<usize, usize>,
@@ -298,11 +396,13 @@ pub(crate) struct TreeAABT {
     root_index: usize
 }
 
-impl TreeAABT {
+impl<T> TreeAABT<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
 
     // дерево строится так, что боксы имеют индексы такие же как и треугольники в полигональных сетках!
-    pub fn new(mesh : &Mesh) -> TreeAABT {
-        let boxes: HashMap<usize, AAB> = HashMap::new();
+    pub fn new(mesh : &Mesh<T>) -> TreeAABT<T> {
+        let boxes: HashMap<usize, AAB<T>> = HashMap::new();
         let successors: HashMap<usize, Vec<usize>> = HashMap::new();
         let parents: HashMap<usize, usize> = HashMap::new();
         let mut tree = TreeAABT {
@@ -315,6 +415,7 @@ impl TreeAABT {
         let mut layer = LayerAABT::new(mesh, &mut tree);
 
         while layer.get_number_of_boxes_in_layer() > 1 {
+            layer.link_isolated_boxes();
             layer = layer.create_next_layer(&mut tree);
         }
         tree.root_index = layer.get_root_index();
@@ -323,7 +424,57 @@ impl TreeAABT {
         return tree;
     }
 
-    pub fn intersect_trees(tree_a : &TreeAABT, tree_b : &TreeAABT) -> TupleIter {
+    // возвращает индексы треугольников, для боксов которых (и всех их предков) выполняется предикат
+    pub fn find_triangles<F>(&self, predicate: F) -> Vec<usize>
+        where F: Fn(&AAB<T>) -> bool
+    {
+        let mut res: Vec<usize> = Vec::new();
+        let mut stack: Vec<usize> = vec![self.root_index];
+
+        while !stack.is_empty() {
+            let index = stack.pop().unwrap();
+            if !predicate(self.boxes.get(&index).unwrap()) {
+                continue;
+            }
+
+            match self.successors.get(&index) {
+                Some(successors) => stack.extend(successors.iter()),
+                None => res.push(index)
+            }
+        }
+
+        return res;
+    }
+
+    // поиск ближайшего к точке треугольника: боксы обходятся в порядке возрастания расстояния до них,
+    // squared_distance возвращает квадрат расстояния от точки до треугольника с заданным индексом
+    pub fn find_nearest_triangle<F>(&self, p: &Point<T>, mut squared_distance: F) -> (usize, T)
+        where F: FnMut(usize) -> T
+    {
+        // (расстояние, является ли элемент треугольником, индекс); при равных расстояниях сначала раскрываются боксы,
+        // поэтому из равноудаленных треугольников возвращается треугольник с наименьшим индексом
+        let mut heap: BinaryHeap<Reverse<(T, bool, usize)>> = BinaryHeap::new();
+        heap.push(Reverse((T::zero(), false, self.root_index)));
+
+        loop {
+            let Reverse((distance, is_triangle, index)) = heap.pop().unwrap();
+            if is_triangle {
+                return (index, distance);
+            }
+
+            match self.successors.get(&index) {
+                Some(successors) => {
+                    for successor in successors.iter() {
+                        let b = self.boxes.get(successor).unwrap();
+                        heap.push(Reverse((b.get_squared_distance_to_point(p), false, *successor)));
+                    }
+                },
+                None => heap.push(Reverse((squared_distance(index), true, index)))
+            }
+        }
+    }
+
+    pub fn intersect_trees(tree_a : &TreeAABT<T>, tree_b : &TreeAABT<T>) -> TupleIter {
         let mut pairs : Vec<(usize, usize)> = Vec::new();
         let mut stack: Vec<(usize, usize)> = Vec::new();
         stack.push((tree_a.root_index, tree_b.root_index));
@@ -331,8 +482,8 @@ impl TreeAABT {
 
         while !stack.is_empty() {
             let (index_a, index_b) = stack.pop().unwrap();
-            let box1: &AAB = tree_a.boxes.get(&index_a).unwrap();
-            let box2: &AAB = tree_b.boxes.get(&index_b).unwrap();
+            let box1: &AAB<T> = tree_a.boxes.get(&index_a).unwrap();
+            let box2: &AAB<T> = tree_b.boxes.get(&index_b).unwrap();
 
             //println!("box1 {}", box1);
             //println!("box2 {}", box2);
@@ -379,9 +530,37 @@ mod test {
     // use primitives::triangle::Triangle;
     use intersect::tuple_iter::{TreeAABT, enumerate_simple};
     use intersect::mesh_x_mesh;
+    use modeling::generators::create_box;
+    use primitives::point::Point;
+    use primitives::number::*;
 
     use time::PreciseTime;
 
+    #[test]
+    fn tree_of_two_components() {
+        let mut mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 1., 1.)).unwrap();
+        let other = create_box(&Point::new_from_f64(3., 0., 0.), &Point::new_from_f64(4., 1., 1.)).unwrap();
+        let ts = other.get_it_iterator().into_iter().map(|i| other.get_triangle(i)).collect();
+        mesh.add_triangles(ts);
+
+        let tree = TreeAABT::new(&mesh);
+        let mut indexes = tree.find_triangles(|_| true);
+        indexes.sort();
+        let mut expected = mesh.get_it_iterator();
+        expected.sort();
+        assert_eq!(indexes, expected);
+
+        let root = tree.boxes.get(&tree.root_index).unwrap();
+        assert!(root.does_box_contain_point(&Point::new_from_f64(0., 0., 0.)));
+        assert!(root.does_box_contain_point(&Point::new_from_f64(4., 1., 1.)));
+
+        // пересекается только со второй компонентой
+        let probe = create_box(&Point::new_from_f64(3.5, 0.5, 0.5), &Point::new_from_f64(5., 2., 2.)).unwrap();
+        let pairs = TreeAABT::intersect_trees(&tree, &TreeAABT::new(&probe));
+        assert!(!pairs.v.is_empty());
+        assert!(pairs.v.iter().all(|&(a, _)| mesh.get_triangle(a).get_points_ref().iter().all(|p| p.x >= Number::new(3.))));
+    }
+
     #[ignore]
     #[test]
     fn first_tree_test() {
@@ -5,6 +5,7 @@
 extern crate core;
 extern crate time;
 extern crate num;
+#[cfg(feature = "gmp")]
 extern crate gmp;
 extern crate bidir_map;
 extern crate byteorder;
@@ -12,6 +13,9 @@ extern crate test;
 extern crate rulinalg;
 extern crate rand;
 
+#[cfg(not(any(feature = "gmp", feature = "bigrational")))]
+compile_error!("One of the features `gmp` or `bigrational` has to be enabled!");
+
 #[macro_use]
 extern crate lazy_static;
 
@@ -20,13 +24,89 @@ extern crate log;
 extern crate env_logger;
 
 /// This module contains basic geometry classes.
+/// Points, vectors, segments, planes, triangles, polygons and meshes are generic over a number type implementing
+/// `NumberTrait`, which is `Number` by default.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::number::{Number, FloatNumber, NumberTrait};
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::primitives::mesh::Mesh;
+/// use geometry_kernel::modeling::create_box;
+///
+/// fn main() {
+///   let exact = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 2., 3.)).unwrap();
+///   let preview : Mesh<FloatNumber> = exact.convert();
+///   assert_eq!(preview.num_of_triangles(), exact.num_of_triangles());
+///   assert_eq!(preview.get_volume(), FloatNumber::new(6.));
+///   assert_eq!(exact.get_volume(), Number::new(6.));
+/// }
+/// ```
 pub mod primitives;
 mod intersect;
-mod matrix;
+
+/// This module contains exact linear algebra: products, the LU decomposition, determinants, inverse matrices,
+/// ranks and nullspaces of dense matrices.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::number::{Number, NumberTrait};
+/// use geometry_kernel::matrix::{Matrix, Row};
+///
+/// fn main() {
+///   let m : Matrix<Number> = Matrix::new_from_vector(vec![
+///     Row::new_from_vector(vec![Number::new(2.), Number::new(1.)]),
+///     Row::new_from_vector(vec![Number::new(1.), Number::new(1.)])
+///   ]);
+///   assert_eq!(m.get_determinant().unwrap(), Number::new(1.));
+///   assert_eq!(m.multiply(&m.get_inverse().unwrap()).unwrap(), Matrix::identity(2));
+///
+///   let x = m.solve(Row::new_from_vector(vec![Number::new(3.), Number::new(2.)])).unwrap();
+///   assert_eq!(x.convert_to_vec(), vec![Number::new(1.), Number::new(1.)]);
+/// }
+/// ```
+pub mod matrix;
+
+/// This module contains planar triangulations: triangulations of point sets and polygons in 3D planes,
+/// the exact constrained Delaunay triangulation and Delaunay refinement. Its functions are re-exported at the crate root.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::primitives::vector::Vector;
+/// use geometry_kernel::primitives::signed_trait::Signed;
+/// use geometry_kernel::triangulate_polygon3d;
+///
+/// fn main() {
+///   let outer = vec![
+///     Point::new_from_f64(0., 0., 0.),
+///     Point::new_from_f64(4., 0., 4.),
+///     Point::new_from_f64(4., 4., 4.),
+///     Point::new_from_f64(0., 4., 0.)
+///   ];
+///   let hole = vec![
+///     Point::new_from_f64(1., 1., 1.),
+///     Point::new_from_f64(1., 3., 1.),
+///     Point::new_from_f64(3., 3., 3.),
+///     Point::new_from_f64(3., 1., 3.)
+///   ];
+///   let normal = Vector::new_from_f64(-1., 0., 1.);
+///   let ts = triangulate_polygon3d(&outer, &vec![hole], &normal).unwrap();
+///   assert_eq!(ts.len(), 8);
+///   assert!(ts.iter().all(|t| t.get_normal().dot_product(&normal).is_it_positive()));
+/// }
+/// ```
 mod triangulation;
+pub use triangulation::{TriangulationAlgorithm, triangulate3d, triangulate_ptree3d, triangulate_polygon3d};
+pub use triangulation::constrained_delaunay_triangulation::triangulate2d;
+pub use triangulation::delaunay_refinement::{MAX_MIN_ANGLE, refine2d};
 
 
-/// This module contains fucntions to perform boolean operations.
+/// This module contains fucntions to perform boolean operations on meshes and on planar regions (`union2d`, `intersection2d`,
+/// `difference2d`, `xor2d`), given as forests of `PolygonTreeNode`.
 /// # Examples
 ///
 /// ```
@@ -54,3 +134,107 @@ mod triangulation;
 /// }
 /// ```
 pub mod bool_op;
+
+/// This module contains exact queries to meshes and least-squares fitting of planes and lines to points.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::mesh::Mesh;
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::query::{PointClassifier, PointPosition};
+/// use std::fs::File;
+///
+/// fn main() {
+///   let mut f = File::open("input_for_tests/cube_in_origin.stl").unwrap();
+///   let mesh = Mesh::read_stl(&mut f).unwrap();
+///
+///   let classifier = PointClassifier::new(&mesh);
+///   let position = classifier.classify(&Point::new_from_f64(100., 100., 100.));
+///   assert_eq!(position, PointPosition::Outside);
+/// }
+/// ```
+pub mod query;
+
+/// This module contains functions to create solids, to build convex hulls, to tetrahedralize, slice, split them
+/// and to offset planar contours.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::number::Number;
+/// use geometry_kernel::primitives::number_trait::NumberTrait;
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::modeling::{create_box, create_cylinder};
+/// use geometry_kernel::bool_op::BoolOpResult;
+///
+/// fn main() {
+///   let cube = create_box(&Point::new_from_f64(-1., -1., 0.5), &Point::new_from_f64(1., 1., 2.)).unwrap();
+///   let cylinder = create_cylinder(Number::new(0.5), Number::new(1.), 8).unwrap();
+///
+///   let result = BoolOpResult::new(&cube, &cylinder)
+///     .expect("The error was raised in a constructor of <BoolOpPerformer>!");
+///   let union = result.union();
+///   assert!(union.geometry_check());
+/// }
+/// ```
+pub mod modeling;
+
+/// This module contains writers of 2D contours and triangles to SVG and DXF.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::primitives::plane::Plane;
+/// use geometry_kernel::primitives::vector::Vector;
+/// use geometry_kernel::modeling::{create_box, slice_mesh};
+/// use geometry_kernel::export::Drawing;
+///
+/// fn main() {
+///   let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 1., 1.)).unwrap();
+///   let plane = Plane::new(Vector::new_from_f64(0., 0., 1.), Point::new_from_f64(0., 0., 0.5));
+///   let section = slice_mesh(&mesh, &plane).unwrap();
+///
+///   let mut drawing = Drawing::new();
+///   drawing.add_section(&section);
+///
+///   let mut svg: Vec<u8> = Vec::new();
+///   drawing.write_svg(&mut svg, false).unwrap();
+///   let mut dxf: Vec<u8> = Vec::new();
+///   drawing.write_dxf(&mut dxf).unwrap();
+/// }
+/// ```
+pub mod export;
+
+/// This module contains the Voronoi diagram of points in the plane, built from the exact Delaunay triangulation.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::primitives::polygon::Polygon;
+/// use geometry_kernel::primitives::vector::Vector;
+/// use geometry_kernel::voronoi::VoronoiDiagram;
+///
+/// fn main() {
+///   let sites = vec![
+///     Point::new_from_f64(0., 0., 0.),
+///     Point::new_from_f64(2., 0., 0.),
+///     Point::new_from_f64(1., 2., 0.),
+///     Point::new_from_f64(1., 0.5, 0.)
+///   ];
+///   let diagram = VoronoiDiagram::new(sites).unwrap();
+///   assert_eq!(diagram.get_cells().iter().filter(|c| c.is_bounded()).count(), 1);
+///
+///   let boundary = Polygon::new(vec![
+///     Point::new_from_f64(-1., -1., 0.),
+///     Point::new_from_f64(3., -1., 0.),
+///     Point::new_from_f64(3., 3., 0.),
+///     Point::new_from_f64(-1., 3., 0.)
+///   ], Vector::new_from_f64(0., 0., 1.));
+///   let regions = diagram.clip(&boundary).unwrap();
+///   assert_eq!(regions.len(), 4);
+/// }
+/// ```
+pub mod voronoi;
@@ -3,6 +3,7 @@ use geometry_kernel::primitives::mesh::Mesh;
 use geometry_kernel::bool_op::BoolOpResult;
 use std::fs::File;
 
+#[cfg(feature = "gmp")]
 extern crate gmp;
 
 fn main() {
@@ -1,12 +1,16 @@
 use std::ops::{Add, Sub, Mul, Div, Neg};
 use primitives::zero_trait::Zero;
+use primitives::number_trait::{NumberTrait, NumberRef};
 
 use std::clone::Clone;
 // use std::mem;
+use std::fmt;
 use std::fmt::Display;
 // use std::iter::Rev;
 
 
+/// This structure represents a row of a matrix. It's also used as a column vector in products and systems of equations.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Row<T>
     where T:
         Add<Output = T> +
@@ -20,6 +24,9 @@ pub struct Row<T>
     values : Vec<T>
 }
 
+/// This structure represents a dense matrix stored by rows. Elimination doesn't lose precision for exact types
+/// like `Number`, for floating point types rows are pivoted by the largest absolute value.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Matrix<T>
     where T:
         Add<Output = T> +
@@ -33,6 +40,25 @@ pub struct Matrix<T>
     rows : Vec<Row<T>>
 }
 
+/// This structure represents the LU decomposition with partial pivoting `PA = LU` of a nonsingular square matrix `A`.
+/// `L` is lower triangular with the unit diagonal, `U` is upper triangular and `P` is a permutation of rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LUDecomposition<T>
+    where T:
+        Add<Output = T> +
+        Sub<Output = T> +
+        Mul<Output = T> +
+        Div<Output = T> +
+        Zero<T> + Clone + Neg<Output = T> +
+        PartialOrd + PartialEq +
+        Display
+{
+    // L без единичной диагонали и U в одной матрице
+    lu : Matrix<T>,
+    permutation : Vec<usize>,
+    odd : bool
+}
+
 impl<T> Row<T>
     where T:
         Add<Output = T> +
@@ -72,11 +98,40 @@ impl<T> Row<T>
         self.values[*i] = value;
     }
 
-    pub fn print(&self) {
-        for j in 0..self.length() {
-            print!("{0}\t", self.get(&j));
+    /// This method returns the dot product of rows of the same length.
+    /// # Arguments
+    ///
+    /// * `other` - A row of the same length.
+    pub fn dot_product(&self, other : &Row<T>) -> Result<T, &'static str> {
+        if self.length() != other.length() {
+            return Err("Lengths of rows don't match!");
+        }
+        let mut res = T::zero();
+        for (a, b) in self.values.iter().zip(other.values.iter()) {
+            res = res + a.clone() * b.clone();
+        }
+        return Ok(res);
+    }
+}
+
+impl<T> Display for Row<T>
+    where T:
+        Add<Output = T> +
+        Sub<Output = T> +
+        Mul<Output = T> +
+        Div<Output = T> +
+        Zero<T> + Clone + Neg<Output = T> +
+        PartialOrd + PartialEq +
+        Display
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        for (j, v) in self.values.iter().enumerate() {
+            if j > 0 {
+                write!(f, "\t")?;
+            }
+            write!(f, "{}", v)?;
         }
-        println!();
+        Ok(())
     }
 }
 
@@ -99,6 +154,16 @@ impl<T> Matrix<T>
         return m
     }
 
+    /// This method creates a zero matrix with `rows` rows and `columns` columns.
+    /// # Arguments
+    ///
+    /// * `rows` - A number of rows.
+    /// * `columns` - A number of columns.
+    pub fn new_with_size(rows : usize, columns : usize) -> Matrix<T> {
+        return Matrix {rows : (0..rows).map(|_| Row::new(columns)).collect()};
+    }
+
+    /// This method creates a matrix from rows. Rows are supposed to have the same length.
     pub fn new_from_vector(rows : Vec<Row<T>>) -> Matrix<T> {
         Matrix {
             rows : rows
@@ -113,98 +178,319 @@ impl<T> Matrix<T>
         self.rows[*i].values[*j] = value;
     }
 
-    #[allow(dead_code)]
-    pub fn print(&self) {
-        for i in 0..self.number_of_rows() {
-            let ref row = self.rows[i];
-            row.print();
-        }
-        println!();
+    pub fn get_row(&self, i : &usize) -> &Row<T> {
+        &self.rows[*i]
     }
 
     pub fn number_of_rows(&self) -> usize {
         self.rows.len()
     }
 
-    pub fn solve(&mut self, mut b : Row<T>) -> Row<T> {
-        let n = self.number_of_rows();
-        let mut x = Row::new(n);
+    pub fn number_of_columns(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.length())
+    }
 
-        //b.print();
-        //self.print();
+    pub fn is_square(&self) -> bool {
+        self.number_of_rows() == self.number_of_columns()
+    }
 
+    /// This method returns the transposed matrix.
+    pub fn transpose(&self) -> Matrix<T> {
+        let (m, n) = (self.number_of_rows(), self.number_of_columns());
+        let mut res = Matrix::new_with_size(n, m);
+        for i in 0..m {
+            for j in 0..n {
+                res.set(&j, &i, self.get(&i, &j));
+            }
+        }
+        return res;
+    }
 
-        for main_index in 0..n-1 {
-            //println!("Iter: {0}", main_index);
-            //self.print();
+    /// This method returns the product `self * other`. It returns an error if the number of columns of `self`
+    /// isn't equal to the number of rows of `other`.
+    /// # Arguments
+    ///
+    /// * `other` - The right factor.
+    pub fn multiply(&self, other : &Matrix<T>) -> Result<Matrix<T>, &'static str> {
+        if self.number_of_columns() != other.number_of_rows() {
+            return Err("Sizes of matrices don't match!");
+        }
+        let columns = other.transpose();
+        let rows = self.rows.iter()
+            .map(|r| Row::new_from_vector(columns.rows.iter().map(|c| r.dot_product(c).unwrap()).collect()))
+            .collect();
+        return Ok(Matrix::new_from_vector(rows));
+    }
 
-            let mut main_value = self.get(&main_index, &main_index);
-            if main_value.is_it_zero() {
-                let mut new_main_index = main_index.clone();
-                for i in main_index..n {
-                    main_value = self.get(&i, &main_index);
-                    //println!("main_value = {0}", main_value);
-                    if !main_value.is_it_zero() {
-                        new_main_index = i;
-                    }
-                }
+    /// This method returns the product of the matrix and the column vector `v`.
+    /// # Arguments
+    ///
+    /// * `v` - A vector with the length equal to the number of columns.
+    pub fn multiply_by_row(&self, v : &Row<T>) -> Result<Row<T>, &'static str> {
+        if self.number_of_columns() != v.length() {
+            return Err("Sizes of the matrix and the vector don't match!");
+        }
+        return Ok(Row::new_from_vector(self.rows.iter().map(|r| r.dot_product(v).unwrap()).collect()));
+    }
 
-                if new_main_index == main_index {
-                    //println!("main_index = {0}", main_index);
-                    panic!("Singular matrix!\n");
-                } else {
-                    self.rows.swap(main_index, new_main_index);
-                    b.values.swap(main_index, new_main_index);
-                }
-            }
-            main_value = self.get(&main_index, &main_index);
+    /// This method returns the LU decomposition with partial pivoting.
+    /// It returns an error if the matrix isn't square or is singular.
+    pub fn get_lu(&self) -> Result<LUDecomposition<T>, &'static str> {
+        if !self.is_square() || self.number_of_rows() == 0 {
+            return Err("Matrix has to be square and non-empty!");
+        }
 
-            //self.print();
-            //println!("main_index = {0}, n = {1}", main_index, n);
+        let n = self.number_of_rows();
+        let mut lu = self.clone();
+        let mut permutation : Vec<usize> = (0..n).collect();
+        let mut odd = false;
+        for k in 0..n {
+            let pivot = match lu.find_pivot(k, k) {
+                Some(pivot) => pivot,
+                None => return Err("Singular matrix!")
+            };
+            if pivot != k {
+                lu.rows.swap(k, pivot);
+                permutation.swap(k, pivot);
+                odd = !odd;
+            }
 
-            for i in main_index+1..n {
-                let value_under_main = self.get(&i, &main_index);
-                //println!("value_under_main = {0}", value_under_main);
-                if value_under_main.is_it_zero() {
+            let main_value = lu.get(&k, &k);
+            for i in k + 1..n {
+                let factor = lu.get(&i, &k) / main_value.clone();
+                if factor.is_it_zero() {
                     continue;
                 }
+                for j in k + 1..n {
+                    let value = lu.get(&i, &j) - factor.clone() * lu.get(&k, &j);
+                    lu.set(&i, &j, value);
+                }
+                lu.set(&i, &k, factor);
+            }
+        }
+        return Ok(LUDecomposition {lu : lu, permutation : permutation, odd : odd});
+    }
 
-                let factor : T = -(value_under_main/main_value.clone());
-                //println!("main_value = {0}", main_value);
-                //println!("factor = {0}", factor);
+    /// This method solves the system `self * x = b`. It returns an error if the matrix isn't square or is singular.
+    /// # Arguments
+    ///
+    /// * `b` - The right side with the length equal to the number of rows.
+    pub fn solve(&self, b : Row<T>) -> Result<Row<T>, &'static str> {
+        return self.get_lu()?.solve(&b);
+    }
 
+    /// This method returns the determinant. It returns an error if the matrix isn't square.
+    pub fn get_determinant(&self) -> Result<T, &'static str> {
+        if !self.is_square() || self.number_of_rows() == 0 {
+            return Err("Matrix has to be square and non-empty!");
+        }
+        return Ok(match self.get_lu() {
+            Ok(lu) => lu.get_determinant(),
+            Err(_) => T::zero()
+        });
+    }
 
-                for j in main_index..n {
-                    let cur_value_in_main = self.get(&main_index, &j);
-                    let cur_value_under_main = self.get(&i, &j);
-                    let new_value = cur_value_under_main + cur_value_in_main*factor.clone();
-                    self.set(&i, &j, new_value)
+    /// This method returns the rank, i.e. the number of linearly independent rows.
+    pub fn get_rank(&self) -> usize {
+        return self.get_row_echelon_form().1.len();
+    }
+
+    // ступенчатый вид и номера столбцов ведущих элементов
+    fn get_row_echelon_form(&self) -> (Matrix<T>, Vec<usize>) {
+        let (m, n) = (self.number_of_rows(), self.number_of_columns());
+        let mut res = self.clone();
+        let mut pivots : Vec<usize> = Vec::new();
+        for j in 0..n {
+            let k = pivots.len();
+            if k == m {
+                break;
+            }
+            let pivot = match res.find_pivot(k, j) {
+                Some(pivot) => pivot,
+                None => continue
+            };
+            res.rows.swap(k, pivot);
+
+            let main_value = res.get(&k, &j);
+            for i in 0..m {
+                if i == k {
+                    continue;
                 }
+                let factor = res.get(&i, &j) / main_value.clone();
+                if factor.is_it_zero() {
+                    continue;
+                }
+                for l in j..n {
+                    let value = res.get(&i, &l) - factor.clone() * res.get(&k, &l);
+                    res.set(&i, &l, value);
+                }
+            }
+            pivots.push(j);
+        }
+        return (res, pivots);
+    }
+
+    // строка с наибольшим по модулю элементом столбца j среди строк начиная с k
+    fn find_pivot(&self, k : usize, j : usize) -> Option<usize> {
+        let abs = |v : T| if v < T::zero() {-v} else {v};
+        let mut res : Option<(usize, T)> = None;
+        for i in k..self.number_of_rows() {
+            let value = abs(self.get(&i, &j));
+            if value.is_it_zero() {
+                continue;
+            }
+            if res.as_ref().map_or(true, |r| value > r.1) {
+                res = Some((i, value));
+            }
+        }
+        return res.map(|r| r.0);
+    }
+}
 
-                b.values[i] = b.values[i].clone() + b.values[main_index].clone()*factor;
+impl<T> Matrix<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    /// This method returns the identity matrix of size `n`.
+    /// # Arguments
+    ///
+    /// * `n` - A size of the matrix.
+    pub fn identity(n : usize) -> Matrix<T> {
+        let mut m = Matrix::new(n);
+        for i in 0..n {
+            m.set(&i, &i, T::new(1.));
+        }
+        return m;
+    }
+
+    /// This method returns the inverse matrix. It returns an error if the matrix isn't square or is singular.
+    pub fn get_inverse(&self) -> Result<Matrix<T>, &'static str> {
+        let lu = self.get_lu()?;
+        let n = self.number_of_rows();
+        let identity : Matrix<T> = Matrix::identity(n);
+        let mut columns : Vec<Row<T>> = Vec::new();
+        for i in 0..n {
+            columns.push(lu.solve(&identity.rows[i])?);
+        }
+        return Ok(Matrix::new_from_vector(columns).transpose());
+    }
 
+    /// This method returns a basis of the nullspace, i.e. of solutions of `self * x = 0`.
+    /// Each vector of the basis has the unit component at one of free variables and zeros at others.
+    pub fn get_nullspace(&self) -> Vec<Row<T>> {
+        let n = self.number_of_columns();
+        let (echelon, pivots) = self.get_row_echelon_form();
+        let mut basis : Vec<Row<T>> = Vec::new();
+        for free in (0..n).filter(|j| !pivots.contains(j)) {
+            let mut v : Row<T> = Row::new(n);
+            v.set(&free, T::new(1.));
+            for (k, &j) in pivots.iter().enumerate() {
+                v.set(&j, -(echelon.get(&k, &free) / echelon.get(&k, &j)));
             }
+            basis.push(v);
+        }
+        return basis;
+    }
+}
 
-            //self.print();
+impl<T> Display for Matrix<T>
+    where T:
+        Add<Output = T> +
+        Sub<Output = T> +
+        Mul<Output = T> +
+        Div<Output = T> +
+        Zero<T> + Clone + Neg<Output = T> +
+        PartialOrd + PartialEq +
+        Display
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows.iter() {
+            writeln!(f, "{}", row)?;
         }
+        Ok(())
+    }
+}
 
-        //b.print();
-        //self.print();
+impl<T> LUDecomposition<T>
+    where T:
+        Add<Output = T> +
+        Sub<Output = T> +
+        Mul<Output = T> +
+        Div<Output = T> +
+        Zero<T> + Clone + Neg<Output = T> +
+        PartialOrd + PartialEq +
+        Display
+{
+    /// This method solves the system `A * x = b`.
+    /// # Arguments
+    ///
+    /// * `b` - The right side with the length equal to the size of the matrix.
+    pub fn solve(&self, b : &Row<T>) -> Result<Row<T>, &'static str> {
+        let n = self.lu.number_of_rows();
+        if b.length() != n {
+            return Err("Sizes of the matrix and the vector don't match!");
+        }
 
+        // прямой ход: L * y = P * b
+        let mut y : Row<T> = Row::new(n);
+        for i in 0..n {
+            let mut value = b.get(&self.permutation[i]);
+            for j in 0..i {
+                value = value - self.lu.get(&i, &j) * y.get(&j);
+            }
+            y.set(&i, value);
+        }
 
+        // обратный ход: U * x = y
+        let mut x : Row<T> = Row::new(n);
         for i in (0..n).rev() {
-            x.set(&i, b.values[i].clone());
+            let mut value = y.get(&i);
+            for j in i + 1..n {
+                value = value - self.lu.get(&i, &j) * x.get(&j);
+            }
+            x.set(&i, value / self.lu.get(&i, &i));
+        }
+        return Ok(x);
+    }
 
-            for j in i+1..n {
-                let xi = x.get(&i);
-                let xj = x.get(&j);
-                x.set(&i,  xi - self.get(&i, &j)*xj);
+    /// This method returns the determinant of `A`.
+    pub fn get_determinant(&self) -> T {
+        let mut res = self.lu.get(&0, &0);
+        for i in 1..self.lu.number_of_rows() {
+            res = res * self.lu.get(&i, &i);
+        }
+        return if self.odd {-res} else {res};
+    }
+
+    /// This method returns the upper triangular matrix `U`.
+    pub fn get_upper(&self) -> Matrix<T> {
+        let mut res = self.lu.clone();
+        for i in 0..res.number_of_rows() {
+            for j in 0..i {
+                res.set(&i, &j, T::zero());
             }
-            let xi = x.get(&i)/self.get(&i, &i);
-            x.set(&i, xi);
         }
+        return res;
+    }
 
-        return x
+    /// This method returns the permutation: the `i`-th row of `PA` is the `permutation[i]`-th row of `A`.
+    pub fn get_permutation(&self) -> &Vec<usize> {
+        return &self.permutation;
+    }
+}
+
+impl<T> LUDecomposition<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    /// This method returns the lower triangular matrix `L` with the unit diagonal.
+    pub fn get_lower(&self) -> Matrix<T> {
+        let n = self.lu.number_of_rows();
+        let mut res : Matrix<T> = Matrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                res.set(&i, &j, self.lu.get(&i, &j));
+            }
+        }
+        return res;
     }
 }
 
@@ -223,7 +509,7 @@ mod tests {
     #[test]
     fn solver_test1() {
         let n = 3;
-        let mut m : Matrix<f32> = Matrix::new_from_vector(
+        let m : Matrix<f32> = Matrix::new_from_vector(
             vec![
                 Row::new_from_vector(vec![0., 1., 2.]),
                 Row::new_from_vector(vec![1., 1., 0.]),
@@ -233,7 +519,7 @@ mod tests {
 
         let b : Row<f32> = Row::new_from_vector(vec![3., 2., 3.]);
 
-        let x  = m.solve(b);
+        let x  = m.solve(b).unwrap();
 
         for i in 0..n {
             let v = x.get(&i);
@@ -247,7 +533,7 @@ mod tests {
     #[test]
     fn solver_test2() {
         // let n = 3;
-        let mut m : Matrix<f32> = Matrix::new_from_vector(
+        let m : Matrix<f32> = Matrix::new_from_vector(
             vec![
                 Row::new_from_vector(vec![0., 1., 2.]),
                 Row::new_from_vector(vec![0., 0., 1.]),
@@ -257,7 +543,7 @@ mod tests {
 
         let b : Row<f32> = Row::new_from_vector(vec![4., 1., 7.]);
 
-        let x  = m.solve(b);
+        let x  = m.solve(b).unwrap();
 
 
         /*
@@ -274,7 +560,7 @@ mod tests {
     #[test]
     fn solver_test3() {
         // let n = 3;
-        let mut m : Matrix<f32> = Matrix::new_from_vector(
+        let m : Matrix<f32> = Matrix::new_from_vector(
             vec![
                 Row::new_from_vector(vec![1., 2., 3.]),
                 Row::new_from_vector(vec![1., 3., 3.]),
@@ -284,7 +570,7 @@ mod tests {
 
         let b : Row<f32> = Row::new_from_vector(vec![9., 11., 3.]);
 
-        let x  = m.solve(b);
+        let x  = m.solve(b).unwrap();
 
         /*
         for i in 0..n {
@@ -300,7 +586,7 @@ mod tests {
     #[test]
     fn solver_test_rational1() {
         // let n = 3;
-        let mut m : Matrix<Number> = Matrix::new_from_vector(
+        let m : Matrix<Number> = Matrix::new_from_vector(
             vec![
                 Row::new_from_vector(vec![Number::new_from_f32(1.), Number::new_from_f32(2.), Number::new_from_f32(3.)]),
                 Row::new_from_vector(vec![Number::new_from_f32(1.), Number::new_from_f32(3.), Number::new_from_f32(3.)]),
@@ -310,7 +596,7 @@ mod tests {
 
         let b : Row<Number> = Row::new_from_vector(vec![Number::new_from_f32(9.), Number::new_from_f32(11.), Number::new_from_f32(3.)]);
 
-        let x  = m.solve(b);
+        let x  = m.solve(b).unwrap();
 
         /*
         for i in 0..n {
@@ -321,4 +607,121 @@ mod tests {
         assert!(x.values == vec![Number::new_from_f32(2.), Number::new_from_f32(2.), Number::new_from_f32(1.)]);
 
     }
-}
\ No newline at end of file
+
+    fn matrix(rows : Vec<Vec<f64>>) -> Matrix<Number> {
+        return Matrix::new_from_vector(rows.into_iter()
+            .map(|r| Row::new_from_vector(r.into_iter().map(Number::new).collect()))
+            .collect());
+    }
+
+    fn row(values : Vec<f64>) -> Row<Number> {
+        return Row::new_from_vector(values.into_iter().map(Number::new).collect());
+    }
+
+    #[test]
+    fn determinant_and_inverse() {
+        let m = matrix(vec![vec![2., 1., 1.], vec![1., 3., 2.], vec![1., 0., 0.]]);
+        assert_eq!(m.get_determinant().unwrap(), Number::new(-1.));
+
+        let inverse = m.get_inverse().unwrap();
+        assert_eq!(inverse.multiply(&m).unwrap(), Matrix::identity(3));
+        assert_eq!(m.multiply(&inverse).unwrap(), Matrix::identity(3));
+        assert_eq!(inverse.get_determinant().unwrap(), Number::new(-1.));
+
+        let m = matrix(vec![vec![0., 0., 3.], vec![0., 2., 0.], vec![1., 0., 0.]]);
+        assert_eq!(m.get_determinant().unwrap(), Number::new(-6.));
+        let x = m.solve(row(vec![3., 1., 2.])).unwrap();
+        assert_eq!(x, row(vec![2., 0.5, 1.]));
+    }
+
+    #[test]
+    fn lu_decomposition() {
+        let m = matrix(vec![vec![1., 2., 0., 1.], vec![2., 4., 1., 0.], vec![0., 1., 1., 1.], vec![3., 1., 0., 2.]]);
+        let lu = m.get_lu().unwrap();
+        let (l, u) = (lu.get_lower(), lu.get_upper());
+        for i in 0..4 {
+            assert_eq!(l.get(&i, &i), Number::new(1.));
+            for j in (i + 1)..4 {
+                assert!(l.get(&i, &j).is_it_zero());
+                assert!(u.get(&j, &i).is_it_zero());
+            }
+        }
+
+        let mut p : Matrix<Number> = Matrix::new(4);
+        for (i, &j) in lu.get_permutation().iter().enumerate() {
+            p.set(&i, &j, Number::new(1.));
+        }
+        assert_eq!(p.multiply(&m).unwrap(), l.multiply(&u).unwrap());
+        assert_eq!(lu.get_determinant(), m.get_determinant().unwrap());
+
+        let b = row(vec![1., 2., 3., 4.]);
+        let x = lu.solve(&b).unwrap();
+        assert_eq!(m.multiply_by_row(&x).unwrap(), b);
+        assert!(lu.solve(&row(vec![1., 2.])).is_err());
+    }
+
+    #[test]
+    fn f64_matrices() {
+        // в f64 результаты совпадают с точными с относительной погрешностью
+        let close = |a: FloatNumber, b: f64| (a.convert_to_f64() - b).abs() <= 1e-12 * b.abs().max(1.);
+        let m : Matrix<FloatNumber> = Matrix::new_from_vector(vec![vec![2., 1., 1.], vec![1., 3., 2.], vec![1., 0., 0.]].into_iter()
+            .map(|r| Row::new_from_vector(r.into_iter().map(FloatNumber::new).collect()))
+            .collect());
+        assert!(close(m.get_determinant().unwrap(), -1.));
+
+        let product = m.get_inverse().unwrap().multiply(&m).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!(close(product.get(&i, &j), if i == j {1.} else {0.}));
+            }
+        }
+
+        let lu = m.get_lu().unwrap();
+        assert!(close(lu.get_determinant(), -1.));
+        let x = lu.solve(&Row::new_from_vector(vec![FloatNumber::new(4.), FloatNumber::new(6.), FloatNumber::new(1.)])).unwrap();
+        for (i, expected) in vec![1., 1., 1.].into_iter().enumerate() {
+            assert!(close(x.get(&i), expected));
+        }
+    }
+
+    #[test]
+    fn singular_matrices() {
+        let m = matrix(vec![vec![1., 2.], vec![2., 4.]]);
+        assert_eq!(m.get_lu().unwrap_err(), "Singular matrix!");
+        assert!(m.solve(row(vec![1., 2.])).is_err());
+        assert!(m.get_inverse().is_err());
+        assert!(m.get_determinant().unwrap().is_it_zero());
+        assert_eq!(m.get_rank(), 1);
+        assert_eq!(m.get_nullspace(), vec![row(vec![-2., 1.])]);
+
+        let rectangular = matrix(vec![vec![1., 2., 3.], vec![2., 4., 6.]]);
+        assert!(rectangular.get_determinant().is_err());
+        assert!(rectangular.get_lu().is_err());
+        assert_eq!(rectangular.get_rank(), 1);
+        let nullspace = rectangular.get_nullspace();
+        assert_eq!(nullspace.len(), 2);
+        for v in nullspace.iter() {
+            assert_eq!(rectangular.multiply_by_row(v).unwrap(), row(vec![0., 0.]));
+        }
+
+        let full = matrix(vec![vec![1., 0., 2.], vec![0., 1., 1.]]);
+        assert_eq!(full.get_rank(), 2);
+        assert_eq!(full.get_nullspace(), vec![row(vec![-2., -1., 1.])]);
+        assert_eq!(Matrix::<Number>::new(3).get_rank(), 0);
+        assert_eq!(Matrix::<Number>::new(3).get_nullspace().len(), 3);
+    }
+
+    #[test]
+    fn products() {
+        let a = matrix(vec![vec![1., 2., 3.], vec![4., 5., 6.]]);
+        let b = matrix(vec![vec![1., 0.], vec![0., 1.], vec![1., 1.]]);
+        assert_eq!(a.multiply(&b).unwrap(), matrix(vec![vec![4., 5.], vec![10., 11.]]));
+        assert_eq!(b.multiply(&a).unwrap().number_of_rows(), 3);
+        assert!(a.multiply(&a).is_err());
+        assert_eq!(a.transpose().multiply(&b.transpose()).unwrap(), b.multiply(&a).unwrap().transpose());
+
+        assert_eq!(a.multiply_by_row(&row(vec![1., 1., 1.])).unwrap(), row(vec![6., 15.]));
+        assert!(a.multiply_by_row(&row(vec![1., 1.])).is_err());
+        assert_eq!(format!("{}", b.transpose()), "1\t0\t1\n0\t1\t1\n");
+    }
+}
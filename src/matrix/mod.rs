@@ -1,5 +1,4 @@
-
 pub mod matrix;
 
 
-pub use self::matrix::{Matrix, Row};
+pub use self::matrix::{Matrix, Row, LUDecomposition};
@@ -0,0 +1,222 @@
+use primitives::*;
+use primitives::predicates::orient3d;
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// This function builds the exact convex hull of points by incremental construction with exact orientation tests.
+/// The result is a closed mesh with outward normals. Coplanar facets are triangulated by fans, points lying
+/// on the hull surface, but not in its corners, aren't used.
+/// It returns an error if points are collinear or coplanar.
+/// # Arguments
+///
+/// * `points` - Points, duplicates are allowed.
+pub fn convex_hull(points: Vec<Point>) -> Result<Mesh, &'static str> {
+    let ps: Vec<Point> = points.into_iter().collect::<BTreeSet<Point>>().into_iter().collect();
+    if ps.len() < 2 {
+        return Err("There are less than 2 different points!");
+    }
+
+    // начальный тетраэдр
+    let i1 = 1;
+    let e1: Vector = &ps[i1] - &ps[0];
+    let i2 = match (i1 + 1..ps.len()).find(|&i| !e1.cross_product(&(&ps[i] - &ps[0])).is_zero()) {
+        Some(i) => i,
+        None => return Err("Points are collinear!")
+    };
+    let i3 = match (i2 + 1..ps.len()).find(|&i| orient3d(&ps[0], &ps[i1], &ps[i2], &ps[i]) != Ordering::Equal) {
+        Some(i) => i,
+        None => return Err("Points are coplanar!")
+    };
+
+    let mut hull = Hull { points: &ps, faces: Vec::new(), edges: HashMap::new() };
+    let tetrahedron = [0, i1, i2, i3];
+    for k in 0..4 {
+        let (a, b, c) = (tetrahedron[k], tetrahedron[(k + 1) % 4], tetrahedron[(k + 2) % 4]);
+        let d = tetrahedron[(k + 3) % 4];
+        if orient3d(&ps[a], &ps[b], &ps[c], &ps[d]) == Ordering::Greater {
+            hull.add_face([a, b, c]);
+        } else {
+            hull.add_face([a, c, b]);
+        }
+    }
+
+    for i in 0..ps.len() {
+        if i != 0 && i != i1 && i != i2 && i != i3 {
+            hull.add_point(i);
+        }
+    }
+
+    let mut mesh = Mesh::new();
+    for f in hull.faces.iter().filter_map(|f| *f) {
+        let t = Triangle::new(vec![ps[f[0]].clone(), ps[f[1]].clone(), ps[f[2]].clone()]);
+        if mesh.add_triangle(t).is_err() {
+            return Err("Degenerate facet of the hull!");
+        }
+    }
+    return Ok(mesh);
+}
+
+/// This function builds the exact convex hull of vertices of the mesh. See `convex_hull`.
+/// # Arguments
+///
+/// * `mesh` - A mesh.
+pub fn convex_hull_of_mesh(mesh: &Mesh) -> Result<Mesh, &'static str> {
+    return convex_hull(mesh.get_points().values().cloned().collect());
+}
+
+struct Hull<'a> {
+    points: &'a Vec<Point>,
+    // грани ориентированы против часовой стрелки при взгляде снаружи
+    faces: Vec<Option<[usize; 3]>>,
+    edges: HashMap<(usize, usize), usize>
+}
+
+impl<'a> Hull<'a> {
+    fn add_face(&mut self, f: [usize; 3]) {
+        self.faces.push(Some(f));
+        let i = self.faces.len() - 1;
+        for j in 0..3 {
+            self.edges.insert((f[j], f[(j + 1) % 3]), i);
+        }
+    }
+
+    fn remove_face(&mut self, i: usize) {
+        let f = self.faces[i].take().unwrap();
+        for j in 0..3 {
+            self.edges.remove(&(f[j], f[(j + 1) % 3]));
+        }
+    }
+
+    fn add_point(&mut self, p: usize) {
+        let ps = self.points;
+        let mut visible: BTreeSet<usize> = BTreeSet::new();
+        for (i, f) in self.faces.iter().enumerate() {
+            let f = match *f {
+                Some(f) => f,
+                None => continue
+            };
+            match orient3d(&ps[f[0]], &ps[f[1]], &ps[f[2]], &ps[p]) {
+                Ordering::Less => { visible.insert(i); },
+                Ordering::Equal => {
+                    // точка в плоскости грани видит ее, только если лежит вне треугольника
+                    if self.is_in_triangle(&f, p) {
+                        return;
+                    }
+                    visible.insert(i);
+                },
+                Ordering::Greater => ()
+            }
+        }
+
+        if visible.is_empty() {
+            return;
+        }
+
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        for &i in visible.iter() {
+            let f = self.faces[i].unwrap();
+            for j in 0..3 {
+                let (a, b) = (f[j], f[(j + 1) % 3]);
+                if !visible.contains(&self.edges[&(b, a)]) {
+                    horizon.push((a, b));
+                }
+            }
+        }
+
+        for &i in visible.iter() {
+            self.remove_face(i);
+        }
+        for (a, b) in horizon {
+            self.add_face([a, b, p]);
+        }
+    }
+
+    fn is_in_triangle(&self, f: &[usize; 3], p: usize) -> bool {
+        let ps = self.points;
+        let normal = (&ps[f[1]] - &ps[f[0]]).cross_product(&(&ps[f[2]] - &ps[f[0]]));
+        for j in 0..3 {
+            let (a, b) = (&ps[f[j]], &ps[f[(j + 1) % 3]]);
+            let side = (b - a).cross_product(&(&ps[p] - a)).dot_product(&normal);
+            if side.is_it_negative() {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use modeling::*;
+
+    fn p(x: f64, y: f64, z: f64) -> Point {
+        return Point::new_from_f64(x, y, z);
+    }
+
+    #[test]
+    fn hull_of_cube_points() {
+        let mut points = Vec::new();
+        for &x in [0., 1., 2.].iter() {
+            for &y in [0., 1., 2.].iter() {
+                for &z in [0., 1., 2.].iter() {
+                    points.push(p(x, y, z));
+                }
+            }
+        }
+        points.push(p(0.5, 0.5, 0.5));
+        points.push(p(2., 2., 2.));
+
+        let hull = convex_hull(points).unwrap();
+        assert!(hull.geometry_check());
+        assert_eq!(hull.num_of_triangles(), 12);
+        assert_eq!(hull.num_of_points(), 8);
+        assert_eq!(hull.get_volume(), Number::new(8.));
+    }
+
+    #[test]
+    fn hull_of_sphere_and_inner_points() {
+        let sphere = create_uv_sphere(Number::new(1.), 12, 6).unwrap();
+        let hull = convex_hull_of_mesh(&sphere).unwrap();
+        assert!(hull.geometry_check());
+        let volume = sphere.get_volume().convert_to_f64();
+        assert!((hull.get_volume().convert_to_f64() - volume).abs() < 1e-9);
+
+        let mut points: Vec<Point> = sphere.get_points().values().cloned().collect();
+        points.extend(vec![p(0., 0., 0.), p(0.1, 0.2, 0.3), p(-0.5, 0.1, 0.)]);
+        let hull2 = convex_hull(points).unwrap();
+        assert_eq!(hull2.num_of_points(), hull.num_of_points());
+        assert!((hull2.get_volume().convert_to_f64() - volume).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hull_of_points_in_general_position() {
+        let mut points = Vec::new();
+        for i in 0..60 {
+            let t = i as f64;
+            points.push(p((t * 1.3).sin() * 3., (t * 0.7).cos() * 2., (t * 2.9).sin() + t * 0.01));
+        }
+        let hull = convex_hull(points.clone()).unwrap();
+        assert!(hull.geometry_check());
+        assert!(hull.get_volume().is_it_positive());
+
+        // все точки лежат внутри или на границе
+        for it in hull.get_it_iterator() {
+            let t = hull.get_triangle(it);
+            let ps = t.get_points_ref();
+            for q in points.iter() {
+                assert!(predicates::orient3d(&ps[0], &ps[1], &ps[2], q) != ::std::cmp::Ordering::Less);
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        assert!(convex_hull(vec![]).is_err());
+        assert!(convex_hull(vec![p(0., 0., 0.), p(0., 0., 0.)]).is_err());
+        assert!(convex_hull(vec![p(0., 0., 0.), p(1., 1., 1.), p(2., 2., 2.), p(3., 3., 3.)]).is_err());
+        assert!(convex_hull(vec![p(0., 0., 0.), p(1., 0., 0.), p(0., 1., 0.), p(1., 1., 0.), p(3., 2., 0.)]).is_err());
+    }
+}
@@ -0,0 +1,460 @@
+use primitives::*;
+use primitives::transform::approximate_by_rational;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+// максимальная погрешность углов, используемых при построении рациональных точек на окружности и сфере
+const ANGLE_TOLERANCE: f64 = 1e-6;
+
+/// This function creates a box with faces parallel to coordinate planes.
+/// # Arguments
+///
+/// * `min` - A corner of the box with minimal coordinates.
+/// * `max` - A corner of the box with maximal coordinates.
+pub fn create_box(min: &Point, max: &Point) -> Result<Mesh, &'static str> {
+    if !(&max.x - &min.x).is_it_positive() || !(&max.y - &min.y).is_it_positive() || !(&max.z - &min.z).is_it_positive() {
+        return Err("The maximal corner of a box has to be greater than the minimal one!");
+    }
+
+    let p = |i: bool, j: bool, k: bool| -> Point {
+        Point::new(
+            if i {max.x.clone()} else {min.x.clone()},
+            if j {max.y.clone()} else {min.y.clone()},
+            if k {max.z.clone()} else {min.z.clone()}
+        )
+    };
+
+    let quads = vec![
+        [p(false, false, false), p(false, true, false), p(true, true, false), p(true, false, false)],
+        [p(false, false, true), p(true, false, true), p(true, true, true), p(false, true, true)],
+        [p(false, false, false), p(true, false, false), p(true, false, true), p(false, false, true)],
+        [p(false, true, false), p(false, true, true), p(true, true, true), p(true, true, false)],
+        [p(false, false, false), p(false, false, true), p(false, true, true), p(false, true, false)],
+        [p(true, false, false), p(true, true, false), p(true, true, true), p(true, false, true)]
+    ];
+
+    let mut mesh = Mesh::new();
+    for q in quads.iter() {
+        add_quad(&mut mesh, &q[0], &q[1], &q[2], &q[3]);
+    }
+    return Ok(mesh);
+}
+
+/// This function creates a UV sphere with the center in the origin.
+/// All vertices lie exactly on the sphere.
+/// # Arguments
+///
+/// * `radius` - A positive radius.
+/// * `segments` - A number of meridians. It has to be at least 3.
+/// * `rings` - A number of parts, which meridians are divided into. It has to be at least 2.
+pub fn create_uv_sphere(radius: Number, segments: usize, rings: usize) -> Result<Mesh, &'static str> {
+    if !radius.is_it_positive() {
+        return Err("The radius has to be positive!");
+    }
+    if segments < 3 || rings < 2 {
+        return Err("A sphere has to have at least 3 segments and 2 rings!");
+    }
+
+    let circle = get_circle_points(segments);
+    let zero = Number::zero();
+
+    let mut layers: Vec<Vec<Point>> = Vec::new();
+    for j in 1..rings {
+        let (cos_phi, sin_phi) = get_unit_circle_point(j, 2 * rings);
+        let r = &radius * &sin_phi;
+        let z = &radius * &cos_phi;
+        layers.push(circle.iter().map(|&(ref c, ref s)| Point::new(&r * c, &r * s, z.clone())).collect());
+    }
+
+    let top = Point::new(zero.clone(), zero.clone(), radius.clone());
+    let bottom = Point::new(zero.clone(), zero, -radius);
+
+    let mut mesh = Mesh::new();
+    for i in 0..segments {
+        let next = (i + 1) % segments;
+        add_triangle(&mut mesh, &top, &layers[0][i], &layers[0][next]);
+
+        for j in 0..(layers.len() - 1) {
+            add_quad(&mut mesh, &layers[j][i], &layers[j + 1][i], &layers[j + 1][next], &layers[j][next]);
+        }
+
+        let last = &layers[layers.len() - 1];
+        add_triangle(&mut mesh, &bottom, &last[next], &last[i]);
+    }
+    return Ok(mesh);
+}
+
+/// This function creates an icosphere with the center in the origin, i.e. a subdivided icosahedron.
+/// All vertices lie exactly on the sphere: they are calculated as inverse stereographic projections
+/// of rational points.
+/// # Arguments
+///
+/// * `radius` - A positive radius.
+/// * `subdivisions` - A number of subdivisions. Each subdivision splits every triangle into four ones.
+pub fn create_icosphere(radius: Number, subdivisions: usize) -> Result<Mesh, &'static str> {
+    if !radius.is_it_positive() {
+        return Err("The radius has to be positive!");
+    }
+
+    let t = (1. + 5f64.sqrt()) / 2.;
+    let mut vertices: Vec<[f64; 3]> = vec![
+        [-1., t, 0.], [1., t, 0.], [-1., -t, 0.], [1., -t, 0.],
+        [0., -1., t], [0., 1., t], [0., -1., -t], [0., 1., -t],
+        [t, 0., -1.], [t, 0., 1.], [-t, 0., -1.], [-t, 0., 1.]
+    ].into_iter().map(normalize).collect();
+
+    let mut faces: Vec<[usize; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1]
+    ];
+
+    for _ in 0..subdivisions {
+        let mut middles: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut new_faces: Vec<[usize; 3]> = Vec::new();
+
+        for f in faces.iter() {
+            let mut m = [0; 3];
+            for k in 0..3 {
+                let (a, b) = (f[k], f[(k + 1) % 3]);
+                let key = if a < b {(a, b)} else {(b, a)};
+                let index = match middles.get(&key) {
+                    Some(index) => *index,
+                    None => {
+                        let (va, vb) = (vertices[a], vertices[b]);
+                        vertices.push(normalize([va[0] + vb[0], va[1] + vb[1], va[2] + vb[2]]));
+                        vertices.len() - 1
+                    }
+                };
+                middles.insert(key, index);
+                m[k] = index;
+            }
+
+            new_faces.push([f[0], m[0], m[2]]);
+            new_faces.push([f[1], m[1], m[0]]);
+            new_faces.push([f[2], m[2], m[1]]);
+            new_faces.push([m[0], m[1], m[2]]);
+        }
+
+        faces = new_faces;
+    }
+
+    let points: Vec<Point> = vertices.iter().map(|v| {
+        let (x, y, z) = get_rational_sphere_point(v);
+        Point::new(&x * &radius, &y * &radius, &z * &radius)
+    }).collect();
+
+    let mut mesh = Mesh::new();
+    for f in faces.iter() {
+        add_triangle(&mut mesh, &points[f[0]], &points[f[1]], &points[f[2]]);
+    }
+    return Ok(mesh);
+}
+
+/// This function creates a cylinder. The axis of the cylinder is the z axis,
+/// the bottom base lies in the plane `z = 0` and the top base lies in the plane `z = height`.
+/// All vertices lie exactly on the cylinder.
+/// # Arguments
+///
+/// * `radius` - A positive radius.
+/// * `height` - A positive height.
+/// * `segments` - A number of side faces. It has to be at least 3.
+pub fn create_cylinder(radius: Number, height: Number, segments: usize) -> Result<Mesh, &'static str> {
+    return create_frustum(radius.clone(), radius, height, segments);
+}
+
+/// This function creates a truncated cone. The axis of the cone is the z axis,
+/// the bottom base lies in the plane `z = 0` and the top base lies in the plane `z = height`.
+/// If `top_radius` is zero, the cone has the apex.
+/// # Arguments
+///
+/// * `bottom_radius` - A positive radius of the bottom base.
+/// * `top_radius` - A non negative radius of the top base.
+/// * `height` - A positive height.
+/// * `segments` - A number of side faces. It has to be at least 3.
+pub fn create_cone(bottom_radius: Number, top_radius: Number, height: Number, segments: usize) -> Result<Mesh, &'static str> {
+    return create_frustum(bottom_radius, top_radius, height, segments);
+}
+
+/// This function creates a right regular prism. The axis of the prism is the z axis,
+/// the bottom base lies in the plane `z = 0` and the top base lies in the plane `z = height`.
+/// # Arguments
+///
+/// * `radius` - A positive radius of the circumscribed circle of bases.
+/// * `height` - A positive height.
+/// * `sides` - A number of sides of bases. It has to be at least 3.
+pub fn create_regular_prism(radius: Number, height: Number, sides: usize) -> Result<Mesh, &'static str> {
+    return create_frustum(radius.clone(), radius, height, sides);
+}
+
+/// This function creates a torus with the center in the origin. The axis of the torus is the z axis.
+/// # Arguments
+///
+/// * `major_radius` - A distance from the axis to the center of the tube.
+/// * `minor_radius` - A radius of the tube. It has to be positive and less than `major_radius`.
+/// * `major_segments` - A number of segments around the axis. It has to be at least 3.
+/// * `minor_segments` - A number of segments around the tube. It has to be at least 3.
+pub fn create_torus(
+    major_radius: Number,
+    minor_radius: Number,
+    major_segments: usize,
+    minor_segments: usize
+) -> Result<Mesh, &'static str> {
+    if !minor_radius.is_it_positive() || minor_radius >= major_radius {
+        return Err("The minor radius has to be positive and less than the major one!");
+    }
+    if major_segments < 3 || minor_segments < 3 {
+        return Err("A torus has to have at least 3 segments in each direction!");
+    }
+
+    let major_circle = get_circle_points(major_segments);
+    let minor_circle = get_circle_points(minor_segments);
+
+    let mut grid: Vec<Vec<Point>> = Vec::new();
+    for &(ref c_theta, ref s_theta) in major_circle.iter() {
+        grid.push(minor_circle.iter().map(|&(ref c_phi, ref s_phi)| {
+            let r = &major_radius + &(&minor_radius * c_phi);
+            Point::new(&r * c_theta, &r * s_theta, &minor_radius * s_phi)
+        }).collect());
+    }
+
+    let mut mesh = Mesh::new();
+    for i in 0..major_segments {
+        let next_i = (i + 1) % major_segments;
+        for j in 0..minor_segments {
+            let next_j = (j + 1) % minor_segments;
+            add_quad(&mut mesh, &grid[i][j], &grid[next_i][j], &grid[next_i][next_j], &grid[i][next_j]);
+        }
+    }
+    return Ok(mesh);
+}
+
+/// This function creates a wedge, i.e. a prism with a right triangle in the base.
+/// The right angle of the triangle is in the origin, the legs lie on the x and z axes,
+/// and the triangle is extruded along the y axis.
+/// # Arguments
+///
+/// * `dx` - A positive length of the leg along the x axis.
+/// * `dy` - A positive length of the wedge along the y axis.
+/// * `dz` - A positive length of the leg along the z axis.
+pub fn create_wedge(dx: Number, dy: Number, dz: Number) -> Result<Mesh, &'static str> {
+    if !dx.is_it_positive() || !dy.is_it_positive() || !dz.is_it_positive() {
+        return Err("The sizes of a wedge have to be positive!");
+    }
+
+    let zero = Number::zero();
+    let a = Point::new(zero.clone(), zero.clone(), zero.clone());
+    let b = Point::new(dx.clone(), zero.clone(), zero.clone());
+    let c = Point::new(zero.clone(), zero.clone(), dz.clone());
+    let a1 = Point::new(zero.clone(), dy.clone(), zero.clone());
+    let b1 = Point::new(dx, dy.clone(), zero.clone());
+    let c1 = Point::new(zero, dy, dz);
+
+    let mut mesh = Mesh::new();
+    add_triangle(&mut mesh, &a, &b, &c);
+    add_triangle(&mut mesh, &a1, &c1, &b1);
+    add_quad(&mut mesh, &a, &a1, &b1, &b);
+    add_quad(&mut mesh, &a, &c, &c1, &a1);
+    add_quad(&mut mesh, &b, &b1, &c1, &c);
+    return Ok(mesh);
+}
+
+fn create_frustum(bottom_radius: Number, top_radius: Number, height: Number, segments: usize) -> Result<Mesh, &'static str> {
+    if !bottom_radius.is_it_positive() || top_radius.is_it_negative() || !height.is_it_positive() {
+        return Err("The radiuses have to be non negative and the height has to be positive!");
+    }
+    if segments < 3 {
+        return Err("The number of segments has to be at least 3!");
+    }
+
+    let circle = get_circle_points(segments);
+    let zero = Number::zero();
+    let bottom_center = Point::new(zero.clone(), zero.clone(), zero.clone());
+    let top_center = Point::new(zero.clone(), zero.clone(), height.clone());
+
+    let bottom: Vec<Point> = circle.iter()
+        .map(|&(ref c, ref s)| Point::new(&bottom_radius * c, &bottom_radius * s, zero.clone()))
+        .collect();
+    let top: Vec<Point> = circle.iter()
+        .map(|&(ref c, ref s)| Point::new(&top_radius * c, &top_radius * s, height.clone()))
+        .collect();
+
+    let mut mesh = Mesh::new();
+    for i in 0..segments {
+        let next = (i + 1) % segments;
+        add_triangle(&mut mesh, &bottom_center, &bottom[next], &bottom[i]);
+        if top_radius.is_it_zero() {
+            add_triangle(&mut mesh, &bottom[i], &bottom[next], &top_center);
+        } else {
+            add_quad(&mut mesh, &bottom[i], &bottom[next], &top[next], &top[i]);
+            add_triangle(&mut mesh, &top_center, &top[i], &top[next]);
+        }
+    }
+    return Ok(mesh);
+}
+
+fn add_triangle(mesh: &mut Mesh, a: &Point, b: &Point, c: &Point) {
+    mesh.add_triangle(Triangle::new(vec![a.clone(), b.clone(), c.clone()]))
+        .expect("A generated triangle is degenerate!");
+}
+
+// четырехугольник abcd разбивается диагональю ac
+fn add_quad(mesh: &mut Mesh, a: &Point, b: &Point, c: &Point, d: &Point) {
+    add_triangle(mesh, a, b, c);
+    add_triangle(mesh, a, c, d);
+}
+
+// точки единичной окружности для углов 2*pi*k/n, k = 0..n
+pub(crate) fn get_circle_points(n: usize) -> Vec<(Number, Number)> {
+    return (0..n).map(|k| get_unit_circle_point(k, n)).collect();
+}
+
+// рациональная точка (cos, sin) единичной окружности, угол которой приближает 2*pi*k/n;
+// точки симметричны относительно осей и биссектрис координатных углов
+pub(crate) fn get_unit_circle_point(k: usize, n: usize) -> (Number, Number) {
+    let k = k % n;
+    // угол равен (pi/2)*(quadrant + r/n)
+    let quadrant = 4 * k / n;
+    let r = 4 * k % n;
+
+    let (c, s) = if 2 * r <= n {
+        get_first_octant_point(r, n)
+    } else {
+        let (c, s) = get_first_octant_point(n - r, n);
+        (s, c)
+    };
+
+    return match quadrant {
+        0 => (c, s),
+        1 => (-s, c),
+        2 => (-c, -s),
+        _ => (s, -c)
+    };
+}
+
+// точка для угла (pi/2)*r/n <= pi/4, вычисляемая через рациональный тангенс половинного угла
+fn get_first_octant_point(r: usize, n: usize) -> (Number, Number) {
+    if r == 0 {
+        return (Number::new(1.), Number::zero());
+    }
+
+    let angle = PI / 2. * (r as f64) / (n as f64);
+    let t = approximate_by_rational((angle / 2.).tan(), ANGLE_TOLERANCE / 2.);
+    let tt = &t * &t;
+    let denominator = &tt + &Number::new(1.);
+    return ((Number::new(1.) - &tt) / &denominator, (Number::new(2.) * &t) / &denominator);
+}
+
+// рациональная точка единичной сферы, близкая к v, получаемая обратной стереографической проекцией
+fn get_rational_sphere_point(v: &[f64; 3]) -> (Number, Number, Number) {
+    // проекция выполняется из полюса, наиболее удаленного от точки
+    let sign = if v[2] > 0. {1.} else {-1.};
+    let u = approximate_by_rational(v[0] / (1. + sign * v[2]), ANGLE_TOLERANCE);
+    let w = approximate_by_rational(v[1] / (1. + sign * v[2]), ANGLE_TOLERANCE);
+
+    let uw = &(&u * &u) + &(&w * &w);
+    let denominator = &uw + &Number::new(1.);
+    let z = (Number::new(1.) - uw) / &denominator * Number::new(sign);
+    return (Number::new(2.) * u / &denominator, Number::new(2.) * w / denominator, z);
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    return [v[0] / length, v[1] / length, v[2] / length];
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use modeling::*;
+    use modeling::generators::get_circle_points;
+    use primitives::fixtures::*;
+
+    fn check_volume(mesh: &Mesh, expected: f64, precision: f64) {
+        let volume = mesh.get_volume().convert_to_f64();
+        assert!((volume - expected).abs() < precision * expected);
+    }
+
+    #[test]
+    fn box_and_wedge() {
+        let mesh = create_box(&Point::new_from_f64(-1., 0., 1.), &Point::new_from_f64(1., 3., 2.)).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.get_volume(), Number::new(6.));
+        assert!(create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 0., 1.)).is_err());
+
+        let mesh = create_wedge(Number::new(1.), Number::new(2.), Number::new(3.)).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.num_of_triangles(), 8);
+        assert_eq!(mesh.get_volume(), Number::new(3.));
+    }
+
+    #[test]
+    fn spheres() {
+        let mesh = create_uv_sphere(Number::new(2.), 16, 8).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.num_of_triangles(), 2 * 16 * 7);
+        for p in mesh.get_points().values() {
+            assert_eq!(p.get_vector().length2(), Number::new(4.));
+        }
+        check_volume(&mesh, 4. / 3. * ::std::f64::consts::PI * 8., 0.1);
+
+        let mesh = create_icosphere(Number::new(1.), 2).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.num_of_triangles(), 20 * 16);
+        for p in mesh.get_points().values() {
+            assert_eq!(p.get_vector().length2(), Number::new(1.));
+        }
+        check_volume(&mesh, 4. / 3. * ::std::f64::consts::PI, 0.05);
+
+        assert!(create_uv_sphere(Number::new(1.), 2, 8).is_err());
+        assert!(create_icosphere(Number::zero(), 1).is_err());
+    }
+
+    #[test]
+    fn cylinders_and_cones() {
+        let mesh = create_cylinder(Number::new(1.), Number::new(2.), 32).unwrap();
+        check_closed_and_oriented(&mesh);
+        check_volume(&mesh, ::std::f64::consts::PI * 2., 0.01);
+
+        let mesh = create_cone(Number::new(1.), Number::zero(), Number::new(3.), 32).unwrap();
+        check_closed_and_oriented(&mesh);
+        check_volume(&mesh, ::std::f64::consts::PI, 0.01);
+
+        let mesh = create_cone(Number::new(2.), Number::new(1.), Number::new(1.), 24).unwrap();
+        check_closed_and_oriented(&mesh);
+
+        // a square prism with the circumradius 0.5 has the side sqrt(2)/2, so its base area is 0.5
+        let mesh = create_regular_prism(Number::new(0.5), Number::new(1.), 4).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.get_volume(), Number::new(0.5));
+
+        assert!(create_cylinder(Number::new(1.), Number::new(-1.), 8).is_err());
+        assert!(create_regular_prism(Number::new(1.), Number::new(1.), 2).is_err());
+    }
+
+    #[test]
+    fn torus() {
+        let mesh = create_torus(Number::new(3.), Number::new(1.), 24, 12).unwrap();
+        check_closed_and_oriented(&mesh);
+        check_volume(&mesh, 2. * ::std::f64::consts::PI * ::std::f64::consts::PI * 3., 0.1);
+        assert!(create_torus(Number::new(1.), Number::new(1.), 8, 8).is_err());
+    }
+
+    #[test]
+    fn circle_points_are_exact() {
+        for n in 3..20 {
+            let points = get_circle_points(n);
+            for &(ref c, ref s) in points.iter() {
+                assert_eq!(c * c + s * s, Number::new(1.));
+            }
+        }
+
+        let points = get_circle_points(8);
+        assert_eq!(points[2], (Number::zero(), Number::new(1.)));
+        assert_eq!(points[3], (-&points[1].1, points[1].0.clone()));
+        assert_eq!(points[5], (-&points[1].0, -&points[1].1));
+    }
+}
@@ -0,0 +1,22 @@
+pub mod generators;
+pub mod sweep;
+pub mod slicing;
+pub mod split;
+pub mod offset;
+pub mod convex_hull;
+/// This module contains Delaunay tetrahedralizations of solids bounded by closed meshes with quality refinement.
+/// Boundary triangles are either split by Steiner points (`tetrahedralize_conforming`) or kept, if they are present
+/// in the Delaunay tetrahedralization of vertices (`tetrahedralize_with_delaunay_boundary`). Boundary recovery by flips,
+/// which is needed to keep arbitrary boundary triangles, isn't implemented.
+pub mod tetrahedralization;
+
+pub use self::generators::{
+    create_box, create_uv_sphere, create_icosphere, create_cylinder, create_cone,
+    create_regular_prism, create_torus, create_wedge
+};
+pub use self::sweep::{extrude, extrude_with_twist, revolve};
+pub use self::slicing::{Section, Slicer, slice_mesh, slice_mesh_by_planes};
+pub use self::split::split_by_plane;
+pub use self::offset::{JoinType, offset_polygons};
+pub use self::convex_hull::{convex_hull, convex_hull_of_mesh};
+pub use self::tetrahedralization::{MIN_RADIUS_EDGE_RATIO, MAX_STEINER_POINTS, tetrahedralize_conforming, tetrahedralize_with_delaunay_boundary};
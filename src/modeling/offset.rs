@@ -0,0 +1,247 @@
+use primitives::*;
+use bool_op::resolve_positive_winding;
+
+/// Types of joins at vertices, where offset edges diverge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinType {
+    /// Edges are extended to their intersection. The parameter is the limit of the distance from a vertex
+    /// to the miter point in units of the offset distance, longer miters are squared off.
+    Miter(f64),
+    /// Edges are joined by an arc. The parameter is the maximum distance between the arc and its chords.
+    Round(f64),
+    /// Edges are joined by a segment perpendicular to the bisector at the offset distance from the vertex.
+    Square
+}
+
+/// This function offsets planar regions in the plane xy by the distance `delta`.
+/// Positive `delta` grows regions, negative `delta` shrinks them. Offset loops are built edge by edge
+/// and then the region with the positive winding number is extracted exactly, so self-overlaps,
+/// merged regions and vanished holes are resolved into a clean forest. Offset points are exact for
+/// axis-aligned edges and miter joins, other directions are normalized in floating point.
+/// # Arguments
+///
+/// * `forest` - Regions: children of a polygon are holes, children of holes are islands.
+/// * `delta` - The offset distance.
+/// * `join` - The type of joins.
+pub fn offset_polygons(forest: &[PolygonTreeNode], delta: Number, join: JoinType) -> Result<Vec<PolygonTreeNode>, &'static str> {
+    match join {
+        JoinType::Miter(limit) if !(limit >= 1.) => return Err("Miter limit has to be at least 1!"),
+        JoinType::Round(tolerance) if !(tolerance > 0.) => return Err("Arc tolerance has to be positive!"),
+        _ => ()
+    }
+
+    let mut loops: Vec<Vec<Point>> = Vec::new();
+    let mut nodes: Vec<(&PolygonTreeNode, usize)> = forest.iter().map(|node| (node, 0)).collect();
+    while let Some((node, depth)) = nodes.pop() {
+        let mut polygon = node.get_cur_polygon();
+        if polygon.signed_area().is_it_zero() {
+            return Err("Polygon is degenerate!");
+        }
+        // внешние контуры против часовой стрелки, дырки по часовой
+        if polygon.signed_area().is_it_positive() != (depth % 2 == 0) {
+            polygon.reverse_order();
+        }
+        let offset_loop = offset_loop(polygon.get_points_ref(), &delta, join);
+        if offset_loop.len() >= 3 {
+            loops.push(offset_loop);
+        }
+        nodes.extend(node.get_children_ref().iter().map(|child| (child, depth + 1)));
+    }
+
+    return resolve_positive_winding(&loops);
+}
+
+fn offset_loop(points: &Vec<Point>, delta: &Number, join: JoinType) -> Vec<Point> {
+    let mut ps = points.clone();
+    ps.dedup();
+    if ps.len() > 1 && ps.first() == ps.last() {
+        ps.pop();
+    }
+    let n = ps.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    // единичные направления ребер и внешние (правые) нормали
+    let directions: Vec<(f64, f64)> = (0..n).map(|i| {
+        let e: Vector = &ps[(i + 1) % n] - &ps[i];
+        let (x, y) = (e.x.convert_to_f64(), e.y.convert_to_f64());
+        let len = (x * x + y * y).sqrt();
+        (x / len, y / len)
+    }).collect();
+    let normals: Vec<(f64, f64)> = directions.iter().map(|&(x, y)| (y, -x)).collect();
+
+    let d = delta.clone().convert_to_f64();
+    let shift = |p: &Point, v: (f64, f64), scale: &Number| -> Point {
+        return Point::new(
+            &p.x + &(Number::new(v.0) * scale.clone()),
+            &p.y + &(Number::new(v.1) * scale.clone()),
+            p.z.clone()
+        );
+    };
+
+    let mut res: Vec<Point> = Vec::new();
+    for i in 0..n {
+        let p = &ps[i];
+        let (t1, t2) = (directions[(i + n - 1) % n], directions[i]);
+        let (n1, n2) = (normals[(i + n - 1) % n], normals[i]);
+        let cross = t1.0 * t2.1 - t1.1 * t2.0;
+        let dot = t1.0 * t2.0 + t1.1 * t2.1;
+
+        if cross == 0. && dot > 0. {
+            res.push(shift(p, n1, delta));
+            continue;
+        }
+
+        if cross * d < 0. || (cross == 0. && d == 0.) {
+            // смещенные ребра пересекаются: вершина добавляется, петля удаляется при разрешении
+            res.push(shift(p, n1, delta));
+            res.push(p.clone());
+            res.push(shift(p, n2, delta));
+            continue;
+        }
+
+        match join {
+            JoinType::Miter(limit) if cross != 0. && 2. / (1. + dot) <= limit * limit => {
+                let m = (n1.0 + n2.0, n1.1 + n2.1);
+                let scale = delta.clone() / Number::new(1. + dot);
+                res.push(shift(p, m, &scale));
+            },
+            JoinType::Round(tolerance) => {
+                let angle = if cross == 0. {std::f64::consts::PI * d.signum()} else {cross.atan2(dot)};
+                let step = if tolerance >= d.abs() {
+                    std::f64::consts::FRAC_PI_2
+                } else {
+                    2. * (1. - tolerance / d.abs()).acos()
+                };
+                let k = ((angle.abs() / step).ceil() as usize).max(1);
+                res.push(shift(p, n1, delta));
+                for j in 1..k {
+                    let a = angle * j as f64 / k as f64;
+                    let v = (n1.0 * a.cos() - n1.1 * a.sin(), n1.0 * a.sin() + n1.1 * a.cos());
+                    res.push(shift(p, v, delta));
+                }
+                res.push(shift(p, n2, delta));
+            },
+            _ => {
+                // квадратное соединение: срез на расстоянии delta от вершины перпендикулярно биссектрисе
+                let b = if cross == 0. {
+                    t1
+                } else {
+                    let (x, y) = (n1.0 + n2.0, n1.1 + n2.1);
+                    let len = (x * x + y * y).sqrt();
+                    (x / len, y / len)
+                };
+                let s1 = (1. - (n1.0 * b.0 + n1.1 * b.1)) / (t1.0 * b.0 + t1.1 * b.1);
+                let s2 = (1. - (n2.0 * b.0 + n2.1 * b.1)) / -(t2.0 * b.0 + t2.1 * b.1);
+                res.push(shift(p, (n1.0 + s1 * t1.0, n1.1 + s1 * t1.1), delta));
+                res.push(shift(p, (n2.0 - s2 * t2.0, n2.1 - s2 * t2.1), delta));
+            }
+        }
+    }
+
+    res.dedup();
+    if res.len() > 1 && res.first() == res.last() {
+        res.pop();
+    }
+    return res;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use modeling::*;
+
+    fn rectangle(x0: f64, y0: f64, x1: f64, y1: f64) -> PolygonTreeNode {
+        let points = vec![
+            Point::new_from_f64(x0, y0, 0.),
+            Point::new_from_f64(x1, y0, 0.),
+            Point::new_from_f64(x1, y1, 0.),
+            Point::new_from_f64(x0, y1, 0.)
+        ];
+        return PolygonTreeNode::new(Polygon::new(points, Vector::new_from_f64(0., 0., 1.)));
+    }
+
+    #[test]
+    fn offset_of_square_with_different_joins() {
+        let square = vec![rectangle(0., 0., 2., 2.)];
+
+        let miter = offset_polygons(&square, Number::new(1.), JoinType::Miter(2.)).unwrap();
+        assert_eq!(miter.len(), 1);
+        assert_eq!(miter[0].get_cur_polygon().get_points_ref().len(), 4);
+        assert_eq!(forest_area(&miter), Number::new(16.));
+
+        // предел меньше sqrt(2): углы срезаются
+        let limited = offset_polygons(&square, Number::new(1.), JoinType::Miter(1.2)).unwrap();
+        assert_eq!(limited[0].get_cur_polygon().get_points_ref().len(), 8);
+
+        let square_join = offset_polygons(&square, Number::new(1.), JoinType::Square).unwrap();
+        assert_eq!(square_join[0].get_cur_polygon().get_points_ref().len(), 8);
+        let expected = 12. + 4. * (2. * 2f64.sqrt() - 2.);
+        assert!((forest_area(&square_join).convert_to_f64() - expected).abs() < 1e-9);
+
+        let round = offset_polygons(&square, Number::new(1.), JoinType::Round(0.001)).unwrap();
+        assert_eq!(round.len(), 1);
+        let a = forest_area(&round).convert_to_f64();
+        assert!(a < 12. + std::f64::consts::PI && a > 12. + std::f64::consts::PI - 0.01);
+    }
+
+    #[test]
+    fn shrinking() {
+        let square = vec![rectangle(0., 0., 4., 4.)];
+        let shrunk = offset_polygons(&square, Number::new(-1.), JoinType::Round(0.01)).unwrap();
+        assert_eq!(shrunk.len(), 1);
+        assert_eq!(shrunk[0].get_cur_polygon().get_points_ref().len(), 4);
+        assert_eq!(forest_area(&shrunk), Number::new(4.));
+
+        assert!(offset_polygons(&square, Number::new(-3.), JoinType::Square).unwrap().is_empty());
+    }
+
+    #[test]
+    fn overlaps_are_resolved() {
+        // два прямоугольника сливаются
+        let forest = vec![rectangle(0., 0., 1., 1.), rectangle(1.5, 0., 2.5, 1.)];
+        let res = offset_polygons(&forest, Number::new(0.5), JoinType::Miter(2.)).unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(res[0].get_children_ref().is_empty());
+        assert_eq!(forest_area(&res), Number::new(7.));
+
+        // дырка сужается, а затем исчезает
+        let mut ring = rectangle(0., 0., 6., 6.);
+        ring.add_child(rectangle(2., 2., 4., 4.));
+        let ring = vec![ring];
+        let res = offset_polygons(&ring, Number::new(0.5), JoinType::Miter(2.)).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].get_children_ref().len(), 1);
+        assert_eq!(forest_area(&res), Number::new(48.));
+
+        let res = offset_polygons(&ring, Number::new(1.5), JoinType::Miter(2.)).unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(res[0].get_children_ref().is_empty());
+        assert_eq!(forest_area(&res), Number::new(81.));
+
+        // кольцо распадается при сжатии узкого места
+        let u = PolygonTreeNode::new(Polygon::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(5., 0., 0.),
+            Point::new_from_f64(5., 3., 0.),
+            Point::new_from_f64(3., 3., 0.),
+            Point::new_from_f64(3., 0.5, 0.),
+            Point::new_from_f64(2., 0.5, 0.),
+            Point::new_from_f64(2., 3., 0.),
+            Point::new_from_f64(0., 3., 0.)
+        ], Vector::new_from_f64(0., 0., 1.)));
+        let res = offset_polygons(&vec![u], Number::new(-0.5), JoinType::Miter(2.)).unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(forest_area(&res), Number::new(4.));
+    }
+
+    #[test]
+    fn wrong_parameters() {
+        let square = vec![rectangle(0., 0., 1., 1.)];
+        assert!(offset_polygons(&square, Number::new(1.), JoinType::Miter(0.5)).is_err());
+        assert!(offset_polygons(&square, Number::new(1.), JoinType::Round(0.)).is_err());
+    }
+}
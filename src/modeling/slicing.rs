@@ -0,0 +1,367 @@
+use primitives::*;
+use intersect::tuple_iter::TreeAABT;
+use primitives::polygon::build_trees;
+use std::collections::BTreeMap;
+
+/// This structure represents a section of a mesh by a plane.
+#[derive(Clone, Debug)]
+pub struct Section {
+    /// The cutting plane.
+    pub plane: Plane,
+    /// Trees of nested closed contours. Roots are outer boundaries, their children are holes and so on.
+    /// Outer boundaries are counterclockwise and holes are clockwise looking against the normal of the plane.
+    pub contours: Vec<PolygonTreeNode>,
+    /// Polylines, which can't be closed. They appear if the mesh isn't closed.
+    pub open_polylines: Vec<Vec<Point>>
+}
+
+impl Section {
+    /// This method returns true if all found contours are closed.
+    pub fn is_closed(&self) -> bool {
+        return self.open_polylines.is_empty();
+    }
+}
+
+/// This structure cuts a mesh by planes.
+/// The tree of bounding boxes is built once, so it's preferable to use it for a lot of planes.
+///
+/// A vertex lying on the plane is treated as if it were slightly above the plane,
+/// so faces lying on the plane are ignored and the section consists of the limit contours of the part below the plane.
+pub struct Slicer<'a> {
+    mesh: &'a Mesh,
+    tree: Option<TreeAABT>
+}
+
+impl<'a> Slicer<'a> {
+    /// This method creates `Slicer` and builds the tree of bounding boxes for the `mesh`.
+    /// # Arguments
+    ///
+    /// * `mesh` - A mesh to cut.
+    pub fn new(mesh: &'a Mesh) -> Slicer<'a> {
+        let tree = if mesh.num_of_triangles() > 0 {
+            Some(TreeAABT::new(mesh))
+        } else {
+            None
+        };
+        return Slicer { mesh, tree };
+    }
+
+    /// This method cuts the mesh by the `plane`.
+    /// It returns an error if the normal of the plane is zero.
+    /// # Arguments
+    ///
+    /// * `plane` - A cutting plane.
+    pub fn slice(&self, plane: &Plane) -> Result<Section, &'static str> {
+        if plane.normal.length2().is_it_zero() {
+            return Err("The normal of a cutting plane has to be non zero!");
+        }
+
+        let mut segments: Vec<(Point, Point)> = Vec::new();
+        if let Some(ref tree) = self.tree {
+            for it in tree.find_triangles(|b| b.does_plane_intersect_box(plane)) {
+                if let Some(s) = intersect_triangle_with_plane(&self.mesh.get_triangle(it), plane) {
+                    segments.push(s);
+                }
+            }
+        }
+
+        let (loops, open_polylines) = link_segments(segments);
+        let loops: Vec<Vec<Point>> = loops.into_iter()
+            .map(|l| simplify(l, true))
+            .filter(|l| l.len() >= 3)
+            .collect();
+        let open_polylines = open_polylines.into_iter().map(|l| simplify(l, false)).collect();
+        return Ok(Section {
+            plane: plane.clone(),
+            contours: build_trees(loops, &plane.normal),
+            open_polylines
+        });
+    }
+}
+
+/// This function cuts the `mesh` by the `plane`.
+/// Use `Slicer` to cut a mesh by a lot of planes.
+/// # Arguments
+///
+/// * `mesh` - A mesh to cut.
+/// * `plane` - A cutting plane.
+pub fn slice_mesh(mesh: &Mesh, plane: &Plane) -> Result<Section, &'static str> {
+    return Slicer::new(mesh).slice(plane);
+}
+
+/// This function cuts the `mesh` by each plane of the stack.
+/// # Arguments
+///
+/// * `mesh` - A mesh to cut.
+/// * `planes` - Cutting planes.
+pub fn slice_mesh_by_planes(mesh: &Mesh, planes: &[Plane]) -> Result<Vec<Section>, &'static str> {
+    let slicer = Slicer::new(mesh);
+    let mut res: Vec<Section> = Vec::new();
+    for plane in planes.iter() {
+        res.push(slicer.slice(plane)?);
+    }
+    return Ok(res);
+}
+
+// отрезок пересечения направлен так, что материал (для внешней нормали) остается слева
+pub(crate) fn intersect_triangle_with_plane(tr: &Triangle, plane: &Plane) -> Option<(Point, Point)> {
+    let ps = [tr.get_ref(0), tr.get_ref(1), tr.get_ref(2)];
+    let values: Vec<Number> = ps.iter().map(|p| plane.normal.dot_product(&(*p - &plane.point))).collect();
+
+    let mut points: Vec<Point> = Vec::new();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        if values[i].is_it_negative() == values[j].is_it_negative() {
+            continue;
+        }
+
+        let t = &values[i] / &(&values[i] - &values[j]);
+        points.push(ps[i] + &(&(ps[j] - ps[i]) * t));
+    }
+
+    if points.len() != 2 {
+        return None;
+    }
+
+    let b = points.pop().unwrap();
+    let a = points.pop().unwrap();
+    let tr_normal = (ps[1] - ps[0]).cross_product(&(ps[2] - ps[0]));
+    let orientation = (&b - &a).dot_product(&plane.normal.cross_product(&tr_normal));
+    match 1 {
+        _ if orientation.is_it_positive() => return Some((a, b)),
+        _ if orientation.is_it_negative() => return Some((b, a)),
+        _ => return None
+    }
+}
+
+// связывает направленные отрезки в замкнутые контуры и незамкнутые ломаные
+pub(crate) fn link_segments(segments: Vec<(Point, Point)>) -> (Vec<Vec<Point>>, Vec<Vec<Point>>) {
+    let mut outgoing: BTreeMap<Point, Vec<usize>> = BTreeMap::new();
+    let mut balance: BTreeMap<Point, i64> = BTreeMap::new();
+    for (i, &(ref org, ref dest)) in segments.iter().enumerate() {
+        outgoing.entry(org.clone()).or_insert(Vec::new()).push(i);
+        *balance.entry(org.clone()).or_insert(0) += 1;
+        *balance.entry(dest.clone()).or_insert(0) -= 1;
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut walk = |start: usize, used: &mut Vec<bool>| -> Vec<Point> {
+        let mut points = vec![segments[start].0.clone()];
+        let mut cur = start;
+        loop {
+            used[cur] = true;
+            let dest = segments[cur].1.clone();
+            let mut next: Option<usize> = None;
+            if let Some(candidates) = outgoing.get_mut(&dest) {
+                while let Some(i) = candidates.pop() {
+                    if !used[i] {
+                        next = Some(i);
+                        break;
+                    }
+                }
+            }
+            points.push(dest);
+
+            match next {
+                Some(i) => cur = i,
+                None => return points
+            }
+        }
+    };
+
+    let mut loops: Vec<Vec<Point>> = Vec::new();
+    let mut open_polylines: Vec<Vec<Point>> = Vec::new();
+
+    // незамкнутые ломаные начинаются в точках, из которых выходит больше отрезков, чем входит
+    let starts: Vec<(Point, i64)> = balance.into_iter().filter(|&(_, b)| b > 0).collect();
+    for (p, b) in starts {
+        for _ in 0..b {
+            let start = segments.iter().enumerate()
+                .position(|(i, s)| !used[i] && s.0 == p);
+            if let Some(start) = start {
+                open_polylines.push(walk(start, &mut used));
+            }
+        }
+    }
+
+    for i in 0..segments.len() {
+        if used[i] {
+            continue;
+        }
+
+        let mut points = walk(i, &mut used);
+        if points[0] == points[points.len() - 1] {
+            points.pop();
+            loops.extend(split_loop(points));
+        } else {
+            open_polylines.push(points);
+        }
+    }
+
+    return (loops, open_polylines);
+}
+
+// разбивает контур, проходящий несколько раз через одну точку, на простые контуры
+fn split_loop(points: Vec<Point>) -> Vec<Vec<Point>> {
+    let mut res: Vec<Vec<Point>> = Vec::new();
+    let mut stack: Vec<Point> = Vec::new();
+    let mut positions: BTreeMap<Point, usize> = BTreeMap::new();
+    for p in points {
+        if let Some(&position) = positions.get(&p) {
+            let sub_loop: Vec<Point> = stack.drain(position..).collect();
+            for q in sub_loop.iter() {
+                positions.remove(q);
+            }
+            res.push(sub_loop);
+        }
+        positions.insert(p.clone(), stack.len());
+        stack.push(p);
+    }
+    res.push(stack);
+    return res;
+}
+
+// удаляет совпадающие соседние точки и точки, лежащие на одной прямой с соседними
+fn simplify(mut points: Vec<Point>, closed: bool) -> Vec<Point> {
+    let mut changed = true;
+    while changed && points.len() > 2 {
+        changed = false;
+        let n = points.len();
+        let (first, last) = if closed {(0, n)} else {(1, n - 1)};
+        for i in first..last {
+            let prev = &points[(i + n - 1) % n];
+            let next = &points[(i + 1) % n];
+            let cur = &points[i];
+            if (cur - prev).cross_product(&(next - cur)).length2().is_it_zero() {
+                points.remove(i);
+                changed = true;
+                break;
+            }
+        }
+    }
+    return points;
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use modeling::*;
+
+    fn z_plane(z: f64) -> Plane {
+        return Plane::new(Vector::new_from_f64(0., 0., 1.), Point::new_from_f64(0., 0., z));
+    }
+
+    #[test]
+    fn box_section() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 1., 1.)).unwrap();
+        let section = slice_mesh(&mesh, &z_plane(0.5)).unwrap();
+        assert!(section.is_closed());
+        assert_eq!(section.contours.len(), 1);
+
+        let polygon = section.contours[0].get_cur_polygon();
+        assert_eq!(polygon.get_points_ref().len(), 4);
+        assert_eq!(polygon.signed_area(), Number::new(2.));
+        assert!(polygon.get_points_ref().iter().all(|p| p.z == Number::new(0.5)));
+
+        // грани, лежащие в плоскости
+        let section = slice_mesh(&mesh, &z_plane(1.)).unwrap();
+        assert_eq!(section.contours.len(), 1);
+        assert_eq!(section.contours[0].get_cur_polygon().signed_area(), Number::new(2.));
+
+        let section = slice_mesh(&mesh, &z_plane(0.)).unwrap();
+        assert!(section.contours.is_empty() && section.is_closed());
+        let section = slice_mesh(&mesh, &z_plane(3.)).unwrap();
+        assert!(section.contours.is_empty());
+
+        let plane = Plane::new(Vector::new_from_f64(0., 0., 0.), Point::new_from_f64(0., 0., 0.));
+        assert!(slice_mesh(&mesh, &plane).is_err());
+    }
+
+    #[test]
+    fn nested_contours() {
+        let mut tree = PolygonTreeNode::new(polygon(vec![(-2., -2.), (2., -2.), (2., 2.), (-2., 2.)]));
+        let mut hole = PolygonTreeNode::new(polygon(vec![(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)]));
+        hole.add_child(PolygonTreeNode::new(polygon(vec![(-0.5, -0.5), (0.5, -0.5), (0., 0.5)])));
+        tree.add_child(hole);
+        let mesh = extrude_with_twist(&tree, Number::new(2.), 0., Number::new(1.), 2).unwrap();
+
+        let planes = vec![z_plane(0.5), z_plane(1.), z_plane(1.5)];
+        let sections = slice_mesh_by_planes(&mesh, &planes).unwrap();
+        assert_eq!(sections.len(), 3);
+        for section in sections.iter() {
+            assert!(section.is_closed());
+            assert_eq!(section.contours.len(), 1);
+            let outer = &section.contours[0];
+            assert_eq!(outer.get_cur_polygon().signed_area(), Number::new(16.));
+            assert_eq!(outer.get_children_ref().len(), 1);
+
+            let hole = &outer.get_children_ref()[0];
+            assert_eq!(hole.get_cur_polygon().signed_area(), Number::new(-4.));
+            assert_eq!(hole.get_children_ref().len(), 1);
+            assert_eq!(hole.get_children_ref()[0].get_cur_polygon().signed_area(), Number::new(0.5));
+        }
+    }
+
+    #[test]
+    fn inclined_plane() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 1., 1.)).unwrap();
+        let plane = Plane::new(Vector::new_from_f64(1., 1., 1.), Point::new_from_f64(0.5, 0.5, 0.5));
+        let section = slice_mesh(&mesh, &plane).unwrap();
+        assert!(section.is_closed());
+        assert_eq!(section.contours.len(), 1);
+
+        // правильный шестиугольник с вершинами в серединах ребер куба
+        let polygon = section.contours[0].get_cur_polygon();
+        let ps = polygon.get_points_ref();
+        assert_eq!(ps.len(), 6);
+        for i in 0..6 {
+            let (a, b) = (&ps[i], &ps[(i + 1) % 6]);
+            assert!(plane.does_it_contain_point(a));
+            let n = (a - &Point::new_from_f64(0.5, 0.5, 0.5)).cross_product(&(b - a));
+            assert!(n.dot_product(&plane.normal).is_it_positive());
+        }
+    }
+
+    #[test]
+    fn touching_boxes() {
+        let mut mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 1., 1.)).unwrap();
+        let other = create_box(&Point::new_from_f64(1., 1., 0.), &Point::new_from_f64(2., 2., 1.)).unwrap();
+        for it in other.get_it_iterator() {
+            mesh.add_triangle(other.get_triangle(it)).unwrap();
+        }
+
+        let section = slice_mesh(&mesh, &z_plane(0.5)).unwrap();
+        assert!(section.is_closed());
+        let mut area = Number::zero();
+        for tree in section.contours.iter() {
+            assert!(tree.get_children_ref().is_empty());
+            area = area + tree.get_cur_polygon().signed_area();
+        }
+        assert_eq!(area, Number::new(2.));
+    }
+
+    #[test]
+    fn open_mesh() {
+        let mut mesh = Mesh::new();
+        mesh.add_triangle(Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 0., 1.)
+        ])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(1., 1., 0.),
+            Point::new_from_f64(0., 0., 1.)
+        ])).unwrap();
+
+        let section = slice_mesh(&mesh, &z_plane(0.5)).unwrap();
+        assert!(!section.is_closed());
+        assert!(section.contours.is_empty());
+        assert_eq!(section.open_polylines.len(), 1);
+        assert_eq!(section.open_polylines[0].len(), 3);
+
+        let section = slice_mesh(&Mesh::new(), &z_plane(0.5)).unwrap();
+        assert!(section.is_closed() && section.contours.is_empty());
+    }
+}
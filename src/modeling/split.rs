@@ -0,0 +1,297 @@
+use primitives::*;
+use primitives::polygon::{build_trees, get_projection_axis, project};
+use modeling::slicing::{intersect_triangle_with_plane, link_segments};
+use triangulation::ear_clipping_triangulation::triangulate_polygon_with_holes;
+use std::collections::BTreeMap;
+
+/// This function cuts a closed mesh by the plane into two closed halves.
+/// It returns the half lying against the normal of the plane and the half lying along the normal.
+/// Triangles crossing the plane are clipped and the cut contours are closed by triangulated caps,
+/// so the halves share the cap points. A half is empty if the mesh lies on one side of the plane.
+/// Faces lying on the plane belong to no half, a cap is built instead of them.
+/// # Arguments
+///
+/// * `mesh` - A closed mesh.
+/// * `plane` - A cutting plane.
+pub fn split_by_plane(mesh: &Mesh, plane: &Plane) -> Result<(Mesh, Mesh), &'static str> {
+    if plane.normal.length2().is_it_zero() {
+        return Err("The normal of a cutting plane has to be non zero!");
+    }
+
+    let opposite_plane = Plane::new(&plane.normal * Number::new(-1.), plane.point.clone());
+    let lower_half = get_lower_half(mesh, plane)?;
+    let upper_half = get_lower_half(mesh, &opposite_plane)?;
+    return Ok((lower_half, upper_half));
+}
+
+// часть сетки, лежащая по другую сторону от нормали, с крышкой;
+// точки на плоскости считаются лежащими чуть выше нее
+fn get_lower_half(mesh: &Mesh, plane: &Plane) -> Result<Mesh, &'static str> {
+    let mut res = Mesh::new();
+    let mut segments: Vec<(Point, Point)> = Vec::new();
+
+    for it in mesh.get_it_iterator() {
+        let tr = mesh.get_triangle(it);
+        let ps = tr.get_points_ref();
+        let values: Vec<Number> = ps.iter().map(|p| plane.normal.dot_product(&(p - &plane.point))).collect();
+
+        if !values.iter().any(|v| v.is_it_negative()) {
+            continue;
+        }
+        if !values.iter().any(|v| v.is_it_positive()) {
+            add_triangle(&mut res, &ps[0], &ps[1], &ps[2])?;
+            if let Some(s) = intersect_triangle_with_plane(&tr, plane) {
+                segments.push(s);
+            }
+            continue;
+        }
+
+        // отсекаем часть треугольника, сохраняя порядок обхода
+        let mut polygon: Vec<Point> = Vec::new();
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+            if values[i].is_it_negative() {
+                polygon.push(ps[i].clone());
+            }
+            if values[i].is_it_negative() != values[j].is_it_negative() {
+                let t = &values[i] / &(&values[i] - &values[j]);
+                polygon.push(&ps[i] + &(&(&ps[j] - &ps[i]) * t));
+            }
+        }
+        for i in 1..(polygon.len() - 1) {
+            add_triangle(&mut res, &polygon[0], &polygon[i], &polygon[i + 1])?;
+        }
+
+        if let Some(s) = intersect_triangle_with_plane(&tr, plane) {
+            segments.push(s);
+        }
+    }
+
+    let (loops, open_polylines) = link_segments(segments);
+    if !open_polylines.is_empty() {
+        return Err("The mesh has to be closed!");
+    }
+
+    // точки контуров, лежащие на одной прямой с соседними, не участвуют в триангуляции крышки,
+    // но сохраняются, чтобы крышка была согласована с боковыми треугольниками
+    let mut edge_to_inner_points: BTreeMap<(Point, Point), Vec<Point>> = BTreeMap::new();
+    let mut corner_loops: Vec<Vec<Point>> = Vec::new();
+    for l in loops.into_iter() {
+        if let Some(corners) = remove_straight_points(l, &mut edge_to_inner_points) {
+            corner_loops.push(corners);
+        }
+    }
+
+    let axis = get_projection_axis(&plane.normal);
+    let mut projection_to_point: BTreeMap<Point, Point> = BTreeMap::new();
+    let mut to_2d = |polygon: Polygon| -> Polygon {
+        let points: Vec<Point> = polygon.get_points_ref().iter().map(|p| {
+            let (u, v) = project(p, axis);
+            let projection = Point::new(u, v, Number::zero());
+            projection_to_point.insert(projection.clone(), p.clone());
+            projection
+        }).collect();
+        return Polygon::new(points, Vector::new_from_f64(0., 0., 1.));
+    };
+
+    let mut cap: Vec<Triangle> = Vec::new();
+    let mut nodes: Vec<(PolygonTreeNode, usize)> = build_trees(corner_loops, &plane.normal).into_iter()
+        .map(|tree| (tree, 0))
+        .collect();
+    while let Some((node, depth)) = nodes.pop() {
+        if depth % 2 == 0 {
+            let boundary = to_2d(node.get_cur_polygon());
+            let holes: Vec<Polygon> = node.get_child_polygons().into_iter().map(|h| to_2d(h)).collect();
+            cap.extend(triangulate_polygon_with_holes(boundary, holes));
+        }
+        for child in node.split_tree() {
+            nodes.push((child, depth + 1));
+        }
+    }
+
+    for t in cap.iter() {
+        let mut ps: Vec<Point> = t.get_points_ref().iter().map(|p| projection_to_point[p].clone()).collect();
+        let normal = (&ps[1] - &ps[0]).cross_product(&(&ps[2] - &ps[0]));
+        if normal.dot_product(&plane.normal).is_it_negative() {
+            ps.swap(1, 2);
+        }
+        add_cap_triangle(&mut res, &ps, &edge_to_inner_points)?;
+    }
+
+    return Ok(res);
+}
+
+// удаляет из контура точки, в которых он не поворачивает;
+// для каждого ребра оставшегося контура запоминаются удаленные точки в порядке от меньшего конца к большему
+fn remove_straight_points(
+    points: Vec<Point>,
+    edge_to_inner_points: &mut BTreeMap<(Point, Point), Vec<Point>>
+) -> Option<Vec<Point>> {
+    let n = points.len();
+    let is_corner = |i: usize| -> bool {
+        let prev = &points[(i + n - 1) % n];
+        let next = &points[(i + 1) % n];
+        let (a, b) = (&points[i] - prev, next - &points[i]);
+        return !a.cross_product(&b).length2().is_it_zero() || !a.dot_product(&b).is_it_positive();
+    };
+
+    let first = match (0..n).position(|i| is_corner(i)) {
+        Some(first) => first,
+        None => return None
+    };
+
+    let mut corners: Vec<Point> = Vec::new();
+    let mut inner_points: Vec<Point> = Vec::new();
+    for k in 0..(n + 1) {
+        let i = (first + k) % n;
+        if k != 0 && k != n && !is_corner(i) {
+            inner_points.push(points[i].clone());
+            continue;
+        }
+
+        if let Some(last) = corners.last() {
+            if !inner_points.is_empty() {
+                let (a, b) = (last.clone(), points[i].clone());
+                if a < b {
+                    edge_to_inner_points.insert((a, b), inner_points.clone());
+                } else {
+                    inner_points.reverse();
+                    edge_to_inner_points.insert((b, a), inner_points.clone());
+                }
+                inner_points.clear();
+            }
+        }
+        if k != n {
+            corners.push(points[i].clone());
+        }
+    }
+
+    if corners.len() < 3 {
+        return None;
+    }
+    return Some(corners);
+}
+
+// треугольник крышки, на ребрах которого лежат точки контура, разбивается веером из центра масс
+fn add_cap_triangle(
+    mesh: &mut Mesh,
+    ps: &Vec<Point>,
+    edge_to_inner_points: &BTreeMap<(Point, Point), Vec<Point>>
+) -> Result<(), &'static str> {
+    let mut boundary: Vec<Point> = Vec::new();
+    for i in 0..3 {
+        let (a, b) = (&ps[i], &ps[(i + 1) % 3]);
+        boundary.push(a.clone());
+        if a < b {
+            if let Some(inner_points) = edge_to_inner_points.get(&(a.clone(), b.clone())) {
+                boundary.extend(inner_points.iter().cloned());
+            }
+        } else if let Some(inner_points) = edge_to_inner_points.get(&(b.clone(), a.clone())) {
+            boundary.extend(inner_points.iter().rev().cloned());
+        }
+    }
+
+    if boundary.len() == 3 {
+        return add_triangle(mesh, &ps[0], &ps[1], &ps[2]);
+    }
+
+    let third = Number::new(1.) / Number::new(3.);
+    let center = &(&ps[0] + &(&(&ps[1] - &ps[0]) * third.clone())) + &(&(&ps[2] - &ps[0]) * third);
+    let n = boundary.len();
+    for i in 0..n {
+        add_triangle(mesh, &center, &boundary[i], &boundary[(i + 1) % n])?;
+    }
+    return Ok(());
+}
+
+fn add_triangle(mesh: &mut Mesh, a: &Point, b: &Point, c: &Point) -> Result<(), &'static str> {
+    return match mesh.add_triangle(Triangle::new(vec![a.clone(), b.clone(), c.clone()])) {
+        Ok(_) => Ok(()),
+        Err(_) => Err("A degenerate triangle is generated, the mesh is probably invalid!")
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use modeling::*;
+    use primitives::fixtures::*;
+
+    fn plane(normal: (f64, f64, f64), point: (f64, f64, f64)) -> Plane {
+        return Plane::new(
+            Vector::new_from_f64(normal.0, normal.1, normal.2),
+            Point::new_from_f64(point.0, point.1, point.2)
+        );
+    }
+
+    #[test]
+    fn split_box() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 1., 1.)).unwrap();
+        let (lower, upper) = split_by_plane(&mesh, &plane((0., 0., 1.), (0., 0., 0.25))).unwrap();
+        check_closed_and_oriented(&lower);
+        check_closed_and_oriented(&upper);
+        assert_eq!(lower.get_volume(), Number::new(0.5));
+        assert_eq!(upper.get_volume(), Number::new(1.5));
+        assert!(lower.get_it_iterator().into_iter()
+            .all(|it| lower.get_triangle(it).get_points_ref().iter().all(|p| p.z <= Number::new(0.25))));
+
+        let (lower, upper) = split_by_plane(&mesh, &plane((1., 1., 1.), (1., 0.5, 0.5))).unwrap();
+        check_closed_and_oriented(&lower);
+        check_closed_and_oriented(&upper);
+        assert_eq!(lower.get_volume(), Number::new(1.));
+        assert_eq!(upper.get_volume(), Number::new(1.));
+    }
+
+    #[test]
+    fn split_by_face_plane() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 1., 1.)).unwrap();
+        let (lower, upper) = split_by_plane(&mesh, &plane((0., 0., 1.), (0., 0., 1.))).unwrap();
+        check_closed_and_oriented(&lower);
+        assert_eq!(lower.get_volume(), Number::new(1.));
+        assert_eq!(upper.num_of_triangles(), 0);
+
+        let (lower, upper) = split_by_plane(&mesh, &plane((0., 0., 1.), (0., 0., 5.))).unwrap();
+        assert_eq!(lower.get_volume(), Number::new(1.));
+        assert_eq!(upper.num_of_triangles(), 0);
+    }
+
+    #[test]
+    fn split_with_hole() {
+        let mesh = create_torus(Number::new(2.), Number::new(0.5), 12, 8).unwrap();
+        let (lower, upper) = split_by_plane(&mesh, &plane((0., 0., 1.), (0., 0., 0.))).unwrap();
+        check_closed_and_oriented(&lower);
+        check_closed_and_oriented(&upper);
+        assert_eq!(&lower.get_volume() + &upper.get_volume(), mesh.get_volume());
+        let (v1, v2) = (lower.get_volume().convert_to_f64(), upper.get_volume().convert_to_f64());
+        assert!((v1 - v2).abs() < 1e-6);
+
+        let (lower, upper) = split_by_plane(&mesh, &plane((1., 0., 0.), (0., 0., 0.))).unwrap();
+        check_closed_and_oriented(&lower);
+        check_closed_and_oriented(&upper);
+        assert_eq!(&lower.get_volume() + &upper.get_volume(), mesh.get_volume());
+    }
+
+    #[test]
+    fn split_sphere() {
+        let mesh = create_uv_sphere(Number::new(1.), 16, 8).unwrap();
+        let (lower, upper) = split_by_plane(&mesh, &plane((0., 1., 2.), (0., 0., 0.25))).unwrap();
+        check_closed_and_oriented(&lower);
+        check_closed_and_oriented(&upper);
+        assert_eq!(&lower.get_volume() + &upper.get_volume(), mesh.get_volume());
+
+        let zero = plane((0., 0., 0.), (0., 0., 0.));
+        assert!(split_by_plane(&mesh, &zero).is_err());
+    }
+
+    #[test]
+    fn split_open_mesh() {
+        let mut mesh = Mesh::new();
+        mesh.add_triangle(Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 0., 1.)
+        ])).unwrap();
+        assert!(split_by_plane(&mesh, &plane((0., 0., 1.), (0., 0., 0.5))).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use primitives::*;
+use primitives::transform::Transform;
+use triangulation::ear_clipping_triangulation::triangulate_polygon_with_holes;
+use modeling::generators::get_circle_points;
+
+use std::f64::consts::PI;
+
+// максимальная погрешность углов поворота сечений
+const ANGLE_TOLERANCE: f64 = 1e-6;
+
+/// This function extrudes a polygon with holes along the z axis.
+/// Polygons of the tree are taken in the plane xy, z coordinates are ignored.
+/// The root of the tree is the outer boundary, its children are holes, children of holes are islands and so on.
+/// Directions of polygons don't matter.
+/// # Arguments
+///
+/// * `tree` - A tree of nested polygons.
+/// * `height` - A positive height of the extrusion.
+pub fn extrude(tree: &PolygonTreeNode, height: Number) -> Result<Mesh, &'static str> {
+    return extrude_with_twist(tree, height, 0., Number::new(1.), 1);
+}
+
+/// This function extrudes a polygon with holes along the z axis, twisting and scaling it around the z axis.
+/// A section at the height `z` is rotated by the angle `twist*z/height` and scaled by the factor
+/// `1 + (scale - 1)*z/height`. Rotations are exact and their angles differ from the requested ones by no more than 1e-6.
+/// Polygons of the tree are taken in the plane xy, z coordinates are ignored.
+/// The root of the tree is the outer boundary, its children are holes, children of holes are islands and so on.
+/// # Arguments
+///
+/// * `tree` - A tree of nested polygons.
+/// * `height` - A positive height of the extrusion.
+/// * `twist` - An angle of the top section rotation in radians.
+/// * `scale` - A positive scale factor of the top section.
+/// * `slices` - A number of layers, which side faces are divided into. It has to be positive.
+pub fn extrude_with_twist(
+    tree: &PolygonTreeNode,
+    height: Number,
+    twist: f64,
+    scale: Number,
+    slices: usize
+) -> Result<Mesh, &'static str> {
+    if !height.is_it_positive() || !scale.is_it_positive() {
+        return Err("The height and the scale factor have to be positive!");
+    }
+    if slices == 0 {
+        return Err("The number of slices has to be positive!");
+    }
+
+    let (loops, caps) = prepare_sketch(tree)?;
+
+    let z_axis = Vector::new_from_f64(0., 0., 1.);
+    let mut layers: Vec<Transform> = Vec::new();
+    for k in 0..(slices + 1) {
+        let part = Number::new(k as f64) / Number::new(slices as f64);
+        let factor = &(&(&scale - &Number::new(1.)) * &part) + &Number::new(1.);
+        let rotation = Transform::rotation_around_axis(&z_axis, twist * (k as f64) / (slices as f64), ANGLE_TOLERANCE)?;
+        let layer = Transform::scaling(factor.clone(), factor, Number::new(1.))?
+            .then(&rotation)
+            .then(&Transform::translation(&Vector::new(Number::zero(), Number::zero(), &height * &part)));
+        layers.push(layer);
+    }
+
+    let mut mesh = Mesh::new();
+    for polygon_loop in loops.iter() {
+        let n = polygon_loop.len();
+        for k in 0..slices {
+            let lower: Vec<Point> = polygon_loop.iter().map(|p| layers[k].apply_to_point(p)).collect();
+            let upper: Vec<Point> = polygon_loop.iter().map(|p| layers[k + 1].apply_to_point(p)).collect();
+            for i in 0..n {
+                let next = (i + 1) % n;
+                add_triangle(&mut mesh, &lower[i], &lower[next], &upper[next])?;
+                add_triangle(&mut mesh, &lower[i], &upper[next], &upper[i])?;
+            }
+        }
+    }
+
+    for t in caps.iter() {
+        let ps = t.get_points_ref();
+        add_triangle(&mut mesh, &ps[0], &ps[2], &ps[1])?;
+        let top: Vec<Point> = ps.iter().map(|p| layers[slices].apply_to_point(p)).collect();
+        add_triangle(&mut mesh, &top[0], &top[1], &top[2])?;
+    }
+
+    return Ok(mesh);
+}
+
+/// This function revolves a polygon with holes around the z axis.
+/// Polygons of the tree are taken in the plane xy: x is a distance from the axis and y becomes z.
+/// All x coordinates have to be non negative. Points with zero x lie on the axis.
+/// The root of the tree is the outer boundary, its children are holes, children of holes are islands and so on.
+/// If `angle` is less than `2*pi`, the start and the end sections are closed by caps.
+/// # Arguments
+///
+/// * `tree` - A tree of nested polygons.
+/// * `angle` - An angle of the revolution in radians. It has to be in `(0, 2*pi]`.
+/// * `segments` - A number of segments around the axis. It has to be at least 3 for the full revolution.
+pub fn revolve(tree: &PolygonTreeNode, angle: f64, segments: usize) -> Result<Mesh, &'static str> {
+    if !(angle > 0.) || angle > 2. * PI {
+        return Err("The angle of a revolution has to be in (0, 2*pi]!");
+    }
+    let full = angle == 2. * PI;
+    if segments == 0 || (full && segments < 3) {
+        return Err("The number of segments is too small!");
+    }
+
+    let (loops, caps) = prepare_sketch(tree)?;
+    for polygon_loop in loops.iter() {
+        if polygon_loop.iter().any(|p| p.x.is_it_negative()) {
+            return Err("A profile of a revolution has to lie in the half plane x >= 0!");
+        }
+    }
+
+    // рациональные (cos, sin) для углов сечений
+    let directions: Vec<(Number, Number)> = if full {
+        get_circle_points(segments)
+    } else {
+        let z_axis = Vector::new_from_f64(0., 0., 1.);
+        let mut directions: Vec<(Number, Number)> = Vec::new();
+        for k in 0..(segments + 1) {
+            let t = Transform::rotation_around_axis(&z_axis, angle * (k as f64) / (segments as f64), ANGLE_TOLERANCE)?;
+            let m = t.get_matrix();
+            directions.push((m[0][0].clone(), m[1][0].clone()));
+        }
+        directions
+    };
+
+    let section = |p: &Point, k: usize| -> Point {
+        let (ref c, ref s) = directions[k % directions.len()];
+        return Point::new(&p.x * c, &p.x * s, p.y.clone());
+    };
+
+    let mut mesh = Mesh::new();
+    for polygon_loop in loops.iter() {
+        let n = polygon_loop.len();
+        for i in 0..n {
+            let (p, q) = (&polygon_loop[i], &polygon_loop[(i + 1) % n]);
+            if p.x.is_it_zero() && q.x.is_it_zero() {
+                continue;
+            }
+
+            for k in 0..segments {
+                let (a, b, c, d) = (section(p, k), section(q, k), section(q, k + 1), section(p, k + 1));
+                if !q.x.is_it_zero() {
+                    add_triangle(&mut mesh, &a, &c, &b)?;
+                }
+                if !p.x.is_it_zero() {
+                    add_triangle(&mut mesh, &a, &d, &c)?;
+                }
+            }
+        }
+    }
+
+    if !full {
+        for t in caps.iter() {
+            let ps = t.get_points_ref();
+            let start: Vec<Point> = ps.iter().map(|p| section(p, 0)).collect();
+            add_triangle(&mut mesh, &start[0], &start[1], &start[2])?;
+            let end: Vec<Point> = ps.iter().map(|p| section(p, segments)).collect();
+            add_triangle(&mut mesh, &end[0], &end[2], &end[1])?;
+        }
+    }
+
+    return Ok(mesh);
+}
+
+// возвращает контуры, ориентированные так, что материал находится слева, и триангуляцию эскиза;
+// все точки переносятся в плоскость z = 0
+fn prepare_sketch(tree: &PolygonTreeNode) -> Result<(Vec<Vec<Point>>, Vec<Triangle>), &'static str> {
+    let mut loops: Vec<Vec<Point>> = Vec::new();
+    let mut caps: Vec<Triangle> = Vec::new();
+    let mut nodes: Vec<(&PolygonTreeNode, usize)> = vec![(tree, 0)];
+
+    while !nodes.is_empty() {
+        let (node, depth) = nodes.pop().unwrap();
+        let polygon = flatten(&node.get_cur_polygon())?;
+
+        let mut points = polygon.get_points_ref().clone();
+        let is_ccw = polygon.signed_area().is_it_positive();
+        if is_ccw != (depth % 2 == 0) {
+            points.reverse();
+        }
+        loops.push(points);
+
+        if depth % 2 == 0 {
+            let mut holes: Vec<Polygon> = Vec::new();
+            for child in node.get_children_ref().iter() {
+                holes.push(flatten(&child.get_cur_polygon())?);
+            }
+            caps.extend(triangulate_polygon_with_holes(polygon, holes));
+        }
+
+        for child in node.get_children_ref().iter() {
+            nodes.push((child, depth + 1));
+        }
+    }
+
+    return Ok((loops, caps));
+}
+
+fn flatten(polygon: &Polygon) -> Result<Polygon, &'static str> {
+    if polygon.get_points_ref().len() < 3 {
+        return Err("A polygon has to have at least 3 points!");
+    }
+
+    let points: Vec<Point> = polygon.get_points_ref().iter()
+        .map(|p| Point::new(p.x.clone(), p.y.clone(), Number::zero()))
+        .collect();
+    let res = Polygon::new(points, Vector::new_from_f64(0., 0., 1.));
+    if res.signed_area().is_it_zero() {
+        return Err("A polygon has to have a non zero area!");
+    }
+    return Ok(res);
+}
+
+fn add_triangle(mesh: &mut Mesh, a: &Point, b: &Point, c: &Point) -> Result<(), &'static str> {
+    return match mesh.add_triangle(Triangle::new(vec![a.clone(), b.clone(), c.clone()])) {
+        Ok(_) => Ok(()),
+        Err(_) => Err("A degenerate triangle is generated, the sketch is probably invalid!")
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use modeling::*;
+    use primitives::fixtures::*;
+    use std::f64::consts::PI;
+
+    fn square_with_hole() -> PolygonTreeNode {
+        let mut tree = PolygonTreeNode::new(polygon(vec![(-2., -2.), (2., -2.), (2., 2.), (-2., 2.)]));
+        // the hole is clockwise, the island is counterclockwise
+        let mut hole = PolygonTreeNode::new(polygon(vec![(-1., -1.), (-1., 1.), (1., 1.), (1., -1.)]));
+        hole.add_child(PolygonTreeNode::new(polygon(vec![(-0.5, -0.5), (0.5, -0.5), (0., 0.5)])));
+        tree.add_child(hole);
+        return tree;
+    }
+
+    #[test]
+    fn extrusion() {
+        let mesh = extrude(&square_with_hole(), Number::new(2.)).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.get_volume(), Number::new(2. * (16. - 4. + 0.5)));
+
+        assert!(extrude(&square_with_hole(), Number::zero()).is_err());
+    }
+
+    #[test]
+    fn tapered_extrusion() {
+        // a frustum of a pyramid: V = h/3*(A1 + A2 + sqrt(A1*A2))
+        let tree = PolygonTreeNode::new(polygon(vec![(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)]));
+        let mesh = extrude_with_twist(&tree, Number::new(3.), 0., Number::new(0.5), 3).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert_eq!(mesh.get_volume(), Number::new(7.));
+    }
+
+    #[test]
+    fn twisted_extrusion() {
+        let mesh = extrude_with_twist(&square_with_hole(), Number::new(4.), PI / 2., Number::new(1.), 8).unwrap();
+        check_closed_and_oriented(&mesh);
+        let volume = mesh.get_volume().convert_to_f64();
+        assert!(volume > 0. && volume < 4. * 12.5);
+    }
+
+    #[test]
+    fn revolution() {
+        let tree = PolygonTreeNode::new(polygon(vec![(1., 0.), (2., 0.), (2., 1.), (1., 1.)]));
+        let mesh = revolve(&tree, 2. * PI, 64).unwrap();
+        check_closed_and_oriented(&mesh);
+        let volume = mesh.get_volume().convert_to_f64();
+        assert!((volume - 3. * PI).abs() < 0.01 * 3. * PI);
+
+        let mesh = revolve(&tree, PI, 32).unwrap();
+        check_closed_and_oriented(&mesh);
+        let volume = mesh.get_volume().convert_to_f64();
+        assert!((volume - 1.5 * PI).abs() < 0.01 * 1.5 * PI);
+
+        assert!(revolve(&tree, 0., 32).is_err());
+        let tree = PolygonTreeNode::new(polygon(vec![(-1., 0.), (2., 0.), (2., 1.)]));
+        assert!(revolve(&tree, PI, 32).is_err());
+    }
+
+    #[test]
+    fn revolution_touching_axis() {
+        // a cone with the apex on the axis
+        let tree = PolygonTreeNode::new(polygon(vec![(0., 0.), (1., 0.), (0., 1.)]));
+        let mesh = revolve(&tree, 2. * PI, 32).unwrap();
+        check_closed_and_oriented(&mesh);
+        let volume = mesh.get_volume().convert_to_f64();
+        assert!((volume - PI / 3.).abs() < 0.02 * PI / 3.);
+
+        let mesh = revolve(&tree, PI / 2., 8).unwrap();
+        check_closed_and_oriented(&mesh);
+        assert!(mesh.get_volume().is_it_positive());
+    }
+}
@@ -0,0 +1,972 @@
+use primitives::*;
+use primitives::predicates::{orient3d, insphere};
+use triangulation::constrained_delaunay_triangulation::get_parity_domain;
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// The smallest bound of the radius-edge ratio of tetrahedra, accepted by `tetrahedralize_conforming`.
+/// The refinement is guaranteed to terminate for bounds not less than it.
+pub const MIN_RADIUS_EDGE_RATIO: f64 = 2.;
+
+/// The maximum number of Steiner points, which may be inserted by `tetrahedralize_conforming`
+/// and `tetrahedralize_with_delaunay_boundary`.
+pub const MAX_STEINER_POINTS: usize = 20000;
+
+/// This function fills the solid bounded by the closed mesh with tetrahedra.
+/// It's a conforming Delaunay tetrahedralization, not a constrained one: boundary edges and triangles, which are missing
+/// in the Delaunay tetrahedralization of vertices, are recovered by Steiner points on them, so boundary triangles may be split
+/// and each of them is a union of boundary faces of the result. Use `tetrahedralize_with_delaunay_boundary`
+/// to keep boundary triangles, if they are present in the Delaunay tetrahedralization.
+/// Tetrahedra with the radius-edge ratio greater than `max_radius_edge_ratio` or the volume
+/// greater than `max_volume` are refined by Steiner points at circumcenters, circumcenters close to the boundary
+/// are replaced by splitting points of boundary edges and triangles. The refinement terminates,
+/// if dihedral angles between boundary triangles aren't acute. Skinny tetrahedra, whose shortest edge joins points
+/// on two boundary edges with an acute angle between them, aren't refined, so small input angles don't lead to infinite refinement.
+/// An error is returned if more than `MAX_STEINER_POINTS` Steiner points are needed.
+/// Boundary faces are marked by planar facets of the mesh: coplanar adjacent triangles share a marker.
+/// Predicates are exact, Steiner points lie exactly on the boundary for rational backends of `Number`.
+/// # Arguments
+///
+/// * `mesh` - A closed manifold mesh.
+/// * `max_radius_edge_ratio` - Optional bound of the ratio of circumradius to the shortest edge, not less than `MIN_RADIUS_EDGE_RATIO`.
+/// * `max_volume` - Optional maximum volume of tetrahedra.
+pub fn tetrahedralize_conforming(mesh: &Mesh, max_radius_edge_ratio: Option<f64>, max_volume: Option<Number>) -> Result<TetMesh, &'static str> {
+    return tetrahedralize_mesh(mesh, max_radius_edge_ratio, max_volume, false);
+}
+
+/// This function fills the solid bounded by the closed mesh with tetrahedra, so that boundary triangles of the mesh
+/// are faces of the result. Steiner points are inserted only inside the solid. It isn't a constrained tetrahedralization:
+/// boundary isn't recovered, so an error is returned if some boundary edge or triangle is missing in the Delaunay
+/// tetrahedralization of vertices. Refinement is the same as in `tetrahedralize_conforming`, but tetrahedra,
+/// whose circumcenters would destroy the boundary, aren't refined.
+/// # Arguments
+///
+/// * `mesh` - A closed manifold mesh.
+/// * `max_radius_edge_ratio` - Optional bound of the ratio of circumradius to the shortest edge, not less than `MIN_RADIUS_EDGE_RATIO`.
+/// * `max_volume` - Optional maximum volume of tetrahedra.
+pub fn tetrahedralize_with_delaunay_boundary(mesh: &Mesh, max_radius_edge_ratio: Option<f64>, max_volume: Option<Number>) -> Result<TetMesh, &'static str> {
+    return tetrahedralize_mesh(mesh, max_radius_edge_ratio, max_volume, true);
+}
+
+fn tetrahedralize_mesh(mesh: &Mesh, max_radius_edge_ratio: Option<f64>, max_volume: Option<Number>, preserve_boundary: bool) -> Result<TetMesh, &'static str> {
+    if let Some(ratio) = max_radius_edge_ratio {
+        if !(ratio >= MIN_RADIUS_EDGE_RATIO) {
+            return Err("Radius-edge ratio bound has to be at least 2!");
+        }
+    }
+    if let Some(ref volume) = max_volume {
+        if !volume.is_it_positive() {
+            return Err("Maximum volume bound has to be positive!");
+        }
+    }
+    if !mesh.geometry_check() {
+        return Err("Mesh has to be closed and manifold!");
+    }
+
+    let mut indices: BTreeMap<Point, usize> = BTreeMap::new();
+    let mut points: Vec<Point> = Vec::new();
+    let mut triangles: Vec<[usize; 3]> = Vec::new();
+    let mut its = mesh.get_it_iterator();
+    its.sort();
+    for it in its {
+        let t = mesh.get_triangle(it);
+        let mut triangle = [0; 3];
+        for (j, p) in t.get_points_ref().iter().enumerate() {
+            let n = points.len();
+            triangle[j] = *indices.entry(p.clone()).or_insert(n);
+            if triangle[j] == n {
+                points.push(p.clone());
+            }
+        }
+        triangles.push(triangle);
+    }
+
+    let mut edges: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for j in 0..3 {
+            edges.entry(get_edge_key(t[j], t[(j + 1) % 3])).or_insert(Vec::new()).push(i);
+        }
+    }
+    if edges.values().any(|ts| ts.len() != 2) {
+        return Err("Mesh has to be closed and manifold!");
+    }
+
+    let mut tetrahedralizer = Tetrahedralizer::new(points);
+    tetrahedralizer.ratio2 = max_radius_edge_ratio.map(|ratio| Number::new(ratio * ratio));
+    tetrahedralizer.max_volume6 = max_volume.map(|volume| volume * Number::new(6.));
+    tetrahedralizer.preserve_boundary = preserve_boundary;
+    let markers = get_facet_markers(&tetrahedralizer.points, &triangles, &edges);
+    for (t, marker) in triangles.into_iter().zip(markers) {
+        tetrahedralizer.add_facet(t, marker);
+    }
+    tetrahedralizer.segments = edges;
+
+    tetrahedralizer.recover_boundary()?;
+    tetrahedralizer.refine()?;
+    return tetrahedralizer.get_tet_mesh();
+}
+
+// соседние треугольники в одной плоскости образуют грань с общим маркером, маркеры нумеруются с единицы
+fn get_facet_markers(points: &Vec<Point>, triangles: &Vec<[usize; 3]>, edges: &BTreeMap<(usize, usize), Vec<usize>>) -> Vec<usize> {
+    let mut parents: Vec<usize> = (0..triangles.len()).collect();
+    fn find(parents: &mut Vec<usize>, i: usize) -> usize {
+        let mut root = i;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        parents[i] = root;
+        return root;
+    }
+
+    for ts in edges.values() {
+        let (t1, t2) = (&triangles[ts[0]], &triangles[ts[1]]);
+        let coplanar = t2.iter().all(|&v| orient3d(&points[t1[0]], &points[t1[1]], &points[t1[2]], &points[v]) == Ordering::Equal);
+        if coplanar {
+            let (r1, r2) = (find(&mut parents, ts[0]), find(&mut parents, ts[1]));
+            parents[r1.max(r2)] = r1.min(r2);
+        }
+    }
+
+    let mut markers: HashMap<usize, usize> = HashMap::new();
+    let mut res: Vec<usize> = Vec::new();
+    for i in 0..triangles.len() {
+        let root = find(&mut parents, i);
+        let n = markers.len() + 1;
+        res.push(*markers.entry(root).or_insert(n));
+    }
+    return res;
+}
+
+fn get_edge_key(a: usize, b: usize) -> (usize, usize) {
+    return (a.min(b), a.max(b));
+}
+
+// ориентированная грань начинается с наименьшего индекса
+fn get_face_key(a: usize, b: usize, c: usize) -> [usize; 3] {
+    if a < b && a < c {
+        return [a, b, c];
+    } else if b < c {
+        return [b, c, a];
+    } else {
+        return [c, a, b];
+    }
+}
+
+fn get_sorted(f: &[usize; 3]) -> [usize; 3] {
+    let mut res = *f;
+    res.sort();
+    return res;
+}
+
+// внешние грани тетраэдра положительной ориентации
+fn get_faces(t: &[usize; 4]) -> [[usize; 3]; 4] {
+    return [[t[0], t[1], t[2]], [t[1], t[3], t[2]], [t[0], t[2], t[3]], [t[0], t[3], t[1]]];
+}
+
+enum Location {
+    Vertex(usize),
+    Cavity(Vec<usize>),
+    Outside
+}
+
+struct Facet {
+    corners: [usize; 3],
+    down: Vector,
+    points: Vec<usize>,
+    subfaces: Vec<[usize; 3]>,
+    marker: usize
+}
+
+struct Tetrahedralizer {
+    points: Vec<Point>,
+    // вершины с меньшими индексами заданы на входе, за ними следуют вершины охватывающего тетраэдра
+    input_count: usize,
+    // тетраэдры (a, b, c, d), для которых orient3d(a, b, c, d) == Greater
+    tets: Vec<Option<[usize; 4]>>,
+    faces: HashMap<[usize; 3], usize>,
+    edges: HashMap<(usize, usize), usize>,
+    last: usize,
+    // подотрезки границы и грани, содержащие их
+    segments: BTreeMap<(usize, usize), Vec<usize>>,
+    // исходный отрезок (пара входных вершин), на котором лежит точка Штейнера
+    origins: HashMap<usize, (usize, usize)>,
+    facets: Vec<Facet>,
+    subfaces: HashMap<[usize; 3], usize>,
+    skipped: HashSet<[usize; 4]>,
+    ratio2: Option<Number>,
+    max_volume6: Option<Number>,
+    // точки Штейнера не вставляются на границу
+    preserve_boundary: bool
+}
+
+impl Tetrahedralizer {
+    fn new(points: Vec<Point>) -> Tetrahedralizer {
+        let input_count = points.len();
+        let mut min = points[0].clone();
+        let mut max = points[0].clone();
+        for p in points.iter() {
+            min = Point::new(min.x.clone().min(p.x.clone()), min.y.clone().min(p.y.clone()), min.z.clone().min(p.z.clone()));
+            max = Point::new(max.x.clone().max(p.x.clone()), max.y.clone().max(p.y.clone()), max.z.clone().max(p.z.clone()));
+        }
+        let half = Number::new(0.5);
+        let center = Point::new((&min.x + &max.x) * half.clone(), (&min.y + &max.y) * half.clone(), (&min.z + &max.z) * half);
+        let size = (&max.x - &min.x).max(&max.y - &min.y).max(&max.z - &min.z) + Number::new(1.);
+        let scale = size * Number::new(1000.);
+
+        let mut tetrahedralizer = Tetrahedralizer {
+            points: points,
+            input_count: input_count,
+            tets: Vec::new(),
+            faces: HashMap::new(),
+            edges: HashMap::new(),
+            last: 0,
+            segments: BTreeMap::new(),
+            origins: HashMap::new(),
+            facets: Vec::new(),
+            subfaces: HashMap::new(),
+            skipped: HashSet::new(),
+            ratio2: None,
+            max_volume6: None,
+            preserve_boundary: false
+        };
+        for &(x, y, z) in [(1., 1., 1.), (1., -1., -1.), (-1., 1., -1.), (-1., -1., 1.)].iter() {
+            let v = Vector::new(Number::new(x), Number::new(y), Number::new(z)) * scale.clone();
+            tetrahedralizer.points.push(&center + &v);
+        }
+        let n = input_count;
+        let ps = &tetrahedralizer.points;
+        let t = if orient3d(&ps[n], &ps[n + 1], &ps[n + 2], &ps[n + 3]) == Ordering::Greater {
+            [n, n + 1, n + 2, n + 3]
+        } else {
+            [n, n + 2, n + 1, n + 3]
+        };
+        tetrahedralizer.add_tet(t);
+
+        for i in 0..input_count {
+            if let Location::Cavity(cavity) = tetrahedralizer.locate(&tetrahedralizer.points[i]) {
+                tetrahedralizer.insert_existing(i, cavity);
+            }
+        }
+        return tetrahedralizer;
+    }
+
+    fn add_facet(&mut self, corners: [usize; 3], marker: usize) {
+        let ps = &self.points;
+        let normal = (&ps[corners[1]] - &ps[corners[0]]).cross_product(&(&ps[corners[2]] - &ps[corners[0]]));
+        let key = get_sorted(&corners);
+        self.subfaces.insert(key, self.facets.len());
+        self.facets.push(Facet {
+            corners: corners,
+            down: normal * Number::new(-1.),
+            points: corners.to_vec(),
+            subfaces: vec![corners],
+            marker: marker
+        });
+    }
+
+    fn is_super(&self, v: usize) -> bool {
+        return v >= self.input_count && v < self.input_count + 4;
+    }
+
+    fn add_tet(&mut self, t: [usize; 4]) {
+        let i = self.tets.len();
+        self.tets.push(Some(t));
+        for f in get_faces(&t).iter() {
+            self.faces.insert(get_face_key(f[0], f[1], f[2]), i);
+        }
+        for j in 0..4 {
+            for k in (j + 1)..4 {
+                *self.edges.entry(get_edge_key(t[j], t[k])).or_insert(0) += 1;
+            }
+        }
+        self.last = i;
+    }
+
+    fn remove_tet(&mut self, i: usize) {
+        let t = self.tets[i].take().unwrap();
+        for f in get_faces(&t).iter() {
+            let key = get_face_key(f[0], f[1], f[2]);
+            if self.faces.get(&key) == Some(&i) {
+                self.faces.remove(&key);
+            }
+        }
+        for j in 0..4 {
+            for k in (j + 1)..4 {
+                let key = get_edge_key(t[j], t[k]);
+                let count = self.edges[&key];
+                if count == 1 {
+                    self.edges.remove(&key);
+                } else {
+                    self.edges.insert(key, count - 1);
+                }
+            }
+        }
+    }
+
+    fn has_tet(&self, t: &[usize; 4]) -> bool {
+        return self.faces.get(&get_face_key(t[0], t[1], t[2])).map_or(false, |&i| self.tets[i] == Some(*t));
+    }
+
+    fn has_face(&self, f: &[usize; 3]) -> bool {
+        return self.faces.contains_key(&get_face_key(f[0], f[1], f[2])) || self.faces.contains_key(&get_face_key(f[0], f[2], f[1]));
+    }
+
+    // тетраэдр по другую сторону внешней грани
+    fn get_neighbour(&self, f: &[usize; 3]) -> Option<usize> {
+        return self.faces.get(&get_face_key(f[0], f[2], f[1])).cloned();
+    }
+
+    fn locate(&self, p: &Point) -> Location {
+        let ps = &self.points;
+        let mut cur = self.last;
+        let mut steps = 0;
+        // обход по видимости завершается в триангуляции Делоне
+        'walk: loop {
+            let faces = get_faces(&self.tets[cur].unwrap());
+            steps += 1;
+            for k in 0..4 {
+                let f = &faces[(k + steps) % 4];
+                if orient3d(&ps[f[0]], &ps[f[1]], &ps[f[2]], p) == Ordering::Less {
+                    match self.get_neighbour(f) {
+                        Some(n) => {
+                            cur = n;
+                            continue 'walk;
+                        },
+                        None => return Location::Outside
+                    }
+                }
+            }
+            break;
+        }
+
+        let t = self.tets[cur].unwrap();
+        if let Some(&v) = t.iter().find(|&&v| ps[v] == *p) {
+            return Location::Vertex(v);
+        }
+
+        // полость: тетраэдры, описанные сферы которых строго содержат точку
+        let mut cavity: Vec<usize> = vec![cur];
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(cur);
+        let mut k = 0;
+        while k < cavity.len() {
+            let t = self.tets[cavity[k]].unwrap();
+            k += 1;
+            for f in get_faces(&t).iter() {
+                if let Some(n) = self.get_neighbour(f) {
+                    if visited.insert(n) {
+                        let s = self.tets[n].unwrap();
+                        if insphere(&ps[s[0]], &ps[s[1]], &ps[s[2]], &ps[s[3]], p) == Ordering::Greater {
+                            cavity.push(n);
+                        }
+                    }
+                }
+            }
+        }
+        return Location::Cavity(cavity);
+    }
+
+    fn get_cavity_boundary(&self, cavity: &Vec<usize>) -> Vec<[usize; 3]> {
+        let in_cavity: HashSet<usize> = cavity.iter().cloned().collect();
+        let mut boundary: Vec<[usize; 3]> = Vec::new();
+        for &i in cavity.iter() {
+            for f in get_faces(&self.tets[i].unwrap()).iter() {
+                if !self.get_neighbour(f).map_or(false, |n| in_cavity.contains(&n)) {
+                    boundary.push(*f);
+                }
+            }
+        }
+        return boundary;
+    }
+
+    fn insert(&mut self, p: Point, cavity: Vec<usize>) -> Result<usize, &'static str> {
+        // общий для восстановления границы и улучшения предел числа точек Штейнера
+        if self.points.len() >= self.input_count + 4 + MAX_STEINER_POINTS {
+            return Err("Too many Steiner points are needed!");
+        }
+        self.points.push(p);
+        let i = self.points.len() - 1;
+        self.insert_existing(i, cavity);
+        return Ok(i);
+    }
+
+    fn insert_existing(&mut self, i: usize, cavity: Vec<usize>) {
+        let boundary = self.get_cavity_boundary(&cavity);
+        for &t in cavity.iter() {
+            self.remove_tet(t);
+        }
+        // точка лежит строго под каждой внешней гранью полости
+        for f in boundary {
+            self.add_tet([f[0], f[1], f[2], i]);
+        }
+    }
+
+    fn get_destroyed_segments(&self, cavity: &Vec<usize>) -> Vec<(usize, usize)> {
+        let mut kept: HashSet<(usize, usize)> = HashSet::new();
+        for f in self.get_cavity_boundary(cavity) {
+            for j in 0..3 {
+                kept.insert(get_edge_key(f[j], f[(j + 1) % 3]));
+            }
+        }
+        let mut res: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for &i in cavity.iter() {
+            let t = self.tets[i].unwrap();
+            for j in 0..4 {
+                for k in (j + 1)..4 {
+                    let key = get_edge_key(t[j], t[k]);
+                    if !kept.contains(&key) && self.segments.contains_key(&key) {
+                        res.insert(key);
+                    }
+                }
+            }
+        }
+        return res.into_iter().collect();
+    }
+
+    fn get_destroyed_subfaces(&self, cavity: &Vec<usize>) -> Vec<(usize, [usize; 3])> {
+        let in_cavity: HashSet<usize> = cavity.iter().cloned().collect();
+        let mut res: BTreeSet<(usize, [usize; 3])> = BTreeSet::new();
+        for &i in cavity.iter() {
+            for f in get_faces(&self.tets[i].unwrap()).iter() {
+                if self.get_neighbour(f).map_or(false, |n| in_cavity.contains(&n)) {
+                    if let Some(&facet) = self.subfaces.get(&get_sorted(f)) {
+                        res.insert((facet, *f));
+                    }
+                }
+            }
+        }
+        return res.into_iter().collect();
+    }
+
+    // подотрезки и грани границы у тетраэдров полости, диаметральные шары которых содержат точку
+    fn get_encroached_segments(&self, cavity: &Vec<usize>, p: &Point) -> Vec<(usize, usize)> {
+        let mut res: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for &i in cavity.iter() {
+            let t = self.tets[i].unwrap();
+            for j in 0..4 {
+                for k in (j + 1)..4 {
+                    let key = get_edge_key(t[j], t[k]);
+                    if self.segments.contains_key(&key) && self.is_in_diametral_sphere(key.0, key.1, p) {
+                        res.insert(key);
+                    }
+                }
+            }
+        }
+        return res.into_iter().collect();
+    }
+
+    fn get_encroached_subfaces(&self, cavity: &Vec<usize>, p: &Point) -> Vec<(usize, [usize; 3])> {
+        let mut res: BTreeSet<(usize, [usize; 3])> = BTreeSet::new();
+        for &i in cavity.iter() {
+            for f in get_faces(&self.tets[i].unwrap()).iter() {
+                if let Some(&facet) = self.subfaces.get(&get_sorted(f)) {
+                    let center = self.get_triangle_circumcenter(f);
+                    if (p - &center).length2() < (&self.points[f[0]] - &center).length2() {
+                        res.insert((facet, *f));
+                    }
+                }
+            }
+        }
+        return res.into_iter().collect();
+    }
+
+    fn recover_boundary(&mut self) -> Result<(), &'static str> {
+        loop {
+            let missing_segment = self.segments.keys().find(|&&(u, v)| !self.edges.contains_key(&(u, v))).cloned();
+            if let Some((u, v)) = missing_segment {
+                self.split_segment(u, v)?;
+                continue;
+            }
+
+            let missing_subface = self.facets.iter().enumerate()
+                .filter_map(|(i, facet)| facet.subfaces.iter().find(|f| !self.has_face(f)).map(|f| (i, *f)))
+                .next();
+            if let Some((facet, f)) = missing_subface {
+                self.split_subface(facet, f)?;
+                continue;
+            }
+            return Ok(());
+        }
+    }
+
+    fn split_segment(&mut self, u: usize, v: usize) -> Result<(), &'static str> {
+        if self.preserve_boundary {
+            return Err("Boundary can't be recovered without Steiner points!");
+        }
+        let facets = match self.segments.remove(&get_edge_key(u, v)) {
+            Some(facets) => facets,
+            None => return Ok(())
+        };
+
+        let (u_input, v_input) = (u < self.input_count, v < self.input_count);
+        let origin = if u_input && v_input {
+            (u, v)
+        } else if u_input {
+            self.origins[&v]
+        } else {
+            self.origins[&u]
+        };
+
+        let p = {
+            let ps = &self.points;
+            if u_input != v_input {
+                // концентрические оболочки: расстояние от входной вершины равно степени двойки
+                let (a, b) = if u_input {(&ps[u], &ps[v])} else {(&ps[v], &ps[u])};
+                let e: Vector = b - a;
+                let len = e.length2().convert_to_f64().sqrt();
+                let mut shell = 2f64.powi(len.log2().floor() as i32);
+                while shell > len * 2. / 3. {
+                    shell /= 2.;
+                }
+                while shell < len / 3. {
+                    shell *= 2.;
+                }
+                a + &(e * Number::new(shell / len))
+            } else {
+                let e: Vector = &ps[v] - &ps[u];
+                &ps[u] + &(e * Number::new(0.5))
+            }
+        };
+
+        let i = match self.locate(&p) {
+            Location::Vertex(i) => i,
+            Location::Cavity(cavity) => self.insert(p, cavity)?,
+            Location::Outside => return Err("Steiner point lies outside the triangulation!")
+        };
+        if i == u || i == v {
+            return Err("Boundary recovery doesn't converge!");
+        }
+        if i >= self.input_count + 4 {
+            self.origins.insert(i, origin);
+        }
+        self.segments.insert(get_edge_key(u, i), facets.clone());
+        self.segments.insert(get_edge_key(i, v), facets.clone());
+        for f in facets {
+            self.add_point_to_facet(f, i);
+        }
+        return Ok(());
+    }
+
+    fn split_subface(&mut self, facet: usize, f: [usize; 3]) -> Result<(), &'static str> {
+        if self.preserve_boundary {
+            return Err("Boundary can't be recovered without Steiner points!");
+        }
+        let center = self.get_triangle_circumcenter(&f);
+
+        let facet_segments: Vec<(usize, usize)> = self.segments.iter()
+            .filter(|&(_, facets)| facets.contains(&facet))
+            .map(|(&key, _)| key)
+            .collect();
+        let encroached: Vec<(usize, usize)> = facet_segments.iter()
+            .filter(|&&(u, v)| self.is_in_diametral_sphere(u, v, &center))
+            .cloned()
+            .collect();
+        if !encroached.is_empty() {
+            for (u, v) in encroached {
+                self.split_segment(u, v)?;
+            }
+            return Ok(());
+        }
+
+        if !self.is_in_facet(facet, &center) {
+            // ближайший к центру подотрезок границы грани
+            let nearest = facet_segments.iter().min_by(|&&(a, b), &&(c, d)| {
+                self.get_distance2_to_middle(a, b, &center).cmp(&self.get_distance2_to_middle(c, d, &center))
+            });
+            return match nearest {
+                Some(&(u, v)) => self.split_segment(u, v),
+                None => Err("Facet has no boundary segments!")
+            };
+        }
+
+        match self.locate(&center) {
+            // без точной арифметики центр может совпасть с вершиной грани, тогда восстановление не продвигается
+            Location::Vertex(i) if self.facets[facet].points.contains(&i) => return Err("Boundary recovery doesn't converge!"),
+            Location::Vertex(i) => self.add_point_to_facet(facet, i),
+            Location::Cavity(cavity) => {
+                let destroyed = self.get_destroyed_segments(&cavity);
+                if destroyed.is_empty() {
+                    let i = self.insert(center, cavity)?;
+                    self.add_point_to_facet(facet, i);
+                } else {
+                    for (u, v) in destroyed {
+                        self.split_segment(u, v)?;
+                    }
+                }
+            },
+            Location::Outside => return Err("Steiner point lies outside the triangulation!")
+        }
+        return Ok(());
+    }
+
+    fn is_in_diametral_sphere(&self, u: usize, v: usize, p: &Point) -> bool {
+        let a: Vector = &self.points[u] - p;
+        let b: Vector = &self.points[v] - p;
+        return a.dot_product(&b).is_it_negative();
+    }
+
+    fn get_distance2_to_middle(&self, u: usize, v: usize, p: &Point) -> Number {
+        let e: Vector = &self.points[v] - &self.points[u];
+        let middle = &self.points[u] + &(e * Number::new(0.5));
+        return (&middle - p).length2();
+    }
+
+    // точка лежит в плоскости грани
+    fn is_in_facet(&self, facet: usize, p: &Point) -> bool {
+        let facet = &self.facets[facet];
+        let c = &facet.corners;
+        let ps = &self.points;
+        return (0..3).all(|j| {
+            let (a, b) = (&ps[c[j]], &ps[c[(j + 1) % 3]]);
+            orient3d(a, b, p, &(a + &facet.down)) != Ordering::Less
+        });
+    }
+
+    fn add_point_to_facet(&mut self, facet: usize, i: usize) {
+        if self.facets[facet].points.contains(&i) {
+            return;
+        }
+        self.facets[facet].points.push(i);
+        for f in self.facets[facet].subfaces.iter() {
+            self.subfaces.remove(&get_sorted(f));
+        }
+        let subfaces = self.triangulate_facet(facet);
+        for f in subfaces.iter() {
+            self.subfaces.insert(get_sorted(f), facet);
+        }
+        self.facets[facet].subfaces = subfaces;
+    }
+
+    // триангуляция Делоне точек грани в ее плоскости: окружность через a, b, c - сечение сферы через a, b, c
+    // и точку под гранью
+    fn triangulate_facet(&self, facet: usize) -> Vec<[usize; 3]> {
+        let facet = &self.facets[facet];
+        let ps = &self.points;
+        let down = &facet.down;
+        let mut ts: Vec<[usize; 3]> = vec![facet.corners];
+        for &q in facet.points.iter().skip(3) {
+            let (cavity, rest): (Vec<[usize; 3]>, Vec<[usize; 3]>) = ts.into_iter().partition(|t| {
+                let below = &ps[t[0]] + down;
+                insphere(&ps[t[0]], &ps[t[1]], &ps[t[2]], &below, &ps[q]) == Ordering::Greater
+            });
+            let mut edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+            for t in cavity.iter() {
+                for j in 0..3 {
+                    edges.insert((t[j], t[(j + 1) % 3]));
+                }
+            }
+            ts = rest;
+            for &(a, b) in edges.iter() {
+                // ребро границы грани, на котором лежит новая точка, не образует треугольника
+                if !edges.contains(&(b, a)) && orient3d(&ps[a], &ps[b], &ps[q], &(&ps[a] + down)) == Ordering::Greater {
+                    ts.push([a, b, q]);
+                }
+            }
+        }
+        return ts;
+    }
+
+    fn get_triangle_circumcenter(&self, f: &[usize; 3]) -> Point {
+        let a = &self.points[f[0]];
+        let u: Vector = &self.points[f[1]] - a;
+        let v: Vector = &self.points[f[2]] - a;
+        let n = u.cross_product(&v);
+        let numerator = v.cross_product(&n) * u.length2() + n.cross_product(&u) * v.length2();
+        let denominator = n.length2() * Number::new(2.);
+        return a + &(numerator * (Number::new(1.) / denominator));
+    }
+
+    fn get_circumcenter(&self, t: &[usize; 4]) -> Point {
+        let a = &self.points[t[0]];
+        let u: Vector = &self.points[t[1]] - a;
+        let v: Vector = &self.points[t[2]] - a;
+        let w: Vector = &self.points[t[3]] - a;
+        let numerator = v.cross_product(&w) * u.length2() + w.cross_product(&u) * v.length2() + u.cross_product(&v) * w.length2();
+        let denominator = u.mixed_product(&v, &w) * Number::new(2.);
+        return a + &(numerator * (Number::new(1.) / denominator));
+    }
+
+    fn get_centroid(&self, t: &[usize; 4]) -> Point {
+        let a = &self.points[t[0]];
+        let sum = &(&(&self.points[t[1]] - a) + &(&self.points[t[2]] - a)) + &(&self.points[t[3]] - a);
+        return a + &(sum * Number::new(0.25));
+    }
+
+    fn does_cavity_keep_boundary(&self, cavity: &Vec<usize>) -> bool {
+        return self.get_destroyed_segments(cavity).is_empty() && self.get_destroyed_subfaces(cavity).is_empty();
+    }
+
+    fn is_too_big(&self, t: &[usize; 4]) -> bool {
+        let ps = &self.points;
+        if let Some(ref max_volume6) = self.max_volume6 {
+            let a = &ps[t[0]];
+            let (u, v, w): (Vector, Vector, Vector) = (&ps[t[1]] - a, &ps[t[2]] - a, &ps[t[3]] - a);
+            return &u.mixed_product(&v, &w).abs() > max_volume6;
+        }
+        return false;
+    }
+
+    fn is_bad(&self, t: &[usize; 4]) -> bool {
+        let ps = &self.points;
+        if self.is_too_big(t) {
+            return true;
+        }
+        if let Some(ref ratio2) = self.ratio2 {
+            let r2 = (&ps[t[0]] - &self.get_circumcenter(t)).length2();
+            let mut min2: Option<(Number, usize, usize)> = None;
+            for j in 0..4 {
+                for k in (j + 1)..4 {
+                    let l2 = (&ps[t[j]] - &ps[t[k]]).length2();
+                    if min2.as_ref().map_or(true, |m| l2 < m.0) {
+                        min2 = Some((l2, t[j], t[k]));
+                    }
+                }
+            }
+            let (l2, p, q) = min2.unwrap();
+            return r2 > ratio2 * &l2 && !self.is_seditious(p, q);
+        }
+        return false;
+    }
+
+    fn is_seditious(&self, p: usize, q: usize) -> bool {
+        // ребро между точками на одной оболочке двух отрезков, сходящихся под острым углом
+        let (s1, s2) = match (self.origins.get(&p), self.origins.get(&q)) {
+            (Some(s1), Some(s2)) => (*s1, *s2),
+            _ => return false
+        };
+        if s1 == s2 || s1 == (s2.1, s2.0) {
+            return false;
+        }
+
+        let apex = if s1.0 == s2.0 || s1.0 == s2.1 {
+            s1.0
+        } else if s1.1 == s2.0 || s1.1 == s2.1 {
+            s1.1
+        } else {
+            return false;
+        };
+        let other1 = if s1.0 == apex {s1.1} else {s1.0};
+        let other2 = if s2.0 == apex {s2.1} else {s2.0};
+
+        let ps = &self.points;
+        let e1: Vector = &ps[other1] - &ps[apex];
+        let e2: Vector = &ps[other2] - &ps[apex];
+        if !e1.dot_product(&e2).is_it_positive() {
+            return false;
+        }
+
+        let d1 = (&ps[p] - &ps[apex]).length2().convert_to_f64();
+        let d2 = (&ps[q] - &ps[apex]).length2().convert_to_f64();
+        return (d1 - d2).abs() <= 1e-6 * d1.max(d2);
+    }
+
+    fn refine(&mut self) -> Result<(), &'static str> {
+        if self.ratio2.is_none() && self.max_volume6.is_none() {
+            return Ok(());
+        }
+        loop {
+            let inside = self.get_domain();
+            let bad: Vec<[usize; 4]> = self.tets.iter().zip(inside)
+                .filter_map(|(t, inside)| match *t {
+                    Some(t) if inside && !self.skipped.contains(&t) && self.is_bad(&t) => Some(t),
+                    _ => None
+                })
+                .collect();
+            if bad.is_empty() {
+                return Ok(());
+            }
+
+            for t in bad {
+                // тетраэдр мог исчезнуть после предыдущих вставок
+                if !self.has_tet(&t) {
+                    continue;
+                }
+                let center = self.get_circumcenter(&t);
+                match self.locate(&center) {
+                    Location::Cavity(cavity) if self.preserve_boundary => {
+                        // центр, разрушающий границу, заменяется центром масс слишком большого тетраэдра,
+                        // иначе тетраэдр пропускается
+                        if self.does_cavity_keep_boundary(&cavity) {
+                            self.insert(center, cavity)?;
+                        } else if !self.is_too_big(&t) {
+                            self.skipped.insert(t);
+                        } else {
+                            let centroid = self.get_centroid(&t);
+                            match self.locate(&centroid) {
+                                Location::Cavity(ref cavity) if self.does_cavity_keep_boundary(cavity) => {
+                                    self.insert(centroid, cavity.clone())?;
+                                },
+                                _ => {
+                                    self.skipped.insert(t);
+                                }
+                            }
+                        }
+                    },
+                    Location::Cavity(cavity) => {
+                        // центры, разрушающие границу, заменяются точками на границе
+                        let mut segments = self.get_destroyed_segments(&cavity);
+                        segments.extend(self.get_encroached_segments(&cavity, &center));
+                        let mut subfaces = self.get_destroyed_subfaces(&cavity);
+                        subfaces.extend(self.get_encroached_subfaces(&cavity, &center));
+                        if !segments.is_empty() {
+                            for (u, v) in segments {
+                                self.split_segment(u, v)?;
+                            }
+                        } else if let Some(&(facet, f)) = subfaces.first() {
+                            self.split_subface(facet, f)?;
+                        } else {
+                            self.insert(center, cavity)?;
+                        }
+                    },
+                    _ => {
+                        self.skipped.insert(t);
+                    }
+                }
+                self.recover_boundary()?;
+            }
+        }
+    }
+
+    // обход в ширину от внешних тетраэдров: пересечение грани границы меняет четность глубины
+    fn get_domain(&self) -> Vec<bool> {
+        let mut seeds: Vec<(usize, usize)> = Vec::new();
+        for (i, t) in self.tets.iter().enumerate() {
+            if let Some(t) = *t {
+                if t.iter().any(|&v| self.is_super(v)) {
+                    seeds.push((i, 0));
+                }
+            }
+        }
+
+        return get_parity_domain(self.tets.len(), seeds, |i| {
+            return get_faces(&self.tets[i].unwrap()).iter()
+                .filter_map(|f| self.get_neighbour(f).map(|n| (n, self.subfaces.contains_key(&get_sorted(f)))))
+                .collect();
+        });
+    }
+
+    fn get_tet_mesh(&self) -> Result<TetMesh, &'static str> {
+        let inside = self.get_domain();
+        let mut used: BTreeSet<usize> = BTreeSet::new();
+        for (t, &inside) in self.tets.iter().zip(inside.iter()) {
+            if let Some(t) = *t {
+                if inside {
+                    used.extend(t.iter().cloned());
+                }
+            }
+        }
+        let indices: HashMap<usize, usize> = used.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let points: Vec<Point> = used.iter().map(|&v| self.points[v].clone()).collect();
+
+        let mut tetrahedra: Vec<[usize; 4]> = Vec::new();
+        let mut boundary_faces: Vec<([usize; 3], usize)> = Vec::new();
+        for (t, inside) in self.tets.iter().zip(inside) {
+            let t = match *t {
+                Some(t) if inside => t,
+                _ => continue
+            };
+            // положительный объем у тетраэдров с противоположной ориентацией
+            tetrahedra.push([indices[&t[0]], indices[&t[1]], indices[&t[3]], indices[&t[2]]]);
+            for f in get_faces(&t).iter() {
+                if let Some(&facet) = self.subfaces.get(&get_sorted(f)) {
+                    boundary_faces.push(([indices[&f[0]], indices[&f[1]], indices[&f[2]]], self.facets[facet].marker));
+                }
+            }
+        }
+        return TetMesh::new(points, tetrahedra, boundary_faces);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use modeling::*;
+
+    fn check(mesh: &Mesh, tet_mesh: &TetMesh) {
+        assert_eq!(tet_mesh.get_volume(), mesh.get_volume());
+        let boundary = tet_mesh.get_boundary_mesh();
+        assert!(boundary.geometry_check());
+        assert_eq!(boundary.get_volume(), mesh.get_volume());
+        for p in mesh.get_points().values() {
+            assert!(tet_mesh.get_points().contains(p));
+        }
+    }
+
+    #[test]
+    fn tetrahedralization_of_box() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 1., 3.)).unwrap();
+        let tet_mesh = tetrahedralize_conforming(&mesh, None, None).unwrap();
+        check(&mesh, &tet_mesh);
+
+        // у каждой стороны коробки свой маркер
+        let mut markers: Vec<usize> = tet_mesh.get_boundary_faces().iter().map(|&(_, marker)| marker).collect();
+        markers.sort();
+        markers.dedup();
+        assert_eq!(markers, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn tetrahedralization_of_nonconvex_solids() {
+        let torus = create_torus(Number::new(2.), Number::new(1.), 8, 4).unwrap();
+        let tet_mesh = tetrahedralize_conforming(&torus, None, None).unwrap();
+        check(&torus, &tet_mesh);
+
+        let wedge = create_wedge(Number::new(3.), Number::new(1.), Number::new(1.)).unwrap();
+        let tet_mesh = tetrahedralize_conforming(&wedge, None, None).unwrap();
+        check(&wedge, &tet_mesh);
+    }
+
+    #[test]
+    fn quality_refinement() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(4., 1., 1.)).unwrap();
+        let max_volume = Number::new(0.125);
+        let tet_mesh = tetrahedralize_conforming(&mesh, Some(2.), Some(max_volume.clone())).unwrap();
+        check(&mesh, &tet_mesh);
+        for i in 0..tet_mesh.num_of_tetrahedra() {
+            assert!(tet_mesh.get_tetrahedron_volume(i) <= max_volume);
+        }
+        assert!(tet_mesh.num_of_tetrahedra() >= 32);
+    }
+
+    #[test]
+    fn constrained_tetrahedralization() {
+        let mut mesh = Mesh::new();
+        let o = Point::new_from_f64(0., 0., 0.);
+        let x = Point::new_from_f64(2., 0., 0.);
+        let y = Point::new_from_f64(0., 2., 0.);
+        let z = Point::new_from_f64(0., 0., 2.);
+        mesh.add_triangle(Triangle::new(vec![o.clone(), y.clone(), x.clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![o.clone(), x.clone(), z.clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![o.clone(), z.clone(), y.clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![x.clone(), y.clone(), z.clone()])).unwrap();
+
+        let max_volume = Number::new(0.25);
+        let tet_mesh = tetrahedralize_with_delaunay_boundary(&mesh, Some(2.), Some(max_volume.clone())).unwrap();
+        check(&mesh, &tet_mesh);
+        assert!(tet_mesh.num_of_tetrahedra() > 1);
+        // треугольники границы не разбиваются
+        assert_eq!(tet_mesh.get_boundary_faces().len(), 4);
+
+        // диагонали сторон коробки не все являются ребрами триангуляции Делоне ее вершин
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 1., 3.)).unwrap();
+        assert!(tetrahedralize_with_delaunay_boundary(&mesh, None, None).is_err());
+    }
+
+    #[test]
+    fn wrong_input() {
+        let mesh = create_box(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 1., 1.)).unwrap();
+        assert!(tetrahedralize_conforming(&mesh, Some(0.5), None).is_err());
+        assert!(tetrahedralize_conforming(&mesh, Some(1.), None).is_err());
+        assert!(tetrahedralize_conforming(&mesh, None, Some(Number::new(0.))).is_err());
+
+        let mut open = Mesh::new();
+        open.add_triangle(Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 1., 0.)
+        ])).unwrap();
+        assert!(tetrahedralize_conforming(&open, None, None).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+// общие для тестов полигональные сетки и вспомогательные функции
+use primitives::*;
+use std::collections::BTreeSet;
+
+// ориентированная наружу сетка параллелепипеда из 12 треугольников
+pub(crate) fn box_mesh(min: &Point, max: &Point) -> Mesh {
+    let p = |i: bool, j: bool, k: bool| -> Point {
+        Point::new(
+            if i {max.x.clone()} else {min.x.clone()},
+            if j {max.y.clone()} else {min.y.clone()},
+            if k {max.z.clone()} else {min.z.clone()}
+        )
+    };
+    let quads = vec![
+        [p(false,false,false), p(false,true,false), p(true,true,false), p(true,false,false)],
+        [p(false,false,true), p(true,false,true), p(true,true,true), p(false,true,true)],
+        [p(false,false,false), p(true,false,false), p(true,false,true), p(false,false,true)],
+        [p(false,true,false), p(false,true,true), p(true,true,true), p(true,true,false)],
+        [p(false,false,false), p(false,false,true), p(false,true,true), p(false,true,false)],
+        [p(true,false,false), p(true,true,false), p(true,true,true), p(true,false,true)],
+    ];
+
+    let mut mesh = Mesh::new();
+    for q in quads {
+        mesh.add_triangle(Triangle::new(vec![q[0].clone(), q[1].clone(), q[2].clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![q[0].clone(), q[2].clone(), q[3].clone()])).unwrap();
+    }
+    return mesh;
+}
+
+// единичный куб [0, 1]^3
+pub(crate) fn cube_mesh() -> Mesh {
+    return box_mesh(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 1., 1.));
+}
+
+// суммарная ориентированная площадь многоугольников леса (площади отверстий отрицательны)
+pub(crate) fn forest_area(forest: &Vec<PolygonTreeNode>) -> Number {
+    let mut s = Number::new(0.);
+    let mut nodes: Vec<&PolygonTreeNode> = forest.iter().collect();
+    while let Some(node) = nodes.pop() {
+        s = s + node.get_cur_polygon().signed_area();
+        nodes.extend(node.get_children_ref().iter());
+    }
+    return s;
+}
+
+// многоугольник в плоскости xy с нормалью +z
+pub(crate) fn polygon(points: Vec<(f64, f64)>) -> Polygon {
+    return Polygon::new(
+        points.into_iter().map(|(x, y)| Point::new_from_f64(x, y, 0.)).collect(),
+        Vector::new_from_f64(0., 0., 1.)
+    );
+}
+
+// каждое ориентированное ребро встречается один раз вместе с противоположным, объем положителен
+pub(crate) fn check_closed_and_oriented(mesh: &Mesh) {
+    let mut edges: BTreeSet<(Point, Point)> = BTreeSet::new();
+    for it in mesh.get_it_iterator() {
+        let ps = mesh.get_triangle(it).get_points();
+        for k in 0..3 {
+            assert!(edges.insert((ps[k].clone(), ps[(k + 1) % 3].clone())));
+        }
+    }
+
+    for &(ref a, ref b) in edges.iter() {
+        assert!(edges.contains(&(b.clone(), a.clone())));
+    }
+    assert!(mesh.geometry_check());
+    assert!(mesh.get_volume().is_it_positive());
+}
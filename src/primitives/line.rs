@@ -2,16 +2,19 @@ use primitives::point::Point;
 use primitives::vector::Vector;
 use std::fmt;
 use primitives::segment::Segment;
+use primitives::number::*;
 
 
-#[derive(Debug)]
-pub struct Line {
-    pub org : Point,
-    pub dest: Point
+#[derive(Clone, Debug)]
+pub struct Line<T = Number> {
+    pub org : Point<T>,
+    pub dest: Point<T>
 }
 
-impl Line {
-    pub fn new(org : Point, dest : Point) -> Line {
+impl<T> Line<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(org : Point<T>, dest : Point<T>) -> Line<T> {
         if org == dest {
             panic!("org == dest");
         }
@@ -22,20 +25,20 @@ impl Line {
         }
     }
 
-    pub fn convert_to_segment(self) -> Segment {
+    pub fn convert_to_segment(self) -> Segment<T> {
         Segment {org: self.org, dest: self.dest}
     }
 
-    pub fn gen_segment(&self) -> Segment {
+    pub fn gen_segment(&self) -> Segment<T> {
         Segment {org: self.org.clone(), dest: self.dest.clone()}
     }
 
-    pub fn  get_dir_vector(&self) -> Vector {
+    pub fn  get_dir_vector(&self) -> Vector<T> {
         &self.dest - &self.org
     }
 
     #[allow(dead_code)]
-    pub fn check_accessory(&self, point : &Point) -> bool {
+    pub fn check_accessory(&self, point : &Point<T>) -> bool {
         let dir_vec = self.get_dir_vector();
         let check_vec = &self.org - point;
         let cp = dir_vec.cross_product(&check_vec);
@@ -43,7 +46,9 @@ impl Line {
     }
 }
 
-impl fmt::Display for Line {
+impl<T> fmt::Display for Line<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "(Line: {}, {})", self.org, self.dest)
     }
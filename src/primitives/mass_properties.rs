@@ -0,0 +1,199 @@
+use primitives::*;
+
+/// This structure contains mass properties of a closed `Mesh` filled with a material of unit density.
+/// All values except the surface area are exact. They are calculated using the divergence theorem:
+/// each triangle together with the origin forms a signed tetrahedron, and the integrals over the solid
+/// are summed up from the integrals over these tetrahedrons.
+#[derive(Clone, Debug)]
+pub struct MassProperties {
+    /// The signed volume. It's positive if the normals of the mesh are directed outwards.
+    pub volume: Number,
+    /// The exact squares of the triangle areas.
+    pub squared_areas: Vec<Number>,
+    /// The approximate surface area, i.e. the sum of square roots of `squared_areas`.
+    pub surface_area: f64,
+    /// The center of mass. It's `None` if the volume is zero.
+    pub centroid: Option<Point>,
+    /// The inertia tensor around the centroid. It's `None` if the volume is zero.
+    pub inertia_tensor: Option<[[Number; 3]; 3]>
+}
+
+impl MassProperties {
+    /// This method calculates mass properties of the `mesh`.
+    /// The mesh has to be closed and consistently oriented, otherwise the result has no physical meaning.
+    /// # Arguments
+    ///
+    /// * `mesh` - A mesh to calculate properties for.
+    pub fn new(mesh: &Mesh) -> MassProperties {
+        let zero = Number::zero();
+
+        // six volumes of signed tetrahedrons
+        let mut volume6 = zero.clone();
+        // the first moment multiplied by 24
+        let mut moment24 : Vector = Vector::new(zero.clone(), zero.clone(), zero.clone());
+        // the second moments multiplied by 120
+        let mut covariance120 : [[Number; 3]; 3] = zero_3x3();
+
+        let mut squared_areas : Vec<Number> = Vec::new();
+        let mut surface_area : f64 = 0.;
+
+        for it in mesh.get_it_iterator() {
+            let t = mesh.get_triangle(it);
+            let squared_area = t.get_squared_area();
+            surface_area += squared_area.clone().convert_to_f64().sqrt();
+            squared_areas.push(squared_area);
+
+            let a = t.get_ref(0).get_vector();
+            let b = t.get_ref(1).get_vector();
+            let c = t.get_ref(2).get_vector();
+
+            let v6 = a.mixed_product(&b, &c);
+            let s = &(&a + &b) + &c;
+
+            volume6 = volume6 + &v6;
+            moment24 = moment24 + &s * v6.clone();
+
+            let a = coordinates(&a);
+            let b = coordinates(&b);
+            let c = coordinates(&c);
+            let s = coordinates(&s);
+            for i in 0..3 {
+                for j in i..3 {
+                    let sum = &a[i]*&a[j] + &b[i]*&b[j] + &c[i]*&c[j] + &s[i]*&s[j];
+                    covariance120[i][j] = &covariance120[i][j] + &(&v6 * &sum);
+                }
+            }
+        }
+
+        let volume = volume6 / Number::new(6.);
+
+        if volume.is_it_zero() {
+            return MassProperties {
+                volume,
+                squared_areas,
+                surface_area,
+                centroid: None,
+                inertia_tensor: None
+            };
+        }
+
+        let moment = moment24 * (Number::new(1.) / Number::new(24.));
+        let centroid = moment * (Number::new(1.) / &volume);
+        let cc = coordinates(&centroid);
+
+        // the covariance around the centroid: C' = C - V*c*c^T
+        let mut covariance : [[Number; 3]; 3] = zero_3x3();
+        for i in 0..3 {
+            for j in i..3 {
+                let value = &covariance120[i][j] / &Number::new(120.) - &volume * &(&cc[i] * &cc[j]);
+                covariance[i][j] = value.clone();
+                covariance[j][i] = value;
+            }
+        }
+
+        // I = tr(C')*E - C'
+        let trace = &(&covariance[0][0] + &covariance[1][1]) + &covariance[2][2];
+        let mut inertia_tensor : [[Number; 3]; 3] = zero_3x3();
+        for i in 0..3 {
+            for j in 0..3 {
+                inertia_tensor[i][j] = if i == j {
+                    &trace - &covariance[i][j]
+                } else {
+                    -&covariance[i][j]
+                };
+            }
+        }
+
+        return MassProperties {
+            volume,
+            squared_areas,
+            surface_area,
+            centroid: Some(centroid.get_point()),
+            inertia_tensor: Some(inertia_tensor)
+        };
+    }
+}
+
+fn zero_3x3() -> [[Number; 3]; 3] {
+    [
+        [Number::zero(), Number::zero(), Number::zero()],
+        [Number::zero(), Number::zero(), Number::zero()],
+        [Number::zero(), Number::zero(), Number::zero()]
+    ]
+}
+
+fn coordinates(v: &Vector) -> [Number; 3] {
+    [v.x.clone(), v.y.clone(), v.z.clone()]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+
+    #[test]
+    fn unit_cube_properties() {
+        let mesh = cube_mesh();
+        let mp = mesh.get_mass_properties();
+
+        assert_eq!(mp.volume, Number::new(1.));
+        assert_eq!(mp.squared_areas.len(), 12);
+        assert!((mp.surface_area - 6.).abs() < 1e-12);
+        assert_eq!(mp.centroid.unwrap(), Point::new_from_f64(0.5, 0.5, 0.5));
+
+        let it = mp.inertia_tensor.unwrap();
+        let one_sixth = Number::new(1.) / Number::new(6.);
+        for i in 0..3 {
+            for j in 0..3 {
+                if i == j {
+                    assert_eq!(it[i][j], one_sixth);
+                } else {
+                    assert!(it[i][j].is_it_zero());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn box_properties() {
+        // a box 1x2x3 has the inertia tensor diag((b^2 + c^2)/12, (a^2 + c^2)/12, (a^2 + b^2)/12)*V
+        let mesh = box_mesh(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(1., 2., 3.));
+        let mp = mesh.get_mass_properties();
+
+        assert_eq!(mp.volume, Number::new(6.));
+        assert!((mp.surface_area - 22.).abs() < 1e-12);
+        assert_eq!(mp.centroid.unwrap(), Point::new_from_f64(0.5, 1., 1.5));
+
+        let it = mp.inertia_tensor.unwrap();
+        assert_eq!(it[0][0], Number::new(13.) / Number::new(2.));
+        assert_eq!(it[1][1], Number::new(5.));
+        assert_eq!(it[2][2], Number::new(5.) / Number::new(2.));
+        assert!(it[0][1].is_it_zero());
+    }
+
+    #[test]
+    fn reversed_cube_has_negative_volume() {
+        let mesh = cube_mesh();
+        let mut reversed = Mesh::new();
+        for it in mesh.get_it_iterator() {
+            reversed.add_triangle(mesh.get_reversed_triangle(it)).unwrap();
+        }
+        assert_eq!(reversed.get_volume(), Number::new(-1.));
+    }
+
+    #[test]
+    fn open_surface_has_no_centroid() {
+        let mut mesh = Mesh::new();
+        mesh.add_triangle(Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 1., 0.)
+        ])).unwrap();
+
+        let mp = mesh.get_mass_properties();
+        assert!(mp.volume.is_it_zero());
+        assert!(mp.centroid.is_none());
+        assert_eq!(mp.squared_areas, vec![Number::new(1.) / Number::new(4.)]);
+    }
+}
@@ -21,13 +21,14 @@ use std::io::Cursor;
 use time::PreciseTime;
 
 use primitives::triangle::Triangle;
+use primitives::mass_properties::MassProperties;
+use primitives::transform::Transform;
 
 
-#[derive(Hash)]
+#[derive(Debug, Hash)]
 #[derive(Clone)]
-#[derive(Debug)]
-pub(crate) struct MeshTriangle {
-    pub normal: vector::Vector,
+pub(crate) struct MeshTriangle<T = Number> {
+    pub normal: vector::Vector<T>,
     // Indexes of PointS in this triangle
     pub ips : Vec<usize>,
     pub attr_byte_count: u16,
@@ -36,8 +37,10 @@ pub(crate) struct MeshTriangle {
 }
 
 
-impl MeshTriangle {
-    fn new(/*ps : &Vec<point::Point>,*/ normal: vector::Vector) -> MeshTriangle {
+impl<T> MeshTriangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn new(/*ps : &Vec<point::Point<T>>,*/ normal: vector::Vector<T>) -> MeshTriangle<T> {
         MeshTriangle {
             normal: normal,
             ips: Vec::new(),
@@ -46,7 +49,7 @@ impl MeshTriangle {
         }
     }
 
-    pub fn get_normal(&self) -> vector::Vector {
+    pub fn get_normal(&self) -> vector::Vector<T> {
         self.normal.clone()
     }
 
@@ -67,8 +70,10 @@ impl MeshTriangle {
 }
 
 
-impl PartialEq for MeshTriangle {
-    fn eq(&self, rhs: &MeshTriangle) -> bool {
+impl<T> PartialEq for MeshTriangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn eq(&self, rhs: &MeshTriangle<T>) -> bool {
         for i in 0..2 {
             if self.ips[i] != rhs.ips[i] {
                 return false;
@@ -79,11 +84,13 @@ impl PartialEq for MeshTriangle {
     }
 }
 
-impl Eq for MeshTriangle {}
+impl<T> Eq for MeshTriangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{}
 
 
 /// It is an alias for BinaryStlFile
-pub type Mesh = BinaryStlFile;
+pub type Mesh<T = Number> = BinaryStlFile<T>;
 
 struct BinaryStlHeader {
     pub header: [u8; 80],
@@ -109,23 +116,25 @@ impl fmt::Debug for BinaryStlHeader {
 
 
 /// This class represents a geometry topology of polygonal mesh.
-#[derive(Clone)]
-#[derive(Debug)]
-pub struct BinaryStlFile {
+/// Coordinates of points are `Number` by default, but any type implementing `NumberTrait` can be used.
+#[derive(Clone, Debug)]
+pub struct BinaryStlFile<T = Number> {
     header: BinaryStlHeader,
-    index_to_triangle: HashMap<usize, MeshTriangle>,
-    ip_to_p: HashMap<usize, point::Point>,
-    p_to_ip: HashMap<point::Point, usize>,
+    index_to_triangle: HashMap<usize, MeshTriangle<T>>,
+    ip_to_p: HashMap<usize, point::Point<T>>,
+    p_to_ip: HashMap<point::Point<T>, usize>,
     ip_to_its: HashMap<usize, Vec<usize>>,
     max_tr_index: usize
 }
 
 
-impl BinaryStlFile {
+impl<T> BinaryStlFile<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
 
 
     /// This method returns a new empty BinaryStlFile
-    pub fn new() -> BinaryStlFile {
+    pub fn new() -> BinaryStlFile<T> {
         BinaryStlFile {
             header: BinaryStlHeader { header: [0u8; 80], num_triangles: 0 },
             index_to_triangle: HashMap::new(),
@@ -146,11 +155,31 @@ impl BinaryStlFile {
         return self.index_to_triangle.len();
     }
 
+    /// This method returns the exact signed volume, enclosed by the mesh.
+    /// It's positive if normals are directed outwards.
+    pub fn get_volume(&self) -> T {
+        let mut volume6 = T::zero();
+        for it in self.get_it_iterator() {
+            let t = self.get_triangle(it);
+            volume6 = volume6 + t.get_ref(0).get_vector().mixed_product(&t.get_ref(1).get_vector(), &t.get_ref(2).get_vector());
+        }
+        return volume6 / T::new(6.);
+    }
+
+    /// This method returns the approximate area of the surface.
+    pub fn get_surface_area(&self) -> f64 {
+        let mut area : f64 = 0.;
+        for it in self.get_it_iterator() {
+            area += self.get_triangle(it).get_area();
+        }
+        return area;
+    }
+
     /// This method adds a triangle to the topology. It does not check if this triangle was added before or wasn't.
     /// # Arguments
     ///
     /// * `tr` - A triangle to add
-    pub fn add_triangle(&mut self, tr : Triangle) -> Result<usize> {
+    pub fn add_triangle(&mut self, tr : Triangle<T>) -> Result<usize> {
         if tr.degradation_level() != 0
         {
             // panic!("ERROR: useless triangle {:?}", tr);
@@ -204,11 +233,24 @@ impl BinaryStlFile {
         return Ok(it);
     }
 
+    /// This method creates a copy of the mesh with coordinates of another number type, e.g. `f64` for fast previews.
+    /// Coordinates are converted through `f64`. Triangles, which become degenerate after the conversion, are skipped.
+    pub fn convert<U>(&self) -> Mesh<U>
+        where U: NumberTrait<U>, for<'b> &'b U: NumberRef<U>
+    {
+        let mut mesh = Mesh::new();
+        for it in self.get_it_iterator() {
+            let ps : Vec<point::Point<U>> = self.get_triangle(it).get_points_ref().iter().map(|p| p.convert()).collect();
+            mesh.add_triangle(Triangle::new(ps)).ok();
+        }
+        return mesh;
+    }
+
     /// This method adds each triangle from a vector of triangles to the topology.
     /// # Arguments
     ///
     /// * `ts` - A vector of triangles
-    pub fn add_triangles(&mut self, ts : Vec<Triangle>) -> Vec<usize> {
+    pub fn add_triangles(&mut self, ts : Vec<Triangle<T>>) -> Vec<usize> {
         let mut indexes: Vec<usize> = Vec::new();
         for t in ts {
             indexes.push(self.add_triangle(t).unwrap());
@@ -216,21 +258,21 @@ impl BinaryStlFile {
         return indexes;
     }
 
-    fn read_point<T: ReadBytesExt>(input: &mut T) -> Result<point::Point> {
+    fn read_point<R: ReadBytesExt>(input: &mut R) -> Result<point::Point<T>> {
 
         let x1 = input.read_f32::<LittleEndian>()?;
 
         let x2 = input.read_f32::<LittleEndian>()?;
         let x3 = input.read_f32::<LittleEndian>()?;
 
-        Ok(point::Point {x: Number::new_from_f32(x1), y: Number::new_from_f32(x2), z: Number::new_from_f32(x3)})
+        Ok(point::Point {x: T::new_from_f32(x1), y: T::new_from_f32(x2), z: T::new_from_f32(x3)})
 
         //Ok(point::Point {x: number::new_from_f32(0.), y: number::new_from_f32(0.), z: number::new_from_f32(0.)})
         // return Err(Error::new(ErrorKind::Other, "bbb"));
     }
 
-    fn read_triangle<T: ReadBytesExt>(&mut self, input: &mut T) -> Result<()> {
-        /*let normal : vector::Vector =*/ Mesh::read_point(input)?.convert_to_vector();
+    fn read_triangle<R: ReadBytesExt>(&mut self, input: &mut R) -> Result<()> {
+        /*let normal : vector::Vector<T> =*/ Mesh::read_point(input)?.convert_to_vector();
 
         let v1 = Mesh::read_point(input)?;
         let v2 = Mesh::read_point(input)?;
@@ -245,11 +287,11 @@ impl BinaryStlFile {
         Ok(())
     }
 
-    pub(crate) fn get_normal_by_index(&self, index: usize) -> vector::Vector {
+    pub(crate) fn get_normal_by_index(&self, index: usize) -> vector::Vector<T> {
         return self.index_to_triangle[&index].get_normal()
     }
 
-    pub(crate) fn get_plane_by_index(&self, index: usize) -> Plane {
+    pub(crate) fn get_plane_by_index(&self, index: usize) -> Plane<T> {
         let p0_index = self.index_to_triangle[&index].ips[0];
         Plane::new(
             self.get_normal_by_index(index),
@@ -279,7 +321,7 @@ impl BinaryStlFile {
 
     }
 
-    pub(crate) fn get_indexes_of_triangles_by_two_points(&self, p1: &point::Point, p2: &point::Point) -> Option<(usize, usize)> {
+    pub(crate) fn get_indexes_of_triangles_by_two_points(&self, p1: &point::Point<T>, p2: &point::Point<T>) -> Option<(usize, usize)> {
         let opt_ip1 = self.p_to_ip.get(p1);
         let opt_ip2 = self.p_to_ip.get(p2);
         if opt_ip1.is_none() || opt_ip2.is_none() {
@@ -325,7 +367,7 @@ impl BinaryStlFile {
         }
     }
 
-    fn read_header<T: ReadBytesExt>(input: &mut T) -> Result<BinaryStlHeader> {
+    fn read_header<R: ReadBytesExt>(input: &mut R) -> Result<BinaryStlHeader> {
         let mut header = [0u8; 80];
 
         match input.read(&mut header) {
@@ -344,40 +386,7 @@ impl BinaryStlFile {
         Ok(BinaryStlHeader{ header: header, num_triangles: num_triangles })
     }
 
-    /// This static method reads a data from the `input` in STL format and creates a new topology.
-    /// # Arguments
-    ///
-    /// * `input` - A type, implementing ReadBytesExt.
-    pub fn read_stl<T: ReadBytesExt>(input: &mut T) -> Result<BinaryStlFile> {
-        // read the header
-        let start = PreciseTime::now();
-        info!("Reading model ...");
-
-        let header = Mesh::read_header(input)?;
-        let mut mesh = Mesh::new();
-
-        // read the whole file
-        let mut buffer = Vec::new();
-        input.read_to_end(&mut buffer)?;
-        let mut cursor = Cursor::new(buffer);
-
-
-        info!("Number of triangles is {:?}", header.num_triangles);
-        for i in 0 .. header.num_triangles {
-            //if i > 10000 {
-            //    break;
-            //}
-            debug!("Iterration number {:?}", i);
-
-            mesh.read_triangle(&mut cursor)?;
-        }
-
-        let end = PreciseTime::now();
-        info!("<read_stl> is finished in {0} seconds\n", start.to(end));
-        Ok(mesh)
-    }
-
-    fn write_point<T: WriteBytesExt>(out: &mut T, p: &point::Point) -> Result<()> {
+    fn write_point<W: WriteBytesExt>(out: &mut W, p: &point::Point<T>) -> Result<()> {
 
         out.write_f32::<LittleEndian>(p.x.clone().convert_to_f32())?;
         out.write_f32::<LittleEndian>(p.y.clone().convert_to_f32())?;
@@ -390,9 +399,9 @@ impl BinaryStlFile {
     /// # Arguments
     ///
     /// * `out` - A type, implementing WryteBytesExt.
-    pub fn write_stl<T: WriteBytesExt>(
+    pub fn write_stl<W: WriteBytesExt>(
         &self,
-        out: &mut T,
+        out: &mut W,
     ) -> Result<()> {
         let start = PreciseTime::now();
         // info!("Writing model ...");
@@ -424,8 +433,8 @@ impl BinaryStlFile {
         Ok(())
     }
 
-    pub(crate) fn get_triangles_and_neighbours(&self) -> (HashMap<usize, Triangle>, HashMap<usize, BTreeSet<usize>>) {
-        let mut ts: HashMap<usize, Triangle> = HashMap::new();
+    pub(crate) fn get_triangles_and_neighbours(&self) -> (HashMap<usize, Triangle<T>>, HashMap<usize, BTreeSet<usize>>) {
+        let mut ts: HashMap<usize, Triangle<T>> = HashMap::new();
         let mut ns: HashMap<usize, BTreeSet<usize>> = HashMap::new();
 
         for (k, v) in self.index_to_triangle.iter() {
@@ -442,7 +451,7 @@ impl BinaryStlFile {
     /// This method returns a reference to HashMap, containing pairs `(id, point)`.
     /// `id` - unique identifier of a point.
     /// `point` - a point in mesh.
-    pub fn get_points(&self) -> &HashMap<usize, point::Point> {
+    pub fn get_points(&self) -> &HashMap<usize, point::Point<T>> {
         return &self.ip_to_p;
     }
 
@@ -461,8 +470,8 @@ impl BinaryStlFile {
     /// # Arguments
     ///
     /// * `index` - An index of triangle to get
-    pub fn get_triangle(&self, index : usize) -> Triangle {
-        let mt : &MeshTriangle = &self.index_to_triangle[&index];
+    pub fn get_triangle(&self, index : usize) -> Triangle<T> {
+        let mt : &MeshTriangle<T> = &self.index_to_triangle[&index];
         let p1 = self.ip_to_p[&mt.ips[0]].clone();
         let p2 = self.ip_to_p[&mt.ips[1]].clone();
         let p3 = self.ip_to_p[&mt.ips[2]].clone();
@@ -474,8 +483,8 @@ impl BinaryStlFile {
     /// # Arguments
     ///
     /// * `index` - An index of triangle to get
-    pub fn get_reversed_triangle(&self, index : usize) -> Triangle {
-        let mt : &MeshTriangle = &self.index_to_triangle[&index];
+    pub fn get_reversed_triangle(&self, index : usize) -> Triangle<T> {
+        let mt : &MeshTriangle<T> = &self.index_to_triangle[&index];
         let p1 = self.ip_to_p[&mt.ips[0]].clone();
         let p2 = self.ip_to_p[&mt.ips[1]].clone();
         let p3 = self.ip_to_p[&mt.ips[2]].clone();
@@ -506,10 +515,10 @@ impl BinaryStlFile {
     }
 
     pub(crate) fn move_triangle(&mut self, index: usize) {
-        let mt: MeshTriangle = self.index_to_triangle.get(&index).unwrap().clone();
-        let move_vec : vector::Vector = mt.normal.clone() * Number::new(0.0000001f64);
+        let mt: MeshTriangle<T> = self.index_to_triangle.get(&index).unwrap().clone();
+        let move_vec : vector::Vector<T> = mt.normal.clone() * T::new(0.0000001f64);
         for ip in mt.ips.iter() {
-            let p_ref: &mut point::Point = self.ip_to_p.get_mut(ip).unwrap();
+            let p_ref: &mut point::Point<T> = self.ip_to_p.get_mut(ip).unwrap();
 
 
             p_ref.x = &p_ref.x + &move_vec.x;
@@ -573,8 +582,8 @@ impl BinaryStlFile {
 
 
     /// This method returns `Vec` of connectivity components.
-    pub fn split_into_connectivity_components(self) -> Vec<Mesh> {
-        let mut res : Vec<Mesh>  = Vec::new();
+    pub fn split_into_connectivity_components(self) -> Vec<Mesh<T>> {
+        let mut res : Vec<Mesh<T>>  = Vec::new();
         let mut visited : HashSet<usize> = HashSet::new();
         for it in self.get_it_iterator() {
             if !visited.contains(&it) {
@@ -603,27 +612,7 @@ impl BinaryStlFile {
     }
 
 
-    pub(crate) fn rotate_x(&mut self, angle: Number) {
-        let start = PreciseTime::now();
-
-        for (ip, p) in self.ip_to_p.iter_mut() {
-            self.p_to_ip.remove(p);
-            p.rotate_x(&angle);
-            self.p_to_ip.insert(p.clone(), ip.clone());
-        }
-
-        let mut index_to_triangle = self.index_to_triangle.clone();
-
-        for (it, mt) in index_to_triangle.iter_mut() {
-            mt.normal = self.get_triangle(*it).calculate_normal();
-        }
-
-        self.index_to_triangle = index_to_triangle;
-
-        info!("Rotation is finished in {0} seconds.\n", start.to(PreciseTime::now()));
-    }
-
-    pub(crate) fn find_xyz_ranges(&self) -> (Number, Number, Number, Number, Number, Number) {
+    pub(crate) fn find_xyz_ranges(&self) -> (T, T, T, T, T, T) {
         let mut x_min;
         let mut x_max;
         let mut y_min;
@@ -671,6 +660,131 @@ impl BinaryStlFile {
     }
 }
 
+impl BinaryStlFile {
+    /// This static method reads a data from the `input` in STL format and creates a new topology.
+    /// # Arguments
+    ///
+    /// * `input` - A type, implementing ReadBytesExt.
+    pub fn read_stl<R: ReadBytesExt>(input: &mut R) -> Result<BinaryStlFile> {
+        // read the header
+        let start = PreciseTime::now();
+        info!("Reading model ...");
+
+        let header = Self::read_header(input)?;
+        let mut mesh = Mesh::new();
+
+        // read the whole file
+        let mut buffer = Vec::new();
+        input.read_to_end(&mut buffer)?;
+        let mut cursor = Cursor::new(buffer);
+
+
+        info!("Number of triangles is {:?}", header.num_triangles);
+        for i in 0 .. header.num_triangles {
+            //if i > 10000 {
+            //    break;
+            //}
+            debug!("Iterration number {:?}", i);
+
+            mesh.read_triangle(&mut cursor)?;
+        }
+
+        let end = PreciseTime::now();
+        info!("<read_stl> is finished in {0} seconds\n", start.to(end));
+        Ok(mesh)
+    }
+
+    /// This method returns mass properties of the solid, bounded by the mesh: volume, surface area, centroid and inertia tensor.
+    pub fn get_mass_properties(&self) -> MassProperties {
+        return MassProperties::new(self);
+    }
+
+    /// This method applies the exact affine transformation `t` to the mesh.
+    /// Normals are recalculated and the winding of triangles is flipped if `t` changes an orientation.
+    /// # Arguments
+    ///
+    /// * `t` - A transformation to apply.
+    pub fn transform(&mut self, t: &Transform) {
+        let mut ip_to_p : HashMap<usize, point::Point> = HashMap::new();
+        let mut p_to_ip : HashMap<point::Point, usize> = HashMap::new();
+
+        for (ip, p) in self.ip_to_p.iter() {
+            let new_p = t.apply_to_point(p);
+            p_to_ip.insert(new_p.clone(), *ip);
+            ip_to_p.insert(*ip, new_p);
+        }
+
+        self.ip_to_p = ip_to_p;
+        self.p_to_ip = p_to_ip;
+
+        if t.does_it_flip_orientation() {
+            for mt in self.index_to_triangle.values_mut() {
+                mt.ips.swap(0, 1);
+            }
+        }
+
+        self.recalculate_all_normals();
+    }
+
+    /// This method translates the mesh by the vector `v`.
+    /// # Arguments
+    ///
+    /// * `v` - A translation vector.
+    pub fn translate(&mut self, v: &vector::Vector) {
+        self.transform(&Transform::translation(v));
+    }
+
+    /// This method scales the mesh relative to the origin.
+    /// It returns an error if one of factors is zero.
+    /// # Arguments
+    ///
+    /// * `sx` - A scale factor along the x axis.
+    /// * `sy` - A scale factor along the y axis.
+    /// * `sz` - A scale factor along the z axis.
+    pub fn scale(&mut self, sx: Number, sy: Number, sz: Number) -> ::std::result::Result<(), &'static str> {
+        let t = Transform::scaling(sx, sy, sz)?;
+        self.transform(&t);
+        return Ok(());
+    }
+
+    /// This method mirrors the mesh relative to the plane, specified by a point and a normal.
+    /// It returns an error if the normal is zero.
+    /// # Arguments
+    ///
+    /// * `point` - A point of the mirror plane.
+    /// * `normal` - A normal of the mirror plane.
+    pub fn mirror(&mut self, point: &point::Point, normal: &vector::Vector) -> ::std::result::Result<(), &'static str> {
+        let t = Transform::mirror(point, normal)?;
+        self.transform(&t);
+        return Ok(());
+    }
+
+    /// This method rotates the mesh around the origin by the exact rotation, specified by a quaternion.
+    /// It returns an error if the quaternion is zero. See `Transform::rotation`.
+    /// # Arguments
+    ///
+    /// * `w` - A scalar part of the quaternion.
+    /// * `x` - The first component of the vector part.
+    /// * `y` - The second component of the vector part.
+    /// * `z` - The third component of the vector part.
+    pub fn rotate(&mut self, w: Number, x: Number, y: Number, z: Number) -> ::std::result::Result<(), &'static str> {
+        let t = Transform::rotation(w, x, y, z)?;
+        self.transform(&t);
+        return Ok(());
+    }
+
+    pub(crate) fn rotate_x(&mut self, angle: Number) {
+        let start = PreciseTime::now();
+
+        // угол задается в градусах; точная рациональная матрица поворота, угол которой отличается от заданного не более чем на 1e-6
+        let axis = vector::Vector::new_from_f64(1., 0., 0.);
+        let t = Transform::rotation_around_axis(&axis, angle.convert_to_f64().to_radians(), 1e-6).unwrap();
+        self.transform(&t);
+
+        info!("Rotation is finished in {0} seconds.\n", start.to(PreciseTime::now()));
+    }
+}
+
 #[cfg(test)]
 mod test {
     // use std;
@@ -781,8 +895,54 @@ mod test {
         };
 
     }
-}
 
+    #[test]
+    fn x_rotation_in_degrees() {
+        let mut mesh = Mesh::new();
+        let t = Triangle::new(vec![Point::new_from_f64(0., 0., 0.), Point::new_from_f64(0., 1., 0.), Point::new_from_f64(1., 0., 0.)]);
+        mesh.add_triangle(t).unwrap();
 
+        mesh.rotate_x(Number::new(90.));
 
+        let points: Vec<Point> = mesh.get_points().values().cloned().collect();
+        assert!(points.contains(&Point::new_from_f64(0., 0., 1.)));
+        assert!(points.contains(&Point::new_from_f64(1., 0., 0.)));
+    }
 
+    #[test]
+    fn mirror_flips_winding() {
+        let mut mesh = Mesh::new();
+        let o = Point::new_from_f64(0., 0., 0.);
+        let x = Point::new_from_f64(1., 0., 0.);
+        let y = Point::new_from_f64(0., 1., 0.);
+        let z = Point::new_from_f64(0., 0., 1.);
+        mesh.add_triangle(Triangle::new(vec![o.clone(), y.clone(), x.clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![o.clone(), x.clone(), z.clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![o.clone(), z.clone(), y.clone()])).unwrap();
+        mesh.add_triangle(Triangle::new(vec![x.clone(), y.clone(), z.clone()])).unwrap();
+
+        let volume = mesh.get_volume();
+        assert!(volume.is_it_positive());
+
+        mesh.mirror(&Point::new_from_f64(0.5, 0., 0.), &Vector::new_from_f64(1., 0., 0.)).unwrap();
+        assert_eq!(mesh.get_volume(), volume);
+        assert!(mesh.geometry_check());
+        assert_eq!(mesh.p_to_ip.get(&Point::new_from_f64(0., 0., 0.)), Some(&2));
+        for it in mesh.get_it_iterator() {
+            let t = mesh.get_triangle(it);
+            assert_eq!(t.get_normal(), t.calculate_normal());
+        }
+
+        mesh.scale(Number::new(2.), Number::new(3.), Number::new(-1.)).unwrap();
+        assert_eq!(mesh.get_volume(), &volume * &Number::new(6.));
+        assert!(mesh.scale(Number::zero(), Number::new(1.), Number::new(1.)).is_err());
+
+        mesh.translate(&Vector::new_from_f64(1., 1., 1.));
+        mesh.rotate(Number::new(1.), Number::new(2.), Number::new(3.), Number::new(4.)).unwrap();
+        assert_eq!(mesh.get_volume(), &volume * &Number::new(6.));
+        assert_eq!(mesh.num_of_points(), 4);
+        for (ip, p) in mesh.ip_to_p.iter() {
+            assert_eq!(mesh.p_to_ip.get(p), Some(ip));
+        }
+    }
+}
@@ -1,17 +1,25 @@
 
-pub(crate) mod number_trait;
+pub mod number_trait;
 pub(crate) mod number_impl_big_rational;
+#[cfg(feature = "gmp")]
 pub(crate) mod number_impl_gmp;
+pub(crate) mod number_impl_f64;
 
 pub mod number;
 pub mod point;
 pub mod vector;
 pub mod mesh;
+pub mod tet_mesh;
 pub mod triangle;
-pub(crate) mod line;
+pub mod mass_properties;
+pub mod transform;
+pub mod line;
 pub(crate) mod segment;
-pub(crate) mod plane;
-pub(crate) mod polygon;
+pub mod plane;
+pub mod polygon;
+pub mod predicates;
+#[cfg(test)]
+pub(crate) mod fixtures;
 
 pub(crate) use self::point::Point;
 pub(crate) use self::number::*;
@@ -23,11 +31,12 @@ pub(crate) use self::triangle::Triangle;
 pub(crate) use self::polygon::Polygon;
 pub(crate) use self::polygon::PolygonTreeNode;
 pub(crate) use self::mesh::Mesh;
+pub(crate) use self::tet_mesh::TetMesh;
 
-pub(crate) mod zero_trait;
+pub mod zero_trait;
 pub(crate) use self::zero_trait::Zero;
 
-pub(crate) mod signed_trait;
+pub mod signed_trait;
 pub(crate) use self::signed_trait::Signed;
 
 pub(crate) mod to_2d_trait;
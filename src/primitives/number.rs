@@ -1,10 +1,30 @@
 // use primitives::number_trait;
-// use primitives::number_impl_big_rational;
+use primitives::number_impl_big_rational;
+#[cfg(feature = "gmp")]
 use primitives::number_impl_gmp;
+use primitives::number_impl_f64;
 
 
-pub use primitives::number_trait::NumberTrait;
+pub use primitives::number_trait::{NumberTrait, NumberRef};
 
 /// An alias for a number type, used in the geomety kernel.
-//pub type Number = number_impl_big_rational::Number;
-pub type Number = number_impl_gmp::Number;
\ No newline at end of file
+/// It's chosen by cargo features: `gmp` (by default) or `bigrational` (doesn't need the native GMP library).
+/// If both features are enabled, `bigrational` is used.
+#[cfg(all(feature = "gmp", not(feature = "bigrational")))]
+pub type Number = number_impl_gmp::Number;
+/// An alias for a number type, used in the geomety kernel.
+/// It's chosen by cargo features: `gmp` (by default) or `bigrational` (doesn't need the native GMP library).
+/// If both features are enabled, `bigrational` is used.
+#[cfg(feature = "bigrational")]
+pub type Number = number_impl_big_rational::Number;
+
+/// A number type based on GMP rationals. It's available with the feature `gmp`.
+#[cfg(feature = "gmp")]
+pub type GmpNumber = number_impl_gmp::Number;
+/// A number type based on `BigRational` from the `num` crate. It's available with every feature.
+pub type BigRationalNumber = number_impl_big_rational::Number;
+/// A number type based on `f64`. It's available with every feature,
+/// so e.g. an exact mesh can be converted to f64 coordinates for display, see `Mesh::convert`.
+/// Predicates from `primitives::predicates` stay exact for it, but boolean operations and other algorithms
+/// work only with exact `Number`.
+pub type FloatNumber = number_impl_f64::Number;
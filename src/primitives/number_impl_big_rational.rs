@@ -3,8 +3,8 @@ use num;
 use num::Signed as NumSigned;
 use num::Zero as NumZero;
 
-use num::FromPrimitive;
 use num::ToPrimitive;
+use num::pow;
 use num::bigint::BigInt;
 use num::rational::{/*Ratio,*/ BigRational /*, Rational64*/};
 // use time::PreciseTime;
@@ -16,7 +16,7 @@ use primitives::signed_trait::Signed;
 use primitives::zero_trait::Zero;
 use std::cmp::{Ord, Ordering};
 
-use primitives::number_trait::{NumberTrait, NumberT};
+use primitives::number_trait::{NumberTrait, NumberRef, NumberT};
 
 use std::ops::{Add, Sub, Mul, Div, Neg};
 
@@ -24,8 +24,7 @@ pub type Number = NumberT<BigRational>;
 
 lazy_static! {
     // static ref LEAST_F32_VALUE : Number = Number::new(0.000001);
-    static ref VALUE_10_6 : BigInt = BigInt::from_u64(1000000).unwrap();
-    static ref VALUE_10 : BigInt = BigInt::from_u64(10).unwrap();
+    static ref PI : Number = Number::new(3.14159265359f64);
 }
 
 impl fmt::Display for Number {
@@ -223,32 +222,15 @@ impl Hash for NumberT<BigRational> {
     }
 }
 
+impl<'a> NumberRef<Number> for &'a Number {}
+
 impl NumberTrait<Number> for Number {
     fn convert_to_f32(self) -> f32 {
+        return self.convert_to_f64() as f32;
+    }
 
-        let mut numer = self.value.numer().clone();
-        let mut denom = self.value.denom().clone();
-
-        loop {
-            let opt_numer = numer.to_f32();
-            let opt_denom = denom.to_f32();
-
-            if opt_denom.is_none() || opt_numer.is_none() {
-                if opt_denom.is_none() && (&numer < &VALUE_10_6) {
-                    return 0.;
-                }
-
-                if opt_numer.is_none() && (&denom < &VALUE_10_6) {
-                    panic!("ERROR: The value is too large: {0}", self.value);
-                }
-
-                numer = numer / VALUE_10.clone();
-                denom = denom / VALUE_10.clone();
-            } else {
-                return opt_numer.unwrap() / opt_denom.unwrap();
-            }
-        }
-        // panic!("Something goes wrong!");
+    fn convert_to_f64(self) -> f64 {
+        return to_f64(self.value.numer(), self.value.denom());
     }
 
     fn abs(self) -> Number {
@@ -268,19 +250,99 @@ impl NumberTrait<Number> for Number {
         return Number::from_value(res);
     }
 
-    fn approx_cos(&self, _: usize) -> Number {
-        // TODO
-        panic!("Not implemented!");
+    fn approx_cos(&self, n: usize) -> Number {
+        let mut res = Number::zero();
+        let x = self * PI.clone() / Number::new(180f64);
+        for k in 0..n {
+            let l = 2 * k;
+            let factor = Number::new(pow(-1., k)) / factorial(l);
+            res = res + factor * x.pow(l);
+        }
+        return res;
     }
 
-    fn approx_sin(&self, _: usize) -> Number {
-        // TODO
-        panic!("Not implemented!");
+    fn approx_sin(&self, n: usize) -> Number {
+        let mut res = Number::zero();
+        let x = self * PI.clone() / Number::new(180f64);
+        for k in 0..n {
+            let l = 2 * k + 1;
+            let factor = Number::new(pow(-1., k)) / factorial(l);
+            res = res + factor * x.pow(l);
+        }
+        return res;
     }
 
+    fn pow(&self, k: usize) -> Number {
+        let mut res = Number::new(1.);
+        for _ in 0..k {
+            res = res * self;
+        }
+        return res;
+    }
+}
 
-    fn pow(&self, _: usize) -> Number {
-        panic!("Not implemented!");
+fn factorial(value: usize) -> Number {
+    let mut res = Number::new(1.);
+    for k in 2..(value + 1) {
+        res = res * Number::new(k as f64);
     }
+    return res;
 }
 
+// частное сдвигается до 55-56 значащих битов (для субнормальных значений - до двух битов ниже 2^-1074),
+// ненулевой остаток учитывается младшим битом, поэтому частное переводится в f64 без потерь,
+// а умножение на степень двойки округляет его один раз, как точное значение
+fn to_f64(numer : &BigInt, denom : &BigInt) -> f64 {
+    if numer.is_zero() {
+        return 0.;
+    }
+    let (mut n, mut d) = (numer.abs(), denom.abs());
+    let shift = (n.bits() as i64 - d.bits() as i64 - 55).max(-1076);
+    if shift > 0 {
+        d = d << shift as usize;
+    } else {
+        n = n << (-shift) as usize;
+    }
+
+    let mut q = (&n / &d).to_u64().unwrap();
+    let mut e = shift;
+    if !(&n % &d).is_zero() {
+        q = 2 * q + 1;
+        e -= 1;
+    }
+    let mut x = ldexp(q as f64, e);
+    if numer.is_negative() != denom.is_negative() {
+        x = -x;
+    }
+    return x;
+}
+
+// умножение на 2^e по частям, чтобы множитель не переполнялся и не обнулялся раньше результата
+fn ldexp(mut x : f64, mut e : i64) -> f64 {
+    while e != 0 && x != 0. && x.is_finite() {
+        let step = e.max(-1000).min(1000);
+        x *= 2f64.powi(step as i32);
+        e -= step;
+    }
+    return x;
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::number_impl_big_rational::Number;
+    use primitives::number_trait::NumberTrait;
+
+    #[test]
+    fn exact_conversion_to_f64() {
+        for &x in [0., 1., -0.1, 1. / 3., 1e300, -1e-300, 5e-324, -2.5e-320, 9.3e18, 123456789.123456789].iter() {
+            assert_eq!(Number::new(x).convert_to_f64(), x);
+        }
+
+        let third = Number::new(1.) / Number::new(3.);
+        assert_eq!(third.convert_to_f64(), 1. / 3.);
+        let tiny = Number::new(2f64.powi(-530)) * Number::new(2f64.powi(-530)) * Number::new(3.);
+        assert_eq!(tiny.convert_to_f64(), 3. * 2f64.powi(-1060));
+        let huge = Number::new(1e300) * Number::new(1e300);
+        assert_eq!(huge.convert_to_f64(), ::std::f64::INFINITY);
+    }
+}
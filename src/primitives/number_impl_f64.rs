@@ -0,0 +1,277 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use primitives::signed_trait::Signed;
+use primitives::zero_trait::Zero;
+use std::cmp::{Ord, Ordering};
+
+use primitives::number_trait::{NumberTrait, NumberRef, NumberT};
+
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// `Number` based on `f64`. Arithmetic is inexact, but predicates from `primitives::predicates`
+/// are exact for coordinates of this type.
+pub type Number = NumberT<f64>;
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        Number::from_value(-self.value)
+    }
+}
+
+impl<'a> Neg for &'a Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        Number::from_value(-self.value)
+    }
+}
+
+impl Add<Number> for Number {
+    type Output = Number;
+
+    fn add(self, other: Number) -> Number {
+        Number::from_value(self.value + other.value)
+    }
+}
+
+impl<'a,'b> Add<&'b Number> for &'a Number {
+    type Output = Number;
+
+    fn add(self, other: &'b Number) -> Number {
+        Number::from_value(self.value + other.value)
+    }
+}
+
+impl<'a,'b> Add<&'b Number> for Number {
+    type Output = Number;
+
+    fn add(self, other: &'b Number) -> Number {
+        Number::from_value(self.value + other.value)
+    }
+}
+
+impl<'a> Add<Number> for &'a Number {
+    type Output = Number;
+
+    fn add(self, other: Number) -> Number {
+        Number::from_value(self.value + other.value)
+    }
+}
+
+impl Sub<Number> for Number {
+    type Output = Number;
+
+    fn sub(self, other: Number) -> Number {
+        Number::from_value(self.value - other.value)
+    }
+}
+
+impl<'a,'b> Sub<&'b Number> for &'a Number {
+    type Output = Number;
+
+    fn sub(self, other: &'b Number) -> Number {
+        Number::from_value(self.value - other.value)
+    }
+}
+
+impl<'a,'b> Sub<&'b Number> for Number {
+    type Output = Number;
+
+    fn sub(self, other: &'b Number) -> Number {
+        Number::from_value(self.value - other.value)
+    }
+}
+
+impl<'a> Sub<Number> for &'a Number {
+    type Output = Number;
+
+    fn sub(self, other: Number) -> Number {
+        Number::from_value(self.value - other.value)
+    }
+}
+
+impl Mul<Number> for Number {
+    type Output = Number;
+
+    fn mul(self, other: Number) -> Number {
+        Number::from_value(self.value * other.value)
+    }
+}
+
+impl<'a,'b> Mul<&'b Number> for &'a Number {
+    type Output = Number;
+
+    fn mul(self, other: &'b Number) -> Number {
+        Number::from_value(self.value * other.value)
+    }
+}
+
+impl<'a,'b> Mul<&'b Number> for Number {
+    type Output = Number;
+
+    fn mul(self, other: &'b Number) -> Number {
+        Number::from_value(self.value * other.value)
+    }
+}
+
+impl<'a> Mul<Number> for &'a Number {
+    type Output = Number;
+
+    fn mul(self, other: Number) -> Number {
+        Number::from_value(self.value * other.value)
+    }
+}
+
+impl Div<Number> for Number {
+    type Output = Number;
+
+    fn div(self, other: Number) -> Number {
+        Number::from_value(self.value / other.value)
+    }
+}
+
+impl<'a,'b> Div<&'b Number> for &'a Number {
+    type Output = Number;
+
+    fn div(self, other: &'b Number) -> Number {
+        Number::from_value(self.value / other.value)
+    }
+}
+
+impl<'a,'b> Div<&'b Number> for Number {
+    type Output = Number;
+
+    fn div(self, other: &'b Number) -> Number {
+        Number::from_value(self.value / other.value)
+    }
+}
+
+impl<'a> Div<Number> for &'a Number {
+    type Output = Number;
+
+    fn div(self, other: Number) -> Number {
+        Number::from_value(self.value / other.value)
+    }
+}
+
+impl Signed<Number> for Number {
+    fn is_it_positive(&self) -> bool {
+        self.value > 0.
+    }
+
+    fn is_it_negative(&self) -> bool {
+        self.value < 0.
+    }
+}
+
+impl Zero<Number> for Number {
+    fn is_it_zero(&self) -> bool {
+        self.value == 0.
+    }
+
+    fn zero() -> Number {
+        Number::from_value(0.)
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for Number {}
+
+// NaN не может появиться в корректных вычислениях, поэтому порядок полный
+impl Ord for Number {
+    fn cmp(&self, other: &Number) -> Ordering {
+        self.value.partial_cmp(&other.value).expect("NaN can't be compared!")
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for NumberT<f64> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // 0. и -0. равны, поэтому должны иметь одинаковый хэш
+        let value = if self.value == 0. {0.} else {self.value};
+        value.to_bits().hash(state);
+    }
+}
+
+impl<'a> NumberRef<Number> for &'a Number {}
+
+impl NumberTrait<Number> for Number {
+    fn convert_to_f32(self) -> f32 {
+        self.value as f32
+    }
+
+    fn convert_to_f64(self) -> f64 {
+        self.value
+    }
+
+    fn abs(self) -> Number {
+        Number::from_value(self.value.abs())
+    }
+
+    fn new(x : f64) -> Number {
+        Number::from_value(x)
+    }
+
+    fn new_from_f32(x : f32) -> Number {
+        Number::from_value(x as f64)
+    }
+
+    fn approx_cos(&self, _: usize) -> Number {
+        Number::from_value(self.value.to_radians().cos())
+    }
+
+    fn approx_sin(&self, _: usize) -> Number {
+        Number::from_value(self.value.to_radians().sin())
+    }
+
+    fn pow(&self, k: usize) -> Number {
+        Number::from_value(self.value.powi(k as i32))
+    }
+
+    fn is_exact() -> bool {
+        false
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::number_impl_f64::Number;
+    use primitives::number_trait::NumberTrait;
+    use primitives::signed_trait::Signed;
+    use std::collections::HashSet;
+
+    #[test]
+    fn arithmetic_and_order() {
+        let a = Number::new(1.5);
+        let b = Number::new(-0.5);
+        assert_eq!(&a + &b, Number::new(1.));
+        assert_eq!(&a * &b, Number::new(-0.75));
+        assert!(b < a && b.is_it_negative());
+        assert_eq!(b.abs(), Number::new(0.5));
+        assert_eq!(Number::new(3.).pow(3), Number::new(27.));
+
+        let mut set: HashSet<Number> = HashSet::new();
+        set.insert(Number::new(0.));
+        assert!(set.contains(&Number::new(-0.)));
+    }
+}
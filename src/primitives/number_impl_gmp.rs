@@ -12,7 +12,7 @@ use primitives::zero_trait::Zero;
 use std::cmp::{Ord, Ordering};
 use std::collections::HashMap;
 
-use primitives::number_trait::{NumberTrait, NumberT};
+use primitives::number_trait::{NumberTrait, NumberRef, NumberT};
 
 use std::ops::{Add, Sub, Mul, Div, Neg};
 
@@ -222,6 +222,8 @@ impl Hash for NumberT<Mpq> {
     }
 }
 
+impl<'a> NumberRef<Number> for &'a Number {}
+
 impl NumberTrait<Number> for Number {
     fn convert_to_f32(self) -> f32 {
 
@@ -255,6 +257,10 @@ impl NumberTrait<Number> for Number {
         // panic!("Something goes wrong!");
     }
 
+    fn convert_to_f64(self) -> f64 {
+        f64::from(&self.value)
+    }
+
     fn abs(self) -> Number {
         Number::from_value(Mpq::abs(&self.value))
     }
@@ -1,27 +1,31 @@
 
 use primitives::*;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 use std::hash::{Hash};
-use std::ops::{Add, Sub, Mul, Div /*, Neg*/};
+use std::ops::{Add, Sub, Mul, Div, Neg};
 use std::cmp::Ord;
 // use std::fmt;
 
 
 /// This trait contains a basic interface for `Number`.
-pub trait NumberTrait <T>
-    where
-    T:
-    Clone + Debug + Hash + Ord +
+/// Primitives and meshes are generic over a type implementing it,
+/// so a custom number type (e.g. a fixed-point one) can be used instead of `Number`.
+/// Arithmetic on references is required by `NumberRef`, which has to be written as a bound near `NumberTrait`:
+/// `T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>`.
+pub trait NumberTrait <T>:
+    Clone + Debug + Display + Hash + Ord +
     Signed<T> + Zero<T> +
-    Add<T> + Sub<T> + Mul<T> + Div<T> +
-    for<'b>  Add<&'b T> + for<'b>  Sub<&'b T> + for<'b>  Mul<&'b T> + for<'b>  Div<&'b T>,
-    for<'b> &'b T:
-    Add<T> + Sub<T> + Mul<T> + Div<T> +
-    Add<&'b T> + Sub<&'b T> + Mul<&'b T> + Div<&'b T>
+    Neg<Output = T> +
+    Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T> +
+    for<'b>  Add<&'b T, Output = T> + for<'b>  Sub<&'b T, Output = T> +
+    for<'b>  Mul<&'b T, Output = T> + for<'b>  Div<&'b T, Output = T>
 {
     /// This method converts `T` to `f32`.
     fn convert_to_f32(self) -> f32;
 
+    /// This method converts `T` to `f64`.
+    fn convert_to_f64(self) -> f64;
+
     /// This method returns an absolute value.
     fn abs(self) -> T;
 
@@ -44,8 +48,25 @@ pub trait NumberTrait <T>
 
     /// This function calculates k-th power
     fn pow(&self, k: usize) -> T;
+
+    /// This function returns `false` if arithmetic of `T` is inexact.
+    /// Predicates then use floating-point filters with an exact fallback,
+    /// which requires `convert_to_f64` to be lossless for such types.
+    fn is_exact() -> bool {
+        return true;
+    }
 }
 
+/// This trait contains arithmetic operations on references to `T`.
+/// A number type implementing `NumberTrait` should implement it for references too, e.g.
+/// `impl<'a> NumberRef<MyNumber> for &'a MyNumber {}`.
+pub trait NumberRef<T>:
+    Neg<Output = T> +
+    Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T> +
+    for<'b>  Add<&'b T, Output = T> + for<'b>  Sub<&'b T, Output = T> +
+    for<'b>  Mul<&'b T, Output = T> + for<'b>  Div<&'b T, Output = T>
+{
+}
 
 #[derive (Debug, Clone)]
 pub struct NumberT<T> {
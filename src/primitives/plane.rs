@@ -1,7 +1,6 @@
 use primitives::vector::Vector;
 use primitives::point::Point;
-use primitives::number::Number;
-use primitives::zero_trait::Zero;
+use primitives::number::*;
 use primitives::to_2d_trait::To2D;
 // use std::mem::swap;
 
@@ -9,16 +8,18 @@ use primitives::to_2d_trait::To2D;
 
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
-pub struct Plane {
-    pub normal: Vector,
-    pub point: Point,
+pub struct Plane<T = Number> {
+    pub normal: Vector<T>,
+    pub point: Point<T>,
 
     // n*x + d = 0
-    pub d: Number
+    pub d: T
 }
 
-impl Plane {
-    pub fn new(normal: Vector, point: Point) -> Plane {
+impl<T> Plane<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(normal: Vector<T>, point: Point<T>) -> Plane<T> {
         let d = -normal.dot_product(&point.get_vector());
         Plane {
             normal: normal,
@@ -27,30 +28,32 @@ impl Plane {
         }
     }
 
-    pub fn new_3p(p0: &Point, p1: &Point, p2: &Point) -> Plane {
+    pub fn new_3p(p0: &Point<T>, p1: &Point<T>, p2: &Point<T>) -> Plane<T> {
         let v1 = p0 - p1;
         let v2 = p1 - p2;
         let n = v1.cross_product(&v2);
         Plane::new(n, p0.clone())
     }
 
-    pub fn does_it_contain_point(&self, point : &Point) -> bool {
+    pub fn does_it_contain_point(&self, point : &Point<T>) -> bool {
         let dp = self.normal.dot_product(&(point - &self.point));
         //println!("dp: {0}", dp);
         return dp.is_it_zero();
     }
 
 
-    pub fn get_ref_d(&self) -> &Number{
+    pub fn get_ref_d(&self) -> &T{
         &self.d
     }
 
-    pub fn get_ref_normal(&self) -> &Vector {
+    pub fn get_ref_normal(&self) -> &Vector<T> {
         &self.normal
     }
 }
 
-impl To2D for Plane {
+impl<T> To2D for Plane<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     fn swap_yz(& mut self) {
         self.normal.swap_yz();
         self.point.swap_yz();
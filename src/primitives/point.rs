@@ -1,4 +1,5 @@
 use primitives::vector;
+use primitives::predicates;
 use std::cmp::Ordering;
 // use std::f64::consts::PI;
 use std::mem::swap;
@@ -17,23 +18,14 @@ use std::fmt;
 
 
 /// This structure describes a point in a 3D space.
+/// Coordinates are `Number` by default, but any type implementing `NumberTrait` can be used.
 #[derive(Clone)]
-#[derive(Hash)]
-pub struct Point
+#[derive(Debug, Hash)]
+pub struct Point<T = Number>
 {
-    pub x: Number,
-    pub y: Number,
-    pub z: Number
-}
-
-impl fmt::Debug for Point {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // write!(f, "Point [{:?}, {:?}, {:?}]", self.x.clone().convert_to_f32(), self.y.clone().convert_to_f32(), self.z.clone().convert_to_f32())
-        write!(f, "Point [{0} <{1}>, {2} <{3}>, {4} <{5}>]",
-               self.x.value, self.x.clone().convert_to_f32(),
-               self.y.value, self.y.clone().convert_to_f32(),
-               self.z.value, self.z.clone().convert_to_f32())
-    }
+    pub x: T,
+    pub y: T,
+    pub z: T
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -48,27 +40,49 @@ pub(crate) enum EPointPosition {
 }
 
 
-impl Point {
+impl<T> Point<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     /// This method converts the `Point` to a `Vector`.
-    pub fn convert_to_vector(self) -> vector::Vector {
+    pub fn convert_to_vector(self) -> vector::Vector<T> {
         vector::Vector {x: self.x, y: self.y, z: self.z}
     }
 
     /// This method creates a `Vector` from the `Point`.
-    pub fn get_vector(&self) -> vector::Vector {
+    pub fn get_vector(&self) -> vector::Vector<T> {
         vector::Vector {x: self.x.clone(), y: self.y.clone(), z: self.z.clone()}
     }
 
     /// This method creates `Point` from `x`, `y` and `z` coordinates.
     /// # Arguments
     ///
-    /// * `x` - A `Number` representing the x coordinate.
-    /// * `y` - A `Number` representing the y coordinate.
-    /// * `z` - A `Number` representing the z coordinate.
-    pub fn new(x : Number, y : Number, z : Number) -> Point {
+    /// * `x` - A number representing the x coordinate.
+    /// * `y` - A number representing the y coordinate.
+    /// * `z` - A number representing the z coordinate.
+    pub fn new(x : T, y : T, z : T) -> Point<T> {
         Point{x: x, y: y, z: z}
     }
 
+    /// This method creates `Point` with coordinates of type `T` from `x`, `y` and `z` coordinates.
+    /// # Arguments
+    ///
+    /// * `x` - A `f64` representing the x coordinate.
+    /// * `y` - A `f64` representing the y coordinate.
+    /// * `z` - A `f64` representing the z coordinate.
+    pub fn from_f64(x : f64, y : f64, z : f64) -> Point<T> {
+        Point{x: T::new(x), y: T::new(y), z: T::new(z)}
+    }
+
+    /// This method converts coordinates of the `Point` to another number type.
+    /// The conversion is made through `f64`, so it's exact only if coordinates are representable as `f64`.
+    pub fn convert<U>(&self) -> Point<U>
+        where U: NumberTrait<U>, for<'b> &'b U: NumberRef<U>
+    {
+        Point::from_f64(self.x.clone().convert_to_f64(), self.y.clone().convert_to_f64(), self.z.clone().convert_to_f64())
+    }
+}
+
+impl Point {
     /// This method creates `Point` from `x`, `y` and `z` coordinates.
     /// # Arguments
     ///
@@ -83,10 +97,10 @@ impl Point {
     pub(crate) fn classify(&self, p0 : &Point, p1 : &Point) -> EPointPosition {
         let a = p1 - p0;
         let b = self - p0;
-        let sa = &a.x*&b.y - &b.x*&a.y;
+        let orientation = predicates::orient2d(p0, p1, self);
         match 1 {
-            _ if sa.is_it_positive() => return EPointPosition::Left,
-            _ if sa.is_it_negative() => return EPointPosition::Right,
+            _ if orientation == Ordering::Greater => return EPointPosition::Left,
+            _ if orientation == Ordering::Less => return EPointPosition::Right,
             _ if (&a.x * &b.x).is_it_negative() | (&a.y * &b.y).is_it_negative() =>
                 return EPointPosition::Behind,
             _ if a.length2() < b.length2() => return EPointPosition::Beyond,
@@ -95,19 +109,9 @@ impl Point {
             _ => return EPointPosition::Between
         }
     }
-
-    pub(crate) fn rotate_x(&mut self, angle: &Number) {
-        // TODO rotation around an arbitrary axis
-        let new_y = &self.y*angle.approx_cos(3) - &self.z*angle.approx_sin(3);
-        let new_z = &self.y*angle.approx_sin(3) + &self.z*angle.approx_cos(3);
-        self.y = new_y;
-        self.z = new_z;
-    }
-
-
 }
 
-impl To2D for Point {
+impl<T> To2D for Point<T> {
     fn swap_yz(& mut self) {
         swap(&mut self.y, &mut self.z);
     }
@@ -121,54 +125,62 @@ impl To2D for Point {
     }
 }
 
-impl Add<vector::Vector> for Point {
-    type Output = Point;
+impl<T> Add<vector::Vector<T>> for Point<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    type Output = Point<T>;
 
-    fn add(self, other: vector::Vector) -> Point {
+    fn add(self, other: vector::Vector<T>) -> Point<T> {
         Point { x: self.x + other.x, y: self.y + other.y, z: self.z + other.z }
     }
 }
 
 
-impl Sub<Point> for Point {
-    type Output = vector::Vector;
-    fn sub(self, other: Point) -> vector::Vector {
+impl<T> Sub<Point<T>> for Point<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    type Output = vector::Vector<T>;
+    fn sub(self, other: Point<T>) -> vector::Vector<T> {
         vector::Vector { x: self.x - other.x, y: self.y - other.y, z: self.z - other.z}
     }
 }
 
-impl<'a,'b> Add<&'b vector::Vector> for &'a Point {
-    type Output = Point;
+impl<'a,'b,T> Add<&'b vector::Vector<T>> for &'a Point<T>
+    where T: NumberTrait<T>, for<'c> &'c T: NumberRef<T>
+{
+    type Output = Point<T>;
 
-    fn add(self, other: &'b vector::Vector) -> Point {
+    fn add(self, other: &'b vector::Vector<T>) -> Point<T> {
         Point { x: &self.x + &other.x, y: &self.y + &other.y, z: &self.z + &other.z }
     }
 }
 
-impl<'a,'b> Sub<&'b Point> for &'a Point {
-    type Output = vector::Vector;
+impl<'a,'b,T> Sub<&'b Point<T>> for &'a Point<T>
+    where T: NumberTrait<T>, for<'c> &'c T: NumberRef<T>
+{
+    type Output = vector::Vector<T>;
 
-    fn sub(self, other: &'b Point) -> vector::Vector {
+    fn sub(self, other: &'b Point<T>) -> vector::Vector<T> {
         vector::Vector { x: &self.x - &other.x, y: &self.y - &other.y, z: &self.z - &other.z}
     }
 }
 
-impl fmt::Display for Point {
+impl<T: fmt::Display> fmt::Display for Point<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "({}, {}, {})", self.x, self.y, self.z)
     }
 }
 
-impl PartialEq for Point {
-    fn eq(&self, other: &Point) -> bool {
+impl<T: PartialEq> PartialEq for Point<T> {
+    fn eq(&self, other: &Point<T>) -> bool {
         (self.x == other.x) & (self.y == other.y) & (self.z == other.z)
     }
 }
 
-impl Eq for Point {}
+impl<T: Eq> Eq for Point<T> {}
 
-impl Ord for Point {
-    fn cmp(&self, other: &Point) -> Ordering {
+impl<T: Ord> Ord for Point<T> {
+    fn cmp(&self, other: &Point<T>) -> Ordering {
         match self {
             _ if *self == *other => Ordering::Equal,
             _ if (self.x < other.x) | (self.x == other.x) & (self.y < other.y) | (self.x == other.x) & (self.y == other.y) & (self.z < other.z) => Ordering::Less,
@@ -177,8 +189,8 @@ impl Ord for Point {
     }
 }
 
-impl PartialOrd for Point {
-    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
+impl<T: Ord> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Point<T>) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
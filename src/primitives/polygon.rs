@@ -6,25 +6,27 @@ use primitives::point::EPointPosition;
 
 #[derive(Clone)]
 #[derive(Debug, Hash)]
-pub struct Polygon {
-    points: Vec<Point>,
-    normal: Vector
+pub struct Polygon<T = Number> {
+    points: Vec<Point<T>>,
+    normal: Vector<T>
 }
 
-impl Polygon {
-    pub fn new(points : Vec<Point>, normal : Vector) -> Polygon {
+impl<T> Polygon<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(points : Vec<Point<T>>, normal : Vector<T>) -> Polygon<T> {
         Polygon {
             points,
             normal
         }
     }
 
-    pub fn get_points(self) -> Vec<Point> {
+    pub fn get_points(self) -> Vec<Point<T>> {
         return self.points;
     }
 
-    pub(crate) fn get_segments(&self) -> Vec<Segment> {
-        let mut res: Vec<Segment> = Vec::new();
+    pub(crate) fn get_segments(&self) -> Vec<Segment<T>> {
+        let mut res: Vec<Segment<T>> = Vec::new();
         for cur_i in 0..self.points.len() {
             let next_i = (cur_i + 1)%self.points.len();
             let s = Segment::new(self.points[cur_i].clone(), self.points[next_i].clone());
@@ -33,32 +35,32 @@ impl Polygon {
         return res;
     }
 
-    pub fn get_points_ref(&self) -> &Vec<Point> {
+    pub fn get_points_ref(&self) -> &Vec<Point<T>> {
         return &self.points;
     }
 
-    pub fn get_normal_ref(&self) -> &Vector {
+    pub fn get_normal_ref(&self) -> &Vector<T> {
         return &self.normal;
     }
 
-    pub fn set_normal(&mut self, normal: Vector) {
+    pub fn set_normal(&mut self, normal: Vector<T>) {
         self.normal = normal;
     }
 
-    pub fn add_point(&mut self, p : Point) {
+    pub fn add_point(&mut self, p : Point<T>) {
         self.points.push(p);
     }
 
-    pub fn get_points_and_normal(self) -> (Vec<Point>, Vector) {
+    pub fn get_points_and_normal(self) -> (Vec<Point<T>>, Vector<T>) {
         return (self.points, self.normal);
     }
 
     // Для двух непересекающихся полигонов проверяется: находится ли other внутри self.
-    pub fn contains(&self, other: &Polygon) -> bool {
+    pub fn contains(&self, other: &Polygon<T>) -> bool {
         // достаточно проверить лишь одну точку
 
-        let one = Number::new(1.);
-        let zero = Number::new(0.);
+        let one = T::new(1.);
+        let zero = T::new(0.);
 
         let pm = other.points[0].clone();
 
@@ -94,9 +96,9 @@ impl Polygon {
 
     }
 
-    pub fn signed_area(&self) -> Number {
+    pub fn signed_area(&self) -> T {
         let n = self.points.len();
-        let mut signed_area = Number::new(0.);
+        let mut signed_area = T::new(0.);
 
         for cur_index in 0..n {
             let next_index = (cur_index+1)%n;
@@ -104,7 +106,7 @@ impl Polygon {
             signed_area = signed_area + &cur_point.x*&next_point.y - &next_point.x*&cur_point.y;
         }
 
-        return signed_area / Number::new(2.);
+        return signed_area / T::new(2.);
     }
 
     pub fn reverse_order(&mut self) {
@@ -558,6 +560,150 @@ impl TriangleContainer {
 }
 
 
+// проекция на координатную плоскость, перпендикулярную наибольшей компоненте нормали;
+// порядок осей выбран так, что обход против часовой стрелки соответствует положительной нормали
+pub(crate) fn project(p: &Point, axis: usize) -> (Number, Number) {
+    match axis {
+        0 => return (p.y.clone(), p.z.clone()),
+        1 => return (p.z.clone(), p.x.clone()),
+        _ => return (p.x.clone(), p.y.clone())
+    }
+}
+
+fn get_signed_area(points: &Vec<(Number, Number)>) -> Number {
+    let n = points.len();
+    let mut res = Number::zero();
+    for i in 0..n {
+        let (ref a, ref b) = (&points[i], &points[(i + 1) % n]);
+        res = res + &a.0 * &b.1 - &b.0 * &a.1;
+    }
+    return res / Number::new(2.);
+}
+
+#[derive(PartialEq)]
+enum Position {
+    Inside,
+    Outside,
+    OnBoundary
+}
+
+fn locate_point(q: &(Number, Number), polygon: &Vec<(Number, Number)>) -> Position {
+    let n = polygon.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (&polygon[i], &polygon[(i + 1) % n]);
+        let cross = &(&(&b.0 - &a.0) * &(&q.1 - &a.1)) - &(&(&b.1 - &a.1) * &(&q.0 - &a.0));
+        if cross.is_it_zero() &&
+            ((&a.0 <= &q.0 && &q.0 <= &b.0) || (&b.0 <= &q.0 && &q.0 <= &a.0)) &&
+            ((&a.1 <= &q.1 && &q.1 <= &b.1) || (&b.1 <= &q.1 && &q.1 <= &a.1)) {
+            return Position::OnBoundary;
+        }
+
+        if (a.1 > q.1) != (b.1 > q.1) {
+            // cross > 0 означает, что q лежит слева от ребра a->b
+            if (b.1 > a.1) == cross.is_it_positive() {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        return Position::Inside;
+    }
+    return Position::Outside;
+}
+
+fn does_contain(outer: &Vec<(Number, Number)>, inner: &Vec<(Number, Number)>) -> bool {
+    let n = inner.len();
+    let midpoints = (0..n).map(|i| {
+        let (a, b) = (&inner[i], &inner[(i + 1) % n]);
+        (&(&a.0 + &b.0) / &Number::new(2.), &(&a.1 + &b.1) / &Number::new(2.))
+    });
+
+    for q in inner.iter().cloned().chain(midpoints) {
+        match locate_point(&q, outer) {
+            Position::Inside => return true,
+            Position::Outside => return false,
+            Position::OnBoundary => ()
+        }
+    }
+    return false;
+}
+
+pub(crate) fn get_projection_axis(normal: &Vector) -> usize {
+    let (x, y, z) = (normal.x.clone().abs(), normal.y.clone().abs(), normal.z.clone().abs());
+    if x >= y && x >= z {
+        return 0;
+    } else if y >= z {
+        return 1;
+    } else {
+        return 2;
+    }
+}
+
+pub(crate) fn build_trees(loops: Vec<Vec<Point>>, normal: &Vector) -> Vec<PolygonTreeNode> {
+    let axis = get_projection_axis(normal);
+    let sign = match axis {
+        0 => normal.x.is_it_positive(),
+        1 => normal.y.is_it_positive(),
+        _ => normal.z.is_it_positive()
+    };
+
+    let projections: Vec<Vec<(Number, Number)>> = loops.iter()
+        .map(|l| l.iter().map(|p| project(p, axis)).collect())
+        .collect();
+    let areas: Vec<Number> = projections.iter().map(|l| get_signed_area(l)).collect();
+    let abs_areas: Vec<Number> = areas.iter().map(|a| a.clone().abs()).collect();
+
+    // родитель контура - содержащий его контур наименьшей площади
+    let n = loops.len();
+    let mut parents: Vec<Option<usize>> = vec![None; n];
+    for i in 0..n {
+        for j in 0..n {
+            if i == j || abs_areas[j] <= abs_areas[i] || !does_contain(&projections[j], &projections[i]) {
+                continue;
+            }
+            let is_better = match parents[i] {
+                Some(k) => abs_areas[j] < abs_areas[k],
+                None => true
+            };
+            if is_better {
+                parents[i] = Some(j);
+            }
+        }
+    }
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut roots: Vec<usize> = Vec::new();
+    for i in 0..n {
+        match parents[i] {
+            Some(j) => children[j].push(i),
+            None => roots.push(i)
+        }
+    }
+
+    fn build_node(
+        i: usize, depth: usize, loops: &Vec<Vec<Point>>, areas: &Vec<Number>,
+        children: &Vec<Vec<usize>>, sign: bool, normal: &Vector
+    ) -> PolygonTreeNode {
+        let mut points = loops[i].clone();
+        let is_ccw = areas[i].is_it_positive() == sign;
+        if is_ccw != (depth % 2 == 0) {
+            points.reverse();
+        }
+
+        let mut node = PolygonTreeNode::new(Polygon::new(points, normal.clone()));
+        for &child in children[i].iter() {
+            node.add_child(build_node(child, depth + 1, loops, areas, children, sign, normal));
+        }
+        return node;
+    }
+
+    return roots.into_iter()
+        .map(|i| build_node(i, 0, &loops, &areas, &children, sign, normal))
+        .collect();
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::File;
@@ -600,7 +746,7 @@ mod tests {
         ];
 
 
-        let ts : Vec<Triangle> = triangulate_ptree3d(t, ss);
+        let ts : Vec<Triangle> = triangulate_ptree3d(t, ss, TriangulationAlgorithm::EarClipping).unwrap();
         assert_eq!(ts.len(), 16);
 
         let mut mesh = Mesh::new();
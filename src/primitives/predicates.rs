@@ -0,0 +1,346 @@
+use primitives::*;
+use std::cmp::Ordering;
+
+// "Adaptive Precision Floating-Point Arithmetic and Fast Robust Geometric Predicates", J. R. Shewchuk
+const EPSILON: f64 = 1.1102230246251565e-16;
+const CCW_ERROR_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const O3D_ERROR_BOUND: f64 = (7.0 + 56.0 * EPSILON) * EPSILON;
+const ICC_ERROR_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+const ISP_ERROR_BOUND: f64 = (16.0 + 224.0 * EPSILON) * EPSILON;
+
+/// This function returns the orientation of points `a`, `b`, `c` in the plane xy.
+/// It returns `Greater` if they are counterclockwise, `Less` if they are clockwise and `Equal` if they are collinear.
+/// The result is exact for every number type. For inexact types (see `NumberTrait::is_exact`) the determinant
+/// is computed in floating point and exact arithmetic is used only if the error bound doesn't guarantee the sign.
+/// # Arguments
+///
+/// * `a`, `b`, `c` - Points, z coordinates are ignored.
+pub fn orient2d<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>) -> Ordering
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    if T::is_exact() {
+        return get_sign(&orient2d_det(a, b, c));
+    }
+    if let Some(res) = orient2d_filter(&to_f64(a), &to_f64(b), &to_f64(c)) {
+        return res;
+    }
+    return get_sign::<BigRationalNumber>(&orient2d_det::<BigRationalNumber>(&to_exact(a), &to_exact(b), &to_exact(c)));
+}
+
+/// This function returns the orientation of the point `d` relative to the plane through `a`, `b`, `c`.
+/// It returns `Greater` if `d` lies below the plane, i.e. `a`, `b`, `c` are counterclockwise looking from `d`'s opposite side,
+/// `Less` if it lies above and `Equal` if the points are coplanar.
+/// It's the sign of the determinant of the matrix with rows `a - d`, `b - d`, `c - d`.
+/// # Arguments
+///
+/// * `a`, `b`, `c` - Points defining the plane.
+/// * `d` - A point to classify.
+pub fn orient3d<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>) -> Ordering
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    if T::is_exact() {
+        return get_sign(&orient3d_det(a, b, c, d));
+    }
+    if let Some(res) = orient3d_filter(&to_f64(a), &to_f64(b), &to_f64(c), &to_f64(d)) {
+        return res;
+    }
+    return get_sign::<BigRationalNumber>(&orient3d_det::<BigRationalNumber>(&to_exact(a), &to_exact(b), &to_exact(c), &to_exact(d)));
+}
+
+/// This function checks whether the point `d` lies inside the circle through `a`, `b`, `c` in the plane xy.
+/// If `a`, `b`, `c` are counterclockwise, it returns `Greater` if `d` is inside the circle,
+/// `Less` if it's outside and `Equal` if it lies on the circle. The result is inverted for clockwise points.
+/// # Arguments
+///
+/// * `a`, `b`, `c` - Points defining the circle, z coordinates are ignored.
+/// * `d` - A point to classify.
+pub fn incircle<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>) -> Ordering
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    if T::is_exact() {
+        return get_sign(&incircle_det(a, b, c, d));
+    }
+    if let Some(res) = incircle_filter(&to_f64(a), &to_f64(b), &to_f64(c), &to_f64(d)) {
+        return res;
+    }
+    return get_sign::<BigRationalNumber>(&incircle_det::<BigRationalNumber>(&to_exact(a), &to_exact(b), &to_exact(c), &to_exact(d)));
+}
+
+/// This function checks whether the point `e` lies inside the sphere through `a`, `b`, `c`, `d`.
+/// If `orient3d(a, b, c, d)` is `Greater`, it returns `Greater` if `e` is inside the sphere,
+/// `Less` if it's outside and `Equal` if it lies on the sphere. The result is inverted for the opposite orientation.
+/// # Arguments
+///
+/// * `a`, `b`, `c`, `d` - Points defining the sphere.
+/// * `e` - A point to classify.
+pub fn insphere<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>, e: &Point<T>) -> Ordering
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    if T::is_exact() {
+        return get_sign(&insphere_det(a, b, c, d, e));
+    }
+    if let Some(res) = insphere_filter(&to_f64(a), &to_f64(b), &to_f64(c), &to_f64(d), &to_f64(e)) {
+        return res;
+    }
+    return get_sign::<BigRationalNumber>(&insphere_det::<BigRationalNumber>(&to_exact(a), &to_exact(b), &to_exact(c), &to_exact(d), &to_exact(e)));
+}
+
+// значения неточного типа переводятся в f64 без потерь, а из f64 - в рациональные числа
+fn to_f64<T>(p: &Point<T>) -> [f64; 3]
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    return [p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64(), p.z.clone().convert_to_f64()];
+}
+
+fn to_exact<T>(p: &Point<T>) -> Point<BigRationalNumber>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let v = to_f64(p);
+    return Point { x: BigRationalNumber::new(v[0]), y: BigRationalNumber::new(v[1]), z: BigRationalNumber::new(v[2]) };
+}
+
+fn orient2d_det<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>) -> T
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    return (&a.x - &c.x) * (&b.y - &c.y) - (&a.y - &c.y) * (&b.x - &c.x);
+}
+
+fn orient3d_det<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>) -> T
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let rows: Vec<[T; 3]> = [a, b, c].iter().map(|p| [
+        &p.x - &d.x,
+        &p.y - &d.y,
+        &p.z - &d.z
+    ]).collect();
+    let (a, b, c) = (&rows[0], &rows[1], &rows[2]);
+    let det = &a[0] * &(&b[1] * &c[2] - &b[2] * &c[1])
+        + &a[1] * &(&b[2] * &c[0] - &b[0] * &c[2])
+        + &a[2] * &(&b[0] * &c[1] - &b[1] * &c[0]);
+    return det;
+}
+
+fn incircle_det<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>) -> T
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let rows: Vec<[T; 3]> = [a, b, c].iter().map(|p| {
+        let x = &p.x - &d.x;
+        let y = &p.y - &d.y;
+        let lift = &x * &x + &y * &y;
+        [x, y, lift]
+    }).collect();
+    let (a, b, c) = (&rows[0], &rows[1], &rows[2]);
+    let det = &a[2] * &(&b[0] * &c[1] - &b[1] * &c[0])
+        + &b[2] * &(&c[0] * &a[1] - &c[1] * &a[0])
+        + &c[2] * &(&a[0] * &b[1] - &a[1] * &b[0]);
+    return det;
+}
+
+fn insphere_det<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>, e: &Point<T>) -> T
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    let rows: Vec<[T; 4]> = [a, b, c, d].iter().map(|p| {
+        let x = &p.x - &e.x;
+        let y = &p.y - &e.y;
+        let z = &p.z - &e.z;
+        let lift = &(&x * &x + &y * &y) + &(&z * &z);
+        [x, y, z, lift]
+    }).collect();
+    let (a, b, c, d) = (&rows[0], &rows[1], &rows[2], &rows[3]);
+    let ab = &a[0] * &b[1] - &b[0] * &a[1];
+    let bc = &b[0] * &c[1] - &c[0] * &b[1];
+    let cd = &c[0] * &d[1] - &d[0] * &c[1];
+    let da = &d[0] * &a[1] - &a[0] * &d[1];
+    let ac = &a[0] * &c[1] - &c[0] * &a[1];
+    let bd = &b[0] * &d[1] - &d[0] * &b[1];
+
+    let abc = &(&a[2] * &bc - &b[2] * &ac) + &(&c[2] * &ab);
+    let bcd = &(&b[2] * &cd - &c[2] * &bd) + &(&d[2] * &bc);
+    let cda = &(&c[2] * &da + &d[2] * &ac) + &(&a[2] * &cd);
+    let dab = &(&d[2] * &ab + &a[2] * &bd) + &(&b[2] * &da);
+
+    let det = (&d[3] * &abc - &c[3] * &dab) + (&b[3] * &cda - &a[3] * &bcd);
+    return det;
+}
+
+fn get_sign<T>(x: &T) -> Ordering
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    match 1 {
+        _ if x.is_it_positive() => return Ordering::Greater,
+        _ if x.is_it_negative() => return Ordering::Less,
+        _ => return Ordering::Equal
+    }
+}
+
+fn get_filtered_sign(det: f64, error_bound: f64) -> Option<Ordering> {
+    match 1 {
+        _ if det > error_bound => return Some(Ordering::Greater),
+        _ if -det > error_bound => return Some(Ordering::Less),
+        _ => return None
+    }
+}
+
+fn orient2d_filter(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3]) -> Option<Ordering> {
+    let left = (a[0] - c[0]) * (b[1] - c[1]);
+    let right = (a[1] - c[1]) * (b[0] - c[0]);
+    let det = left - right;
+    return get_filtered_sign(det, CCW_ERROR_BOUND * (left.abs() + right.abs()));
+}
+
+fn orient3d_filter(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3], d: &[f64; 3]) -> Option<Ordering> {
+    let (adx, ady, adz) = (a[0] - d[0], a[1] - d[1], a[2] - d[2]);
+    let (bdx, bdy, bdz) = (b[0] - d[0], b[1] - d[1], b[2] - d[2]);
+    let (cdx, cdy, cdz) = (c[0] - d[0], c[1] - d[1], c[2] - d[2]);
+
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+
+    let det = adz * (bdxcdy - cdxbdy) + bdz * (cdxady - adxcdy) + cdz * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * adz.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bdz.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cdz.abs();
+    return get_filtered_sign(det, O3D_ERROR_BOUND * permanent);
+}
+
+fn incircle_filter(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3], d: &[f64; 3]) -> Option<Ordering> {
+    let (adx, ady) = (a[0] - d[0], a[1] - d[1]);
+    let (bdx, bdy) = (b[0] - d[0], b[1] - d[1]);
+    let (cdx, cdy) = (c[0] - d[0], c[1] - d[1]);
+
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    return get_filtered_sign(det, ICC_ERROR_BOUND * permanent);
+}
+
+fn insphere_filter(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3], d: &[f64; 3], e: &[f64; 3]) -> Option<Ordering> {
+    let (aex, aey, aez) = (a[0] - e[0], a[1] - e[1], a[2] - e[2]);
+    let (bex, bey, bez) = (b[0] - e[0], b[1] - e[1], b[2] - e[2]);
+    let (cex, cey, cez) = (c[0] - e[0], c[1] - e[1], c[2] - e[2]);
+    let (dex, dey, dez) = (d[0] - e[0], d[1] - e[1], d[2] - e[2]);
+
+    let (aexbey, bexaey) = (aex * bey, bex * aey);
+    let (bexcey, cexbey) = (bex * cey, cex * bey);
+    let (cexdey, dexcey) = (cex * dey, dex * cey);
+    let (dexaey, aexdey) = (dex * aey, aex * dey);
+    let (aexcey, cexaey) = (aex * cey, cex * aey);
+    let (bexdey, dexbey) = (bex * dey, dex * bey);
+    let (ab, bc, cd, da) = (aexbey - bexaey, bexcey - cexbey, cexdey - dexcey, dexaey - aexdey);
+    let (ac, bd) = (aexcey - cexaey, bexdey - dexbey);
+
+    let abc = aez * bc - bez * ac + cez * ab;
+    let bcd = bez * cd - cez * bd + dez * bc;
+    let cda = cez * da + dez * ac + aez * cd;
+    let dab = dez * ab + aez * bd + bez * da;
+
+    let alift = aex * aex + aey * aey + aez * aez;
+    let blift = bex * bex + bey * bey + bez * bez;
+    let clift = cex * cex + cey * cey + cez * cez;
+    let dlift = dex * dex + dey * dey + dez * dez;
+    let det = (dlift * abc - clift * dab) + (blift * cda - alift * bcd);
+
+    let (aezplus, bezplus, cezplus, dezplus) = (aez.abs(), bez.abs(), cez.abs(), dez.abs());
+    let (aexbeyplus, bexaeyplus) = (aexbey.abs(), bexaey.abs());
+    let (bexceyplus, cexbeyplus) = (bexcey.abs(), cexbey.abs());
+    let (cexdeyplus, dexceyplus) = (cexdey.abs(), dexcey.abs());
+    let (dexaeyplus, aexdeyplus) = (dexaey.abs(), aexdey.abs());
+    let (aexceyplus, cexaeyplus) = (aexcey.abs(), cexaey.abs());
+    let (bexdeyplus, dexbeyplus) = (bexdey.abs(), dexbey.abs());
+    let permanent = ((cexdeyplus + dexceyplus) * bezplus
+        + (dexbeyplus + bexdeyplus) * cezplus
+        + (bexceyplus + cexbeyplus) * dezplus) * alift
+        + ((dexaeyplus + aexdeyplus) * cezplus
+        + (aexceyplus + cexaeyplus) * dezplus
+        + (cexdeyplus + dexceyplus) * aezplus) * blift
+        + ((aexbeyplus + bexaeyplus) * dezplus
+        + (bexdeyplus + dexbeyplus) * aezplus
+        + (dexaeyplus + aexdeyplus) * bezplus) * clift
+        + ((bexceyplus + cexbeyplus) * aezplus
+        + (cexaeyplus + aexceyplus) * bezplus
+        + (aexbeyplus + bexaeyplus) * cezplus) * dlift;
+    return get_filtered_sign(det, ISP_ERROR_BOUND * permanent);
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::predicates::*;
+    use std::cmp::Ordering;
+    use num::rational::BigRational;
+    use num::{Zero, Signed};
+
+    fn p(x: f64, y: f64, z: f64) -> Point {
+        return Point::new_from_f64(x, y, z);
+    }
+
+    fn exact(x: f64) -> BigRational {
+        return BigRational::from_float(x).unwrap();
+    }
+
+    fn exact_orient2d(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Ordering {
+        let det = (exact(a.0) - exact(c.0)) * (exact(b.1) - exact(c.1))
+            - (exact(a.1) - exact(c.1)) * (exact(b.0) - exact(c.0));
+        match 1 {
+            _ if det.is_zero() => return Ordering::Equal,
+            _ if det.is_positive() => return Ordering::Greater,
+            _ => return Ordering::Less
+        }
+    }
+
+    #[test]
+    fn orientation() {
+        assert_eq!(orient2d(&p(0., 0., 0.), &p(1., 0., 0.), &p(0., 1., 0.)), Ordering::Greater);
+        assert_eq!(orient2d(&p(0., 0., 0.), &p(0., 1., 0.), &p(1., 0., 0.)), Ordering::Less);
+        assert_eq!(orient2d(&p(0., 0., 0.), &p(1., 1., 0.), &p(3., 3., 5.)), Ordering::Equal);
+
+        let (a, b, c) = (p(0., 0., 0.), p(1., 0., 0.), p(0., 1., 0.));
+        assert_eq!(orient3d(&a, &b, &c, &p(0.2, 0.2, -1.)), Ordering::Greater);
+        assert_eq!(orient3d(&a, &b, &c, &p(0.2, 0.2, 1.)), Ordering::Less);
+        assert_eq!(orient3d(&a, &b, &c, &p(5., -3., 0.)), Ordering::Equal);
+    }
+
+    #[test]
+    fn circle() {
+        let (a, b, c) = (p(1., 0., 0.), p(0., 1., 0.), p(-1., 0., 0.));
+        assert_eq!(incircle(&a, &b, &c, &p(0., 0., 0.)), Ordering::Greater);
+        assert_eq!(incircle(&a, &b, &c, &p(0., -1., 0.)), Ordering::Equal);
+        assert_eq!(incircle(&a, &b, &c, &p(0., -1.5, 0.)), Ordering::Less);
+        assert_eq!(incircle(&c, &b, &a, &p(0., 0., 0.)), Ordering::Less);
+    }
+
+    #[test]
+    fn sphere() {
+        let (a, b, c, d) = (p(1., 0., 0.), p(0., 1., 0.), p(-1., 0., 0.), p(0., 0., -1.));
+        assert_eq!(orient3d(&a, &b, &c, &d), Ordering::Greater);
+        assert_eq!(insphere(&a, &b, &c, &d, &p(0., 0., 0.5)), Ordering::Greater);
+        assert_eq!(insphere(&a, &b, &c, &d, &p(0., 0., 1.)), Ordering::Equal);
+        assert_eq!(insphere(&a, &b, &c, &d, &p(0.8, 0.8, 0.)), Ordering::Less);
+        assert_eq!(insphere(&b, &a, &c, &d, &p(0., 0., 0.5)), Ordering::Less);
+    }
+
+    #[test]
+    fn nearly_collinear_points() {
+        // точки почти на прямой y = x, где наивное вычисление в f64 ошибается
+        let (a, b) = ((0.5, 0.5), (12., 12.));
+        for i in 0..64 {
+            for j in 0..64 {
+                let c = (0.5 + (i as f64) * 2f64.powi(-53), 0.5 + (j as f64) * 2f64.powi(-53));
+                let expected = exact_orient2d(a, b, c);
+                let res = orient2d(&p(a.0, a.1, 0.), &p(b.0, b.1, 0.), &p(c.0, c.1, 0.));
+                assert_eq!(res, expected);
+                let (fa, fb, fc) : (Point<FloatNumber>, Point<FloatNumber>, Point<FloatNumber>) =
+                    (Point::from_f64(a.0, a.1, 0.), Point::from_f64(b.0, b.1, 0.), Point::from_f64(c.0, c.1, 0.));
+                assert_eq!(orient2d(&fa, &fb, &fc), expected);
+            }
+        }
+    }
+}
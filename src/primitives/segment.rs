@@ -3,7 +3,7 @@ use primitives::point::Point;
 use primitives::line::Line;
 // use primitives::number_trait;
 // use primitives::number_trait::NumberTrait;
-use primitives::zero_trait::Zero;
+use primitives::number::*;
 
 use std::fmt;
 use std::cmp::Ordering;
@@ -12,13 +12,15 @@ use std::mem::swap;
 
 #[derive(Clone)]
 #[derive(Debug, Hash)]
-pub struct Segment {
-    pub org : Point,
-    pub dest: Point
+pub struct Segment<T = Number> {
+    pub org : Point<T>,
+    pub dest: Point<T>
 }
 
-impl Segment {
-    pub fn new(org : Point, dest : Point) -> Segment {
+impl<T> Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    pub fn new(org : Point<T>, dest : Point<T>) -> Segment<T> {
         if org == dest {
             panic!("org == dest");
         }
@@ -29,7 +31,7 @@ impl Segment {
         }
     }
 
-    pub fn get_point_projection(&self, p: &Point) -> Point {
+    pub fn get_point_projection(&self, p: &Point<T>) -> Point<T> {
         let op = p - &self.org;
         let od = &self.dest - &self.org;
 
@@ -43,17 +45,17 @@ impl Segment {
         return &self.org + &(od*(dot_od_od / dot_op_od));
     }
 
-    pub fn get_org_dest(self) -> (Point, Point) {
+    pub fn get_org_dest(self) -> (Point<T>, Point<T>) {
         return (self.org, self.dest);
     }
 
-    pub fn contains_point(&self, p: &Point) -> bool {
+    pub fn contains_point(&self, p: &Point<T>) -> bool {
         // <p>, <s.org> and <s.dest> have to belong the same line!
         return (self.org <= self.dest) && (p >= &self.org) && (p <= &self.dest) ||
                (self.org > self.dest) && (p >= &self.dest) && (p <= &self.org);
     }
 
-    pub fn gen_line(&self) -> Line {
+    pub fn gen_line(&self) -> Line<T> {
         return Line::new(self.org.clone(), self.dest.clone());
     }
 
@@ -71,29 +73,39 @@ impl Segment {
 
 // pub enum SegmentsInfo {Parallel, Skew}
 
-impl fmt::Display for Segment {
+impl<T> fmt::Display for Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "(Segment: {}, {})", self.org, self.dest)
     }
 }
 
-impl PartialOrd for Segment {
-    fn partial_cmp(&self, other: &Segment) -> Option<Ordering> {
+impl<T> PartialOrd for Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn partial_cmp(&self, other: &Segment<T>) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Eq for Segment {}
+impl<T> Eq for Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{}
 
-impl PartialEq for Segment {
-    fn eq(&self, other: &Segment) -> bool {
+impl<T> PartialEq for Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn eq(&self, other: &Segment<T>) -> bool {
         self.org == other.org &&  self.dest == other.dest || self.org == other.dest &&  self.dest == other.org
     }
 }
 
 
-impl Ord for Segment {
-    fn cmp(&self, other: &Segment) -> Ordering {
+impl<T> Ord for Segment<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn cmp(&self, other: &Segment<T>) -> Ordering {
         match self {
             _ if *self == *other => Ordering::Equal,
             _ if self.org < other.org => Ordering::Less,
@@ -0,0 +1,205 @@
+use primitives::*;
+use std::io::{Result, Write};
+
+/// A volume mesh of tetrahedra. Tetrahedra are oriented so that their signed volumes
+/// (the mixed product of edges from the first vertex, divided by 6) are positive.
+/// Boundary faces are oriented outwards and have markers: faces of the same planar facet of the boundary
+/// share the marker, markers start from 1.
+#[derive(Clone, Debug)]
+pub struct TetMesh {
+    points: Vec<Point>,
+    tetrahedra: Vec<[usize; 4]>,
+    boundary_faces: Vec<([usize; 3], usize)>
+}
+
+impl TetMesh {
+    /// This method creates a volume mesh. It returns an error if an index is out of range, a tetrahedron is degenerate
+    /// or negatively oriented or a boundary face is degenerate.
+    /// # Arguments
+    ///
+    /// * `points` - Vertices.
+    /// * `tetrahedra` - Indexes of vertices of tetrahedra.
+    /// * `boundary_faces` - Indexes of vertices of boundary faces with markers.
+    pub fn new(points: Vec<Point>, tetrahedra: Vec<[usize; 4]>, boundary_faces: Vec<([usize; 3], usize)>) -> ::std::result::Result<TetMesh, &'static str> {
+        let n = points.len();
+        if tetrahedra.iter().any(|t| t.iter().any(|&i| i >= n)) || boundary_faces.iter().any(|f| f.0.iter().any(|&i| i >= n)) {
+            return Err("Index of a point is out of range!");
+        }
+        let mesh = TetMesh { points: points, tetrahedra: tetrahedra, boundary_faces: boundary_faces };
+        if (0..mesh.tetrahedra.len()).any(|i| !mesh.get_signed_volume6(i).is_it_positive()) {
+            return Err("Tetrahedron is degenerate or negatively oriented!");
+        }
+        if mesh.boundary_faces.iter().any(|&(f, _)| mesh.get_face(&f).degradation_level() != 0) {
+            return Err("Boundary face is degenerate!");
+        }
+        return Ok(mesh);
+    }
+
+    pub fn get_points(&self) -> &Vec<Point> {
+        return &self.points;
+    }
+
+    pub fn get_tetrahedra(&self) -> &Vec<[usize; 4]> {
+        return &self.tetrahedra;
+    }
+
+    pub fn get_boundary_faces(&self) -> &Vec<([usize; 3], usize)> {
+        return &self.boundary_faces;
+    }
+
+    pub fn num_of_points(&self) -> usize {
+        return self.points.len();
+    }
+
+    pub fn num_of_tetrahedra(&self) -> usize {
+        return self.tetrahedra.len();
+    }
+
+    /// This method returns the exact volume of the tetrahedron by `index`.
+    /// # Arguments
+    ///
+    /// * `index` - An index of the tetrahedron.
+    pub fn get_tetrahedron_volume(&self, index: usize) -> Number {
+        return self.get_signed_volume6(index) / Number::new(6.);
+    }
+
+    /// This method returns the exact volume of the mesh.
+    pub fn get_volume(&self) -> Number {
+        let mut volume6 = Number::new(0.);
+        for i in 0..self.tetrahedra.len() {
+            volume6 = volume6 + self.get_signed_volume6(i);
+        }
+        return volume6 / Number::new(6.);
+    }
+
+    /// This method returns the surface mesh of boundary faces.
+    pub fn get_boundary_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::new();
+        for &(f, _) in self.boundary_faces.iter() {
+            // вырожденные грани отсеиваются в TetMesh::new
+            mesh.add_triangle(self.get_face(&f)).unwrap();
+        }
+        return mesh;
+    }
+
+    fn get_face(&self, f: &[usize; 3]) -> Triangle {
+        return Triangle::new(f.iter().map(|&i| self.points[i].clone()).collect());
+    }
+
+    fn get_signed_volume6(&self, index: usize) -> Number {
+        let t = &self.tetrahedra[index];
+        let a = &self.points[t[0]];
+        let (ab, ac, ad): (Vector, Vector, Vector) = (&self.points[t[1]] - a, &self.points[t[2]] - a, &self.points[t[3]] - a);
+        return ab.mixed_product(&ac, &ad);
+    }
+
+    /// This method writes the mesh in TetGen format: vertices to `node`, tetrahedra to `ele` and
+    /// boundary faces with markers to `face`. Indexes start from 1.
+    /// # Arguments
+    ///
+    /// * `node`, `ele`, `face` - Types, implementing Write.
+    pub fn write_tetgen<N: Write, E: Write, F: Write>(&self, node: &mut N, ele: &mut E, face: &mut F) -> Result<()> {
+        writeln!(node, "{} 3 0 0", self.points.len())?;
+        for (i, p) in self.points.iter().enumerate() {
+            writeln!(node, "{} {} {} {}", i + 1, p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64(), p.z.clone().convert_to_f64())?;
+        }
+
+        writeln!(ele, "{} 4 0", self.tetrahedra.len())?;
+        for (i, t) in self.tetrahedra.iter().enumerate() {
+            writeln!(ele, "{} {} {} {} {}", i + 1, t[0] + 1, t[1] + 1, t[2] + 1, t[3] + 1)?;
+        }
+
+        writeln!(face, "{} 1", self.boundary_faces.len())?;
+        for (i, &(f, marker)) in self.boundary_faces.iter().enumerate() {
+            writeln!(face, "{} {} {} {} {}", i + 1, f[0] + 1, f[1] + 1, f[2] + 1, marker)?;
+        }
+        Ok(())
+    }
+
+    /// This method writes the mesh to the `out` in ASCII Gmsh format 2.2.
+    /// Boundary faces are triangles with the physical tag equal to the marker, tetrahedra have the physical tag 1.
+    /// # Arguments
+    ///
+    /// * `out` - A type, implementing Write.
+    pub fn write_msh<T: Write>(&self, out: &mut T) -> Result<()> {
+        writeln!(out, "$MeshFormat")?;
+        writeln!(out, "2.2 0 8")?;
+        writeln!(out, "$EndMeshFormat")?;
+
+        writeln!(out, "$Nodes")?;
+        writeln!(out, "{}", self.points.len())?;
+        for (i, p) in self.points.iter().enumerate() {
+            writeln!(out, "{} {} {} {}", i + 1, p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64(), p.z.clone().convert_to_f64())?;
+        }
+        writeln!(out, "$EndNodes")?;
+
+        writeln!(out, "$Elements")?;
+        writeln!(out, "{}", self.boundary_faces.len() + self.tetrahedra.len())?;
+        let mut id = 1;
+        for &(f, marker) in self.boundary_faces.iter() {
+            writeln!(out, "{} 2 2 {} {} {} {} {}", id, marker, marker, f[0] + 1, f[1] + 1, f[2] + 1)?;
+            id += 1;
+        }
+        for t in self.tetrahedra.iter() {
+            writeln!(out, "{} 4 2 1 1 {} {} {} {}", id, t[0] + 1, t[1] + 1, t[2] + 1, t[3] + 1)?;
+            id += 1;
+        }
+        writeln!(out, "$EndElements")?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::tet_mesh::TetMesh;
+
+    fn unit_tetrahedron() -> TetMesh {
+        let points = vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 1., 0.),
+            Point::new_from_f64(0., 0., 1.)
+        ];
+        let faces = vec![([0, 2, 1], 1), ([0, 1, 3], 2), ([0, 3, 2], 3), ([1, 2, 3], 4)];
+        return TetMesh::new(points, vec![[0, 1, 2, 3]], faces).unwrap();
+    }
+
+    #[test]
+    fn volume_and_boundary() {
+        let mesh = unit_tetrahedron();
+        assert_eq!(mesh.get_volume(), Number::new(1.) / Number::new(6.));
+        let boundary = mesh.get_boundary_mesh();
+        assert!(boundary.geometry_check());
+        assert_eq!(boundary.get_volume(), mesh.get_volume());
+
+        let points = mesh.get_points().clone();
+        assert!(TetMesh::new(points.clone(), vec![[0, 2, 1, 3]], vec![]).is_err());
+        assert!(TetMesh::new(points.clone(), vec![[0, 1, 2, 4]], vec![]).is_err());
+        assert!(TetMesh::new(points, vec![[0, 1, 2, 3]], vec![([0, 1, 1], 1)]).is_err());
+    }
+
+    #[test]
+    fn writers() {
+        let mesh = unit_tetrahedron();
+
+        let mut msh: Vec<u8> = Vec::new();
+        mesh.write_msh(&mut msh).unwrap();
+        let msh = String::from_utf8(msh).unwrap();
+        let lines: Vec<&str> = msh.lines().collect();
+        assert_eq!(lines[0], "$MeshFormat");
+        assert_eq!(lines[4], "4");
+        assert_eq!(lines[6], "2 1 0 0");
+        assert_eq!(lines[11], "5");
+        assert_eq!(lines[12], "1 2 2 1 1 1 3 2");
+        assert_eq!(lines[16], "5 4 2 1 1 1 2 3 4");
+        assert_eq!(lines[17], "$EndElements");
+
+        let (mut node, mut ele, mut face): (Vec<u8>, Vec<u8>, Vec<u8>) = (Vec::new(), Vec::new(), Vec::new());
+        mesh.write_tetgen(&mut node, &mut ele, &mut face).unwrap();
+        assert_eq!(String::from_utf8(node).unwrap().lines().nth(4), Some("4 0 0 1"));
+        assert_eq!(String::from_utf8(ele).unwrap(), "1 4 0\n1 1 2 3 4\n");
+        assert_eq!(String::from_utf8(face).unwrap().lines().last(), Some("4 2 3 4 4"));
+    }
+}
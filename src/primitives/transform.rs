@@ -0,0 +1,525 @@
+use primitives::point::Point;
+use primitives::vector::Vector;
+use primitives::plane::Plane;
+use matrix::{Matrix, Row};
+use primitives::number::*;
+use primitives::zero_trait::Zero;
+use primitives::signed_trait::Signed;
+use std::f64::consts::PI;
+
+const MAX_DENOMINATOR: f64 = 67108864.;
+
+/// This structure represents an exact affine transformation of 3D space.
+/// It's stored as a 4x4 matrix with the last row `[0, 0, 0, 1]`, which is applied to column vectors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transform {
+    matrix: [[Number; 4]; 4]
+}
+
+impl Transform {
+    /// This method creates `Transform` from a 4x4 affine matrix.
+    /// It returns an error if the last row is not `[0, 0, 0, 1]` or the linear part is singular.
+    /// # Arguments
+    ///
+    /// * `matrix` - A matrix, which is applied to column vectors `[x, y, z, 1]`.
+    pub fn new(matrix: [[Number; 4]; 4]) -> Result<Transform, &'static str> {
+        let zero = Number::zero();
+        if matrix[3][0] != zero || matrix[3][1] != zero || matrix[3][2] != zero || matrix[3][3] != Number::new(1.) {
+            return Err("The last row of an affine matrix has to be [0, 0, 0, 1]!");
+        }
+
+        let t = Transform { matrix };
+        if t.get_determinant().is_it_zero() {
+            return Err("The linear part of an affine matrix is singular!");
+        }
+        return Ok(t);
+    }
+
+    fn new_linear(m: [[Number; 3]; 3], v: [Number; 3]) -> Transform {
+        let row = |i: usize| -> [Number; 4] {
+            [m[i][0].clone(), m[i][1].clone(), m[i][2].clone(), v[i].clone()]
+        };
+        return Transform {
+            matrix: [
+                row(0),
+                row(1),
+                row(2),
+                [Number::zero(), Number::zero(), Number::zero(), Number::new(1.)]
+            ]
+        };
+    }
+
+    /// This method returns the identity transformation.
+    pub fn identity() -> Transform {
+        return Transform::scaling(Number::new(1.), Number::new(1.), Number::new(1.)).unwrap();
+    }
+
+    /// This method returns a translation by the vector `v`.
+    /// # Arguments
+    ///
+    /// * `v` - A translation vector.
+    pub fn translation(v: &Vector) -> Transform {
+        let one = Number::new(1.);
+        let zero = Number::zero();
+        return Transform::new_linear(
+            [
+                [one.clone(), zero.clone(), zero.clone()],
+                [zero.clone(), one.clone(), zero.clone()],
+                [zero.clone(), zero, one]
+            ],
+            [v.x.clone(), v.y.clone(), v.z.clone()]
+        );
+    }
+
+    /// This method returns a non-uniform scaling relative to the origin.
+    /// It returns an error if one of factors is zero. Negative factors mirror the space.
+    /// # Arguments
+    ///
+    /// * `sx` - A scale factor along the x axis.
+    /// * `sy` - A scale factor along the y axis.
+    /// * `sz` - A scale factor along the z axis.
+    pub fn scaling(sx: Number, sy: Number, sz: Number) -> Result<Transform, &'static str> {
+        if sx.is_it_zero() || sy.is_it_zero() || sz.is_it_zero() {
+            return Err("Scale factors have to be non zero!");
+        }
+
+        let zero = Number::zero();
+        return Ok(Transform::new_linear(
+            [
+                [sx, zero.clone(), zero.clone()],
+                [zero.clone(), sy, zero.clone()],
+                [zero.clone(), zero.clone(), sz]
+            ],
+            [zero.clone(), zero.clone(), zero]
+        ));
+    }
+
+    /// This method returns a reflection relative to the plane, specified by a point and a normal.
+    /// The normal doesn't have to be normalized, so the reflection is exact.
+    /// # Arguments
+    ///
+    /// * `point` - A point of the mirror plane.
+    /// * `normal` - A non zero normal of the mirror plane.
+    pub fn mirror(point: &Point, normal: &Vector) -> Result<Transform, &'static str> {
+        if normal.is_zero() {
+            return Err("The normal of a mirror plane has to be non zero!");
+        }
+
+        // x' = x - 2*n*(n*(x - p))/(n*n)
+        let n = [normal.x.clone(), normal.y.clone(), normal.z.clone()];
+        let k = Number::new(2.) / normal.length2();
+        let np = normal.dot_product(&point.get_vector());
+
+        let mut m: [[Number; 3]; 3] = zero_3x3();
+        let mut v: [Number; 3] = [Number::zero(), Number::zero(), Number::zero()];
+        for i in 0..3 {
+            for j in 0..3 {
+                m[i][j] = -(&(&k * &n[i]) * &n[j]);
+                if i == j {
+                    m[i][j] = &m[i][j] + &Number::new(1.);
+                }
+            }
+            v[i] = &(&k * &n[i]) * &np;
+        }
+
+        return Ok(Transform::new_linear(m, v));
+    }
+
+    /// This method returns a rotation around the origin, specified by a quaternion `w + x*i + y*j + z*k`.
+    /// The quaternion doesn't have to be normalized: the matrix is divided by its squared norm,
+    /// so the rotation is exactly orthogonal for any rational quaternion.
+    /// It rotates around the axis `(x, y, z)` by the angle `2*atan2(|(x, y, z)|, w)`.
+    /// # Arguments
+    ///
+    /// * `w` - A scalar part of the quaternion.
+    /// * `x` - The first component of the vector part.
+    /// * `y` - The second component of the vector part.
+    /// * `z` - The third component of the vector part.
+    pub fn rotation(w: Number, x: Number, y: Number, z: Number) -> Result<Transform, &'static str> {
+        let ww = &w * &w;
+        let xx = &x * &x;
+        let yy = &y * &y;
+        let zz = &z * &z;
+        let norm2 = &(&ww + &xx) + &(&yy + &zz);
+        if norm2.is_it_zero() {
+            return Err("The quaternion has to be non zero!");
+        }
+
+        let two = Number::new(2.);
+        let xy = &x * &y;
+        let xz = &x * &z;
+        let yz = &y * &z;
+        let wx = &w * &x;
+        let wy = &w * &y;
+        let wz = &w * &z;
+
+        let m = [
+            [&(&ww + &xx) - &(&yy + &zz), &two * &(&xy - &wz), &two * &(&xz + &wy)],
+            [&two * &(&xy + &wz), &(&ww + &yy) - &(&xx + &zz), &two * &(&yz - &wx)],
+            [&two * &(&xz - &wy), &two * &(&yz + &wx), &(&ww + &zz) - &(&xx + &yy)]
+        ];
+
+        let mut normalized: [[Number; 3]; 3] = zero_3x3();
+        for i in 0..3 {
+            for j in 0..3 {
+                normalized[i][j] = &m[i][j] / &norm2;
+            }
+        }
+
+        return Ok(Transform::new_linear(normalized, [Number::zero(), Number::zero(), Number::zero()]));
+    }
+
+    /// This method returns an exact rotation around the axis, which passes through the origin.
+    /// The rotation matrix has rational entries and is exactly orthogonal, and its angle differs
+    /// from `angle` by no more than `tolerance`. Rational approximations with small denominators are chosen.
+    /// The rotation is counterclockwise if it's seen from the end of `axis`.
+    /// # Arguments
+    ///
+    /// * `axis` - A non zero direction of the axis. It doesn't have to be normalized.
+    /// * `angle` - An angle of the rotation in radians.
+    /// * `tolerance` - A positive maximum error of the angle in radians.
+    pub fn rotation_around_axis(axis: &Vector, angle: f64, tolerance: f64) -> Result<Transform, &'static str> {
+        if axis.is_zero() {
+            return Err("The axis of a rotation has to be non zero!");
+        }
+        if !angle.is_finite() {
+            return Err("The angle of a rotation has to be finite!");
+        }
+        if !(tolerance > 0.) {
+            return Err("The tolerance has to be positive!");
+        }
+
+        // the angle is reduced to (-pi, pi]
+        let two_pi = 2. * PI;
+        let mut angle = angle % two_pi;
+        if angle > PI {
+            angle -= two_pi;
+        } else if angle <= -PI {
+            angle += two_pi;
+        }
+
+        // the quaternion (w, s*axis) rotates by the angle 2*atan2(|s*axis|, w)
+        let axis_length = axis.length2().convert_to_f64().sqrt();
+        let half_angle = angle.abs() / 2.;
+        let (w, s) = if half_angle <= PI / 4. {
+            // w = 1, s ~ tan(angle/2)/|axis|; d(angle)/ds <= 2*|axis|
+            let s = approximate_by_rational(half_angle.tan() / axis_length, tolerance / (2. * axis_length));
+            (Number::new(1.), s)
+        } else {
+            // s = 1, w ~ |axis|*cot(angle/2); |d(angle)/dw| <= 2/|axis|
+            let w = approximate_by_rational(axis_length / half_angle.tan(), tolerance * axis_length / 2.);
+            (w, Number::new(1.))
+        };
+
+        let s = if angle < 0. {-s} else {s};
+        return Transform::rotation(w, &axis.x * &s, &axis.y * &s, &axis.z * &s);
+    }
+
+    /// This method returns a transformation, which applies `self` and then `other`.
+    /// # Arguments
+    ///
+    /// * `other` - A transformation to apply after `self`.
+    pub fn then(&self, other: &Transform) -> Transform {
+        let mut matrix: [[Number; 4]; 4] = [
+            [Number::zero(), Number::zero(), Number::zero(), Number::zero()],
+            [Number::zero(), Number::zero(), Number::zero(), Number::zero()],
+            [Number::zero(), Number::zero(), Number::zero(), Number::zero()],
+            [Number::zero(), Number::zero(), Number::zero(), Number::zero()]
+        ];
+
+        for i in 0..4 {
+            for j in 0..4 {
+                for k in 0..4 {
+                    matrix[i][j] = &matrix[i][j] + &(&other.matrix[i][k] * &self.matrix[k][j]);
+                }
+            }
+        }
+
+        return Transform { matrix };
+    }
+
+    /// This method returns the reference to the 4x4 matrix of the transformation.
+    pub fn get_matrix(&self) -> &[[Number; 4]; 4] {
+        return &self.matrix;
+    }
+
+    /// This method creates `Transform` from a 4x4 affine `Matrix`. See `Transform::new`.
+    /// # Arguments
+    ///
+    /// * `m` - A matrix, which is applied to column vectors `[x, y, z, 1]`.
+    pub fn new_from_homogeneous_matrix(m: &Matrix<Number>) -> Result<Transform, &'static str> {
+        if m.number_of_rows() != 4 || m.number_of_columns() != 4 {
+            return Err("An affine matrix has to be 4x4!");
+        }
+        let row = |i: usize| -> [Number; 4] {
+            [m.get(&i, &0), m.get(&i, &1), m.get(&i, &2), m.get(&i, &3)]
+        };
+        return Transform::new([row(0), row(1), row(2), row(3)]);
+    }
+
+    /// This method returns the 4x4 matrix of the transformation as `Matrix`.
+    pub fn get_homogeneous_matrix(&self) -> Matrix<Number> {
+        return Matrix::new_from_vector(self.matrix.iter().map(|r| Row::new_from_vector(r.to_vec())).collect());
+    }
+
+    /// This method returns the inverse transformation.
+    pub fn get_inverse(&self) -> Transform {
+        let inverse = self.get_homogeneous_matrix().get_inverse().expect("Affine transformation is singular!");
+        return Transform::new_from_homogeneous_matrix(&inverse).unwrap();
+    }
+
+    /// This method returns the determinant of the linear part.
+    /// It's negative if the transformation changes an orientation of space.
+    pub fn get_determinant(&self) -> Number {
+        let m = &self.matrix;
+        let minor = |i: usize, j: usize, k: usize, l: usize| -> Number {
+            &m[i][k] * &m[j][l] - &m[i][l] * &m[j][k]
+        };
+        return &(&m[0][0] * &minor(1, 2, 1, 2) - &m[0][1] * &minor(1, 2, 0, 2)) +
+            &(&m[0][2] * &minor(1, 2, 0, 1));
+    }
+
+    /// This method checks if the transformation changes an orientation of space, i.e. if it mirrors.
+    pub fn does_it_flip_orientation(&self) -> bool {
+        return self.get_determinant().is_it_negative();
+    }
+
+    /// This method applies the transformation to the point `p`.
+    /// # Arguments
+    ///
+    /// * `p` - A point to transform.
+    pub fn apply_to_point(&self, p: &Point) -> Point {
+        let v = self.apply_to_vector(&p.get_vector());
+        return Point::new(
+            v.x + &self.matrix[0][3],
+            v.y + &self.matrix[1][3],
+            v.z + &self.matrix[2][3]
+        );
+    }
+
+    /// This method applies the linear part of the transformation to the vector `v`.
+    /// # Arguments
+    ///
+    /// * `v` - A vector to transform.
+    pub fn apply_to_vector(&self, v: &Vector) -> Vector {
+        let c = [&v.x, &v.y, &v.z];
+        let m = &self.matrix;
+        let row = |i: usize| -> Number {
+            &(&(&m[i][0] * c[0]) + &(&m[i][1] * c[1])) + &(&m[i][2] * c[2])
+        };
+        return Vector::new(row(0), row(1), row(2));
+    }
+
+    /// This method applies the transformation to the plane `plane`. The normal is transformed by the inverse transposed
+    /// linear part, so the result contains images of points of the plane and its normal points to the image of the same side.
+    /// # Arguments
+    ///
+    /// * `plane` - A plane to transform.
+    pub fn apply_to_plane(&self, plane: &Plane) -> Plane {
+        let inverse = self.get_inverse();
+        let m = &inverse.matrix;
+        let n = plane.get_ref_normal();
+        let column = |j: usize| -> Number {
+            &(&(&m[0][j] * &n.x) + &(&m[1][j] * &n.y)) + &(&m[2][j] * &n.z)
+        };
+        return Plane::new(Vector::new(column(0), column(1), column(2)), self.apply_to_point(&plane.point));
+    }
+}
+
+// возвращает подходящую дробь цепной дроби, отличающуюся от value не более чем на tolerance;
+// точность меньше единицы последнего разряда value не имеет смысла, поэтому tolerance ограничивается снизу
+pub(crate) fn approximate_by_rational(value: f64, tolerance: f64) -> Number {
+    let tolerance = tolerance.max(value.abs().max(1.) * ::std::f64::EPSILON);
+    let (mut h_prev, mut h) = (0f64, 1f64);
+    let (mut k_prev, mut k) = (1f64, 0f64);
+    let mut x = value;
+
+    loop {
+        let a = x.floor();
+        let h_next = a * h + h_prev;
+        let k_next = a * k + k_prev;
+        if k_next > MAX_DENOMINATOR || h_next.abs() > MAX_DENOMINATOR {
+            break;
+        }
+        h_prev = h;
+        h = h_next;
+        k_prev = k;
+        k = k_next;
+
+        if (h / k - value).abs() <= tolerance || x == a {
+            return Number::new(h) / Number::new(k);
+        }
+        x = 1. / (x - a);
+    }
+
+    // цепная дробь не сошлась в пределах точности f64, поэтому используется двоичная дробь
+    let mut q = 1f64;
+    while 1. / q > tolerance {
+        q *= 2.;
+    }
+    return Number::new((value * q).round()) / Number::new(q);
+}
+
+fn zero_3x3() -> [[Number; 3]; 3] {
+    [
+        [Number::zero(), Number::zero(), Number::zero()],
+        [Number::zero(), Number::zero(), Number::zero()],
+        [Number::zero(), Number::zero(), Number::zero()]
+    ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::transform::{Transform, approximate_by_rational};
+
+    fn transpose_product(t: &Transform) -> Vec<Vec<Number>> {
+        let m = t.get_matrix();
+        let mut res: Vec<Vec<Number>> = Vec::new();
+        for i in 0..3 {
+            let mut row: Vec<Number> = Vec::new();
+            for j in 0..3 {
+                let mut sum = Number::zero();
+                for k in 0..3 {
+                    sum = sum + &m[k][i] * &m[k][j];
+                }
+                row.push(sum);
+            }
+            res.push(row);
+        }
+        return res;
+    }
+
+    #[test]
+    fn rotation_is_orthogonal() {
+        let t = Transform::rotation(Number::new(3.), Number::new(1.), Number::new(-2.), Number::new(5.)).unwrap();
+        let mtm = transpose_product(&t);
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j {Number::new(1.)} else {Number::zero()};
+                assert_eq!(mtm[i][j], expected);
+            }
+        }
+        assert_eq!(t.get_determinant(), Number::new(1.));
+    }
+
+    #[test]
+    fn rotation_by_right_angle() {
+        // the quaternion (1, 0, 0, 1) rotates by 90 degrees around the z axis
+        let t = Transform::rotation(Number::new(1.), Number::zero(), Number::zero(), Number::new(1.)).unwrap();
+        assert_eq!(t.apply_to_point(&Point::new_from_f64(1., 2., 3.)), Point::new_from_f64(-2., 1., 3.));
+    }
+
+    #[test]
+    fn mirror_and_composition() {
+        let m = Transform::mirror(&Point::new_from_f64(1., 0., 0.), &Vector::new_from_f64(2., 0., 0.)).unwrap();
+        assert!(m.does_it_flip_orientation());
+        assert_eq!(m.apply_to_point(&Point::new_from_f64(3., 1., 1.)), Point::new_from_f64(-1., 1., 1.));
+
+        let m = Transform::mirror(&Point::new_from_f64(0., 0., 0.), &Vector::new_from_f64(1., 1., 0.)).unwrap();
+        assert_eq!(m.apply_to_point(&Point::new_from_f64(1., 0., 5.)), Point::new_from_f64(0., -1., 5.));
+
+        let t = Transform::translation(&Vector::new_from_f64(1., 2., 3.))
+            .then(&Transform::scaling(Number::new(2.), Number::new(1.), Number::new(-1.)).unwrap());
+        assert_eq!(t.apply_to_point(&Point::new_from_f64(0., 0., 0.)), Point::new_from_f64(2., 2., -3.));
+        assert_eq!(t.apply_to_vector(&Vector::new_from_f64(1., 1., 1.)), Vector::new_from_f64(2., 1., -1.));
+        assert!(t.does_it_flip_orientation());
+        assert!(!Transform::identity().does_it_flip_orientation());
+    }
+
+    #[test]
+    fn inverse_and_planes() {
+        let t = Transform::rotation(Number::new(1.), Number::new(1.), Number::new(0.), Number::new(2.)).unwrap()
+            .then(&Transform::scaling(Number::new(2.), Number::new(1.), Number::new(-3.)).unwrap())
+            .then(&Transform::translation(&Vector::new_from_f64(1., -2., 0.5)));
+        let inverse = t.get_inverse();
+        assert_eq!(t.then(&inverse), Transform::identity());
+        assert_eq!(inverse.then(&t), Transform::identity());
+        assert_eq!(Transform::new_from_homogeneous_matrix(&t.get_homogeneous_matrix()).unwrap(), t);
+        assert!(Transform::new_from_homogeneous_matrix(&t.get_homogeneous_matrix().transpose()).is_err());
+
+        let plane = Plane::new(Vector::new_from_f64(1., 2., -1.), Point::new_from_f64(1., 1., 3.));
+        let image = t.apply_to_plane(&plane);
+        for p in [Point::new_from_f64(1., 1., 3.), Point::new_from_f64(3., 0., 3.), Point::new_from_f64(0., 0., 0.)].iter() {
+            assert!(plane.does_it_contain_point(p));
+            assert!(image.does_it_contain_point(&t.apply_to_point(p)));
+        }
+
+        // точка с положительной стороны плоскости переходит на положительную сторону образа
+        let above = t.apply_to_point(&Point::new_from_f64(2., 3., 2.));
+        assert!(image.get_ref_normal().dot_product(&(&above - &image.point)).is_it_positive());
+    }
+
+    #[test]
+    fn invalid_matrices() {
+        assert!(Transform::scaling(Number::new(1.), Number::zero(), Number::new(1.)).is_err());
+        assert!(Transform::rotation(Number::zero(), Number::zero(), Number::zero(), Number::zero()).is_err());
+
+        let mut matrix = Transform::identity().get_matrix().clone();
+        assert!(Transform::new(matrix.clone()).is_ok());
+        matrix[3][0] = Number::new(1.);
+        assert!(Transform::new(matrix.clone()).is_err());
+        matrix[3][0] = Number::zero();
+        matrix[2][2] = Number::zero();
+        assert!(Transform::new(matrix).is_err());
+    }
+
+    fn get_angle(t: &Transform) -> f64 {
+        let m = t.get_matrix();
+        let trace = (&(&m[0][0] + &m[1][1]) + &m[2][2]).convert_to_f64();
+        return ((trace - 1.) / 2.).max(-1.).min(1.).acos();
+    }
+
+    #[test]
+    fn tiny_tolerances() {
+        for &value in [0.1, -2.5, 1e-20, 3e15].iter() {
+            let q = approximate_by_rational(value, 1e-310).convert_to_f64();
+            assert!((q - value).abs() <= value.abs().max(1.) * ::std::f64::EPSILON);
+        }
+
+        let axis = Vector::new_from_f64(0., 0., 1.);
+        let t = Transform::rotation_around_axis(&axis, 1., 1e-310).unwrap();
+        assert!((get_angle(&t) - 1.).abs() < 1e-7);
+    }
+
+    #[test]
+    fn approximated_rotations() {
+        let axis = Vector::new_from_f64(1., 2., 2.);
+        for &angle in [0., 0.1, 1., 1.5707963267948966, 2., 3., 3.141592653589793, -2.5, 7.].iter() {
+            for &tolerance in [1e-3, 1e-6, 1e-10].iter() {
+                let t = Transform::rotation_around_axis(&axis, angle, tolerance).unwrap();
+                let mtm = transpose_product(&t);
+                for i in 0..3 {
+                    for j in 0..3 {
+                        assert_eq!(mtm[i][j], if i == j {Number::new(1.)} else {Number::zero()});
+                    }
+                }
+
+                // the axis is fixed
+                assert_eq!(t.apply_to_vector(&axis), axis);
+
+                let mut expected = (angle % (2. * ::std::f64::consts::PI)).abs();
+                if expected > ::std::f64::consts::PI {
+                    expected = 2. * ::std::f64::consts::PI - expected;
+                }
+                assert!((get_angle(&t) - expected).abs() <= tolerance + 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn rotation_direction() {
+        let t = Transform::rotation_around_axis(&Vector::new_from_f64(0., 0., 2.), 0.5, 1e-9).unwrap();
+        let p = t.apply_to_point(&Point::new_from_f64(1., 0., 0.));
+        assert!((p.x.convert_to_f64() - 0.5f64.cos()).abs() < 1e-8);
+        assert!((p.y.convert_to_f64() - 0.5f64.sin()).abs() < 1e-8);
+
+        let t = Transform::rotation_around_axis(&Vector::new_from_f64(0., 0., 1.), -3., 1e-9).unwrap();
+        let p = t.apply_to_point(&Point::new_from_f64(1., 0., 0.));
+        assert!((p.y.convert_to_f64() - (-3f64).sin()).abs() < 1e-8);
+
+        assert!(Transform::rotation_around_axis(&Vector::new_from_f64(0., 0., 0.), 1., 1e-3).is_err());
+        assert!(Transform::rotation_around_axis(&Vector::new_from_f64(0., 0., 1.), 1., 0.).is_err());
+    }
+}
@@ -2,33 +2,39 @@ use primitives::point::Point;
 use primitives::plane::Plane;
 use primitives::vector::Vector;
 use primitives::number::*;
-use primitives::zero_trait::Zero;
-use primitives::signed_trait::Signed;
 use primitives::to_2d_trait::To2D;
 use primitives::segment::Segment;
+use primitives::predicates::orient2d;
+use std::cmp::Ordering;
 // use std::collections::BTreeSet;
 
 /// This structure represents a triangle in 3D space.
 #[derive(Debug, Hash)]
-pub struct Triangle {
-    points : Vec<Point>,
-    normal : Option<Vector>
+pub struct Triangle<T = Number> {
+    points : Vec<Point<T>>,
+    normal : Option<Vector<T>>
 }
 
-impl PartialEq for Triangle {
-    fn eq(&self, other: &Triangle) -> bool {
+impl<T> PartialEq for Triangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    fn eq(&self, other: &Triangle<T>) -> bool {
         (self.points[0] == other.points[0]) & (self.points[1] == other.points[1]) & (self.points[2] == other.points[2])
     }
 }
 
-impl Eq for Triangle {}
+impl<T> Eq for Triangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{}
 
-impl Triangle {
+impl<T> Triangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     /// This method creates `Triangle` from a `Vec` of points and calculates a normal using `points`.
     /// # Arguments
     ///
     /// * `points` - A `Vec<Point>` to create the triangle.
-    pub fn new(points : Vec<Point>) -> Triangle {
+    pub fn new(points : Vec<Point<T>>) -> Triangle<T> {
         let mut t  = Triangle {
             points : points,
             normal : None
@@ -45,7 +51,7 @@ impl Triangle {
     ///
     /// * `points` - A `Vec<Point>' to create the triangle.
     /// * `normal` - A normal vector.
-    pub fn new_with_normal(points : Vec<Point>, normal : Vector) -> Triangle {
+    pub fn new_with_normal(points : Vec<Point<T>>, normal : Vector<T>) -> Triangle<T> {
         let t  = Triangle {
             points : points,
             normal : Some(normal)
@@ -54,17 +60,17 @@ impl Triangle {
     }
 
     /// This method returns the mutable reference to the `Vec<Point>`, containing the triangle points.
-    pub fn get_points_mut_ref(&mut self) -> &mut Vec<Point> {
+    pub fn get_points_mut_ref(&mut self) -> &mut Vec<Point<T>> {
         return &mut self.points;
     }
 
     /// This method returns the reference to the `Vec<Point>`, containing the triangle points.
-    pub fn get_points_ref(&self) -> &Vec<Point> {
+    pub fn get_points_ref(&self) -> &Vec<Point<T>> {
         return &self.points;
     }
 
     /// This method returns the reference to the `Vec<Point>`, containing the triangle points.
-    pub fn get_points(self) -> Vec<Point> {
+    pub fn get_points(self) -> Vec<Point<T>> {
         return self.points;
     }
 
@@ -72,7 +78,7 @@ impl Triangle {
     /// # Arguments
     ///
     /// * `index` - An index of the point. It has to be less than 3.
-    pub fn get(&self, index : usize) -> Point {
+    pub fn get(&self, index : usize) -> Point<T> {
         self.points[index].clone()
     }
 
@@ -80,11 +86,11 @@ impl Triangle {
     /// # Arguments
     ///
     /// * `index` - An index of the point. It has to be less than 3.
-    pub fn get_ref(&self, index : usize) -> &Point {
+    pub fn get_ref(&self, index : usize) -> &Point<T> {
         &self.points[index]
     }
 
-    pub(crate) fn gen_plane(&self) -> Plane {
+    pub(crate) fn gen_plane(&self) -> Plane<T> {
         let p = Plane::new(
             // check it!
             (self.get_ref(0) - self.get_ref(1)).cross_product(&(self.get_ref(1) - self.get_ref(2))),
@@ -95,7 +101,7 @@ impl Triangle {
     }
 
     /// This method returns a copy of the normal `Vector`.
-    pub fn get_normal(&self) -> Vector {
+    pub fn get_normal(&self) -> Vector<T> {
         if self.normal.is_some() {
             return self.normal.clone().unwrap();
         } else {
@@ -103,61 +109,53 @@ impl Triangle {
         }
     }
 
-    pub(crate) fn calculate_normal(&self) -> Vector {
+    pub(crate) fn calculate_normal(&self) -> Vector<T> {
         let v1 = self.get_ref(0) - self.get_ref(1);
         let v2 = self.get_ref(1) - self.get_ref(2);
         return v1.cross_product(&v2);
     }
 
+    /// This method returns an exact square of the triangle area.
+    pub fn get_squared_area(&self) -> T {
+        return self.calculate_normal().length2() / T::new(4.);
+    }
+
+    /// This method returns an approximate area of the triangle.
+    pub fn get_area(&self) -> f64 {
+        return self.get_squared_area().convert_to_f64().sqrt();
+    }
+
     pub(crate) fn reverse(&mut self) {
         self.points.swap(0, 1);
-        self.normal = Some(self.normal.clone().unwrap() * Number::new(-1.));
+        self.normal = Some(self.normal.clone().unwrap() * T::new(-1.));
     }
 
-    pub(crate) fn does_triangle_contain_point(&self, p : &Point, strict : bool) -> bool {
-        // http://blackpawn.com/texts/pointinpoly/
-
-        let v0 = self.get_ref(2) - self.get_ref(0);
-        let v1 = self.get_ref(1) - self.get_ref(0);
-        let v2 = p - self.get_ref(0);
-
-        /*
-        println!("p = {:?}", p);
-        println!("p0 = {:?}", self.get_ref(0));
-        println!("p1 = {:?}", self.get_ref(1));
-        println!("p2 = {:?}", self.get_ref(2));
-        println!("v0 = {:?}", v0);
-        println!("v1 = {:?}", v1);
-        println!("v2 = {:?}", v2);
-        */
-
-        // Compute dot products
-        let dot00 = v0.dot_product(&v0);
-        let dot01 = v0.dot_product(&v1);
-        let dot02 = v0.dot_product(&v2);
-        let dot11 = v1.dot_product(&v1);
-        let dot12 = v1.dot_product(&v2);
-
-        // Compute barycentric coordinates
-        let inv_denom = Number::new(1.) / (&dot00 * &dot11 - &dot01 * &dot01);
-        let u = (&dot11 * &dot02 - &dot01 * &dot12) * &inv_denom;
-        let v = (&dot00 * &dot12 - &dot01 * &dot02) * &inv_denom;
-
-        //println!("u = {0}", u);
-        //println!("v = {0}", v);
-
-        // Check if point is in triangle
+    pub(crate) fn does_triangle_contain_point(&self, p : &Point<T>, strict : bool) -> bool {
+        // точки проецируются на координатную плоскость, в которой проекция треугольника наибольшая,
+        // и p сравнивается со сторонами предикатом orient2d
+        let n = self.calculate_normal();
+        let (nx, ny, nz) = (n.x.abs(), n.y.abs(), n.z.abs());
+        let project = |q: &Point<T>| -> Point<T> {
+            match 1 {
+                _ if nx >= ny && nx >= nz => return Point { x: q.y.clone(), y: q.z.clone(), z: T::new(0.) },
+                _ if ny >= nz => return Point { x: q.z.clone(), y: q.x.clone(), z: T::new(0.) },
+                _ => return Point { x: q.x.clone(), y: q.y.clone(), z: T::new(0.) }
+            }
+        };
+
+        let (a, b, c, p) = (project(self.get_ref(0)), project(self.get_ref(1)), project(self.get_ref(2)), project(p));
+        let o = orient2d(&a, &b, &c);
+        if o == Ordering::Equal {
+            return false;
+        }
+
+        let inside = |side: Ordering| side == o || side == Ordering::Equal;
+        let opposite = orient2d(&b, &c, &p);
         if strict {
-            return (u.is_it_positive() || u.is_it_zero()) &&
-                (v.is_it_positive() || v.is_it_zero()) &&
-                (u + v < Number::new(1.))
+            return inside(orient2d(&a, &b, &p)) && inside(orient2d(&c, &a, &p)) && opposite == o;
         } else {
-            return (u.is_it_positive() || u.is_it_zero()) &&
-                (v.is_it_positive() || v.is_it_zero()) &&
-                (u + v <= Number::new(1.))
+            return inside(orient2d(&a, &b, &p)) && inside(orient2d(&c, &a, &p)) && inside(opposite);
         }
-
-
     }
 
     /// This method checks the triangle and returns:
@@ -178,16 +176,18 @@ impl Triangle {
     }
 
 
-    pub(crate) fn get_sides(&self) -> Vec<Segment> {
+    pub(crate) fn get_sides(&self) -> Vec<Segment<T>> {
         let s1 = Segment::new(self.get(0), self.get(1));
         let s2 = Segment::new(self.get(1), self.get(2));
         let s3 = Segment::new(self.get(2), self.get(0));
-        let tr1_segments : Vec<Segment> = vec![s1, s2, s3];
+        let tr1_segments : Vec<Segment<T>> = vec![s1, s2, s3];
         return tr1_segments;
     }
 }
 
-impl To2D for Triangle {
+impl<T> To2D for Triangle<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
     fn swap_yz(&mut self) {
         self.normal.iter_mut().next().unwrap().swap_yz();
         for p in self.points.iter_mut() {
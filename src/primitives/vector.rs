@@ -13,33 +13,25 @@ use std::fmt;
 
 
 /// This structure repsresents a 3D vector.
-#[derive(Hash)]
+/// Coordinates are `Number` by default, but any type implementing `NumberTrait` can be used.
+#[derive(Debug, Hash)]
 #[derive(Clone)]
-pub struct Vector
+pub struct Vector<T = Number>
 {
-    pub x: Number,
-    pub y: Number,
-    pub z: Number
+    pub x: T,
+    pub y: T,
+    pub z: T
 }
 
-impl fmt::Debug for Vector {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Point [{:?}, {:?}, {:?}]", self.x.clone().convert_to_f32(), self.y.clone().convert_to_f32(), self.z.clone().convert_to_f32())
-    }
-}
-
-lazy_static! {
-    static ref  ZERO : Vector = Vector {x: Number::new(0.), y: Number::new(0.), z: Number::new(0.)};
-}
-
-
-impl Vector {
+impl<T> Vector<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
 
     /// This method calculates a dot product of `self` and `other` and returns `Number`.
     /// # Arguments
     ///
     /// * `other` - The `Vector` to multiply on.
-    pub fn dot_product(&self, other: &Vector) -> Number {
+    pub fn dot_product(&self, other: &Vector<T>) -> T {
         &self.x*&other.x + &self.y*&other.y + &self.z*&other.z
     }
 
@@ -47,7 +39,7 @@ impl Vector {
     /// # Arguments
     ///
     /// * `other` - The `Vector` to multiply on.
-    pub fn cross_product(&self, other: &Vector) -> Vector {
+    pub fn cross_product(&self, other: &Vector<T>) -> Vector<T> {
         //a2*b3  -   a3*b2,     a3*b1   -   a1*b3,     a1*b2   -   a2*b1
         Vector {x: &self.y*&other.z - &self.z*&other.y,
                 y: &self.z*&other.x - &self.x*&other.z,
@@ -58,25 +50,25 @@ impl Vector {
     /// # Arguments
     ///
     /// * `other` - The `Vector` to multiply on.
-    pub fn mixed_product(&self, a: &Vector, b: &Vector) -> Number {
+    pub fn mixed_product(&self, a: &Vector<T>, b: &Vector<T>) -> T {
         self.dot_product(&(a.cross_product(b)))
     }
 
     // This method returns `true` if  it's a null.
     pub fn is_zero(&self) -> bool {
-        *ZERO == *self
+        self.x.is_it_zero() && self.y.is_it_zero() && self.z.is_it_zero()
     }
 
     /// This method checks if `self` collinear to `other`.
     /// # Arguments
     ///
     /// * `other` - The `Vector` to compare with.
-    pub fn is_collinear_to(&self, other : &Vector) -> bool {
+    pub fn is_collinear_to(&self, other : &Vector<T>) -> bool {
         self.cross_product(other).is_zero()
     }
 
     /// This method creates a `Point` from the `Vector`.
-    pub fn get_point(&self) -> point::Point {
+    pub fn get_point(&self) -> point::Point<T> {
         point::Point {
             x: self.x.clone(),
             y: self.y.clone(),
@@ -87,36 +79,35 @@ impl Vector {
     /// This method creates `Vector` from `x`, `y` and `z` coordinates.
     /// # Arguments
     ///
-    /// * `x` - A `Number` representing the x coordinate.
-    /// * `y` - A `Number` representing the y coordinate.
-    /// * `z` - A `Number` representing the z coordinate.
-    pub fn new(x : Number, y : Number, z : Number) -> Vector {
+    /// * `x` - A number representing the x coordinate.
+    /// * `y` - A number representing the y coordinate.
+    /// * `z` - A number representing the z coordinate.
+    pub fn new(x : T, y : T, z : T) -> Vector<T> {
         Vector {x:x, y:y, z:z}
     }
 
-    /// This method creates `Vector` from `x`, `y` and `z` coordinates.
+    /// This method creates `Vector` with coordinates of type `T` from `x`, `y` and `z` coordinates.
     /// # Arguments
     ///
     /// * `x` - A `f64` representing the x coordinate.
     /// * `y` - A `f64` representing the y coordinate.
     /// * `z` - A `f64` representing the z coordinate.
-    pub fn new_from_f64(x : f64, y : f64, z : f64) -> Vector {
-        Vector {x: Number::new(x), y: Number::new(y), z: Number::new(z)}
+    pub fn from_f64(x : f64, y : f64, z : f64) -> Vector<T> {
+        Vector {x: T::new(x), y: T::new(y), z: T::new(z)}
     }
 
     /// This method returns a square length of the `Vector`.
-    pub fn length2(&self) -> Number {
+    pub fn length2(&self) -> T {
         &self.x*&self.x + &self.y*&self.y + &self.z*&self.z
     }
 
-    pub(crate) fn get_signed_cos2(&self, other: &Vector) -> Number {
-        let minus_one = Number::new(-1.);
-        let zero = Number::new(0.);
+    pub(crate) fn get_signed_cos2(&self, other: &Vector<T>) -> T {
+        let minus_one = T::new(-1.);
 
         let dp = self.dot_product(other);
 
         let mut cos2 = dp.clone()*dp.clone()/self.length2()/other.length2();
-        if dp < zero {
+        if dp.is_it_negative() {
             cos2 = cos2 * &minus_one;
         }
         return cos2;
@@ -124,7 +115,19 @@ impl Vector {
 
 }
 
-impl To2D for Vector {
+impl Vector {
+    /// This method creates `Vector` from `x`, `y` and `z` coordinates.
+    /// # Arguments
+    ///
+    /// * `x` - A `f64` representing the x coordinate.
+    /// * `y` - A `f64` representing the y coordinate.
+    /// * `z` - A `f64` representing the z coordinate.
+    pub fn new_from_f64(x : f64, y : f64, z : f64) -> Vector {
+        Vector {x: Number::new(x), y: Number::new(y), z: Number::new(z)}
+    }
+}
+
+impl<T> To2D for Vector<T> {
     fn swap_yz(& mut self) {
         swap(&mut self.y, &mut self.z);
     }
@@ -138,56 +141,66 @@ impl To2D for Vector {
     }
 }
 
-impl PartialEq for Vector {
-    fn eq(&self, other: &Vector) -> bool {
+impl<T: PartialEq> PartialEq for Vector<T> {
+    fn eq(&self, other: &Vector<T>) -> bool {
         (self.x == other.x) & (self.y == other.y) & (self.z == other.z)
     }
 }
 
-impl Eq for Vector {}
+impl<T: Eq> Eq for Vector<T> {}
 
-impl Add<Vector> for Vector {
-    type Output = Vector;
+impl<T> Add<Vector<T>> for Vector<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    type Output = Vector<T>;
 
-    fn add(self, other: Vector) -> Vector {
+    fn add(self, other: Vector<T>) -> Vector<T> {
         Vector { x: self.x + &other.x, y: self.y + &other.y, z: self.z + &other.z }
     }
 }
 
-impl<'a,'b> Add<&'b Vector> for &'a Vector {
-    type Output = Vector;
+impl<'a,'b,T> Add<&'b Vector<T>> for &'a Vector<T>
+    where T: NumberTrait<T>, for<'c> &'c T: NumberRef<T>
+{
+    type Output = Vector<T>;
 
-    fn add(self, other: &Vector) -> Vector {
+    fn add(self, other: &Vector<T>) -> Vector<T> {
         Vector { x: &self.x + &other.x, y: &self.y + &other.y, z: &self.z + &other.z }
     }
 }
 
-impl Sub<Vector> for Vector {
-    type Output = Vector;
+impl<T> Sub<Vector<T>> for Vector<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    type Output = Vector<T>;
 
-    fn sub(self, other: Vector) -> Vector {
+    fn sub(self, other: Vector<T>) -> Vector<T> {
         Vector { x: self.x - &other.x, y: self.y - &other.y, z: self.z - &other.z }
     }
 }
 
-impl Mul<Number> for Vector {
-    type Output = Vector;
+impl<T> Mul<T> for Vector<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    type Output = Vector<T>;
 
-    fn mul(self, other: Number) -> Vector {
+    fn mul(self, other: T) -> Vector<T> {
         Vector { x: self.x*&other, y: self.y*&other, z: self.z*&other }
     }
 }
 
-impl<'a> Mul<Number> for &'a Vector {
-    type Output = Vector;
+impl<'a,T> Mul<T> for &'a Vector<T>
+    where T: NumberTrait<T>, for<'b> &'b T: NumberRef<T>
+{
+    type Output = Vector<T>;
 
-    fn mul(self, other: Number) -> Vector {
+    fn mul(self, other: T) -> Vector<T> {
         Vector { x: &self.x*&other, y: &self.y*&other, z: &self.z*&other }
     }
 }
 
 
-impl fmt::Display for Vector {
+impl<T: fmt::Display> fmt::Display for Vector<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "({}, {}, {})", self.x, self.y, self.z)
     }
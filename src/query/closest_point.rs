@@ -0,0 +1,269 @@
+use primitives::*;
+use query::{PointPosition, PointClassifier};
+
+/// This enum describes a feature of a triangle containing the closest point.
+/// Vertices are specified by their indexes in the triangle (0, 1 or 2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    Vertex(usize),
+    Edge(usize, usize),
+    Face
+}
+
+/// This structure represents the closest point of a mesh to a query point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClosestPoint {
+    /// The index of the closest triangle in the mesh.
+    pub triangle_index: usize,
+    /// The feature of the triangle containing the closest point.
+    pub feature: Feature,
+    /// The exact closest point.
+    pub point: Point,
+    /// The exact squared distance between the query point and the closest point.
+    pub squared_distance: Number
+}
+
+/// This structure performs exact closest point and distance queries to a mesh.
+/// The tree of bounding boxes is built once, so it's preferable to use it for a lot of queries.
+pub struct DistanceQuery<'a> {
+    mesh: &'a Mesh,
+    classifier: PointClassifier<'a>
+}
+
+impl<'a> DistanceQuery<'a> {
+    /// This method creates `DistanceQuery` and builds the tree of bounding boxes for the `mesh`.
+    /// # Arguments
+    ///
+    /// * `mesh` - A mesh to calculate distances to.
+    pub fn new(mesh: &'a Mesh) -> DistanceQuery<'a> {
+        return DistanceQuery {
+            mesh,
+            classifier: PointClassifier::new(mesh)
+        };
+    }
+
+    /// This method returns the closest point of the mesh to the point `p`.
+    /// If several triangles contain the closest point, the one with the least index is returned.
+    /// It returns `None` if the mesh is empty.
+    /// # Arguments
+    ///
+    /// * `p` - A query point.
+    pub fn closest_point(&self, p: &Point) -> Option<ClosestPoint> {
+        let tree = match self.classifier.get_tree() {
+            Some(tree) => tree,
+            None => return None
+        };
+
+        let (it, _) = tree.find_nearest_triangle(p, |it| {
+            let (point, _) = get_closest_point_on_triangle(&self.mesh.get_triangle(it), p);
+            return (&point - p).length2();
+        });
+
+        let (point, feature) = get_closest_point_on_triangle(&self.mesh.get_triangle(it), p);
+        let squared_distance = (&point - p).length2();
+        return Some(ClosestPoint {
+            triangle_index: it,
+            feature,
+            point,
+            squared_distance
+        });
+    }
+
+    /// This method returns the exact squared distance between the point `p` and the mesh.
+    /// It returns `None` if the mesh is empty.
+    /// # Arguments
+    ///
+    /// * `p` - A query point.
+    pub fn squared_distance(&self, p: &Point) -> Option<Number> {
+        return self.closest_point(p).map(|cp| cp.squared_distance);
+    }
+
+    /// This method returns the exact signed squared distance between the point `p` and the mesh.
+    /// It's negative if the point is inside the mesh. The mesh has to be closed.
+    /// It returns `None` if the mesh is empty.
+    /// # Arguments
+    ///
+    /// * `p` - A query point.
+    pub fn signed_squared_distance(&self, p: &Point) -> Option<Number> {
+        let squared_distance = match self.squared_distance(p) {
+            Some(squared_distance) => squared_distance,
+            None => return None
+        };
+
+        match self.classifier.classify(p) {
+            PointPosition::Inside => return Some(-squared_distance),
+            _ => return Some(squared_distance)
+        }
+    }
+
+    /// This method returns an approximate distance between the point `p` and the mesh.
+    /// # Arguments
+    ///
+    /// * `p` - A query point.
+    pub fn distance(&self, p: &Point) -> Option<f64> {
+        return self.squared_distance(p).map(|d| d.convert_to_f64().sqrt());
+    }
+
+    /// This method returns an approximate signed distance between the point `p` and the mesh.
+    /// It's negative if the point is inside the mesh. The mesh has to be closed.
+    /// # Arguments
+    ///
+    /// * `p` - A query point.
+    pub fn signed_distance(&self, p: &Point) -> Option<f64> {
+        return self.signed_squared_distance(p).map(|d| {
+            if d.is_it_negative() {
+                -(-d).convert_to_f64().sqrt()
+            } else {
+                d.convert_to_f64().sqrt()
+            }
+        });
+    }
+}
+
+/// This function returns the closest point of the `mesh` to the point `p`.
+/// Use `DistanceQuery` to perform a lot of queries.
+/// # Arguments
+///
+/// * `mesh` - A mesh.
+/// * `p` - A query point.
+pub fn find_closest_point(mesh: &Mesh, p: &Point) -> Option<ClosestPoint> {
+    return DistanceQuery::new(mesh).closest_point(p);
+}
+
+// "Real-Time Collision Detection", C. Ericson, 5.1.5
+pub(crate) fn get_closest_point_on_triangle(tr: &Triangle, p: &Point) -> (Point, Feature) {
+    let a = tr.get_ref(0);
+    let b = tr.get_ref(1);
+    let c = tr.get_ref(2);
+
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = p - a;
+    let d1 = ab.dot_product(&ap);
+    let d2 = ac.dot_product(&ap);
+    if !d1.is_it_positive() && !d2.is_it_positive() {
+        return (a.clone(), Feature::Vertex(0));
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot_product(&bp);
+    let d4 = ac.dot_product(&bp);
+    if !d3.is_it_negative() && d4 <= d3 {
+        return (b.clone(), Feature::Vertex(1));
+    }
+
+    let vc = &d1 * &d4 - &d3 * &d2;
+    if !vc.is_it_positive() && !d1.is_it_negative() && !d3.is_it_positive() {
+        let v = &d1 / &(&d1 - &d3);
+        return get_point_on_edge(a, &ab, v, 0, 1);
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot_product(&cp);
+    let d6 = ac.dot_product(&cp);
+    if !d6.is_it_negative() && d5 <= d6 {
+        return (c.clone(), Feature::Vertex(2));
+    }
+
+    let vb = &d5 * &d2 - &d1 * &d6;
+    if !vb.is_it_positive() && !d2.is_it_negative() && !d6.is_it_positive() {
+        let w = &d2 / &(&d2 - &d6);
+        return get_point_on_edge(a, &ac, w, 0, 2);
+    }
+
+    let va = &d3 * &d6 - &d5 * &d4;
+    let d43 = &d4 - &d3;
+    let d56 = &d5 - &d6;
+    if !va.is_it_positive() && !d43.is_it_negative() && !d56.is_it_negative() {
+        let w = &d43 / &(&d43 + &d56);
+        return get_point_on_edge(b, &(c - b), w, 1, 2);
+    }
+
+    let denominator = &(&va + &vb) + &vc;
+    let v = vb / &denominator;
+    let w = vc / denominator;
+    let point = &(a + &(&ab * v)) + &(&ac * w);
+    return (point, Feature::Face);
+}
+
+fn get_point_on_edge(org: &Point, dir: &Vector, t: Number, i: usize, j: usize) -> (Point, Feature) {
+    if t.is_it_zero() {
+        return (org.clone(), Feature::Vertex(i));
+    }
+    if t == Number::new(1.) {
+        return (org + dir, Feature::Vertex(j));
+    }
+    return (org + &(dir * t), Feature::Edge(i, j));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use query::*;
+
+    #[test]
+    fn closest_point_on_triangle_features() {
+        let tr = Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(2., 0., 0.),
+            Point::new_from_f64(0., 2., 0.)
+        ]);
+
+        let (p, f) = closest_point::get_closest_point_on_triangle(&tr, &Point::new_from_f64(-1., -1., 3.));
+        assert_eq!((p, f), (Point::new_from_f64(0., 0., 0.), Feature::Vertex(0)));
+
+        let (p, f) = closest_point::get_closest_point_on_triangle(&tr, &Point::new_from_f64(1., -1., 0.));
+        assert_eq!((p, f), (Point::new_from_f64(1., 0., 0.), Feature::Edge(0, 1)));
+
+        let (p, f) = closest_point::get_closest_point_on_triangle(&tr, &Point::new_from_f64(2., 2., 1.));
+        assert_eq!((p, f), (Point::new_from_f64(1., 1., 0.), Feature::Edge(1, 2)));
+
+        let (p, f) = closest_point::get_closest_point_on_triangle(&tr, &Point::new_from_f64(0.5, 0.25, -1.));
+        assert_eq!((p, f), (Point::new_from_f64(0.5, 0.25, 0.), Feature::Face));
+
+        let (p, f) = closest_point::get_closest_point_on_triangle(&tr, &Point::new_from_f64(0., 3., 0.));
+        assert_eq!((p, f), (Point::new_from_f64(0., 2., 0.), Feature::Vertex(2)));
+    }
+
+    #[test]
+    fn distances_to_cube() {
+        let mesh = cube_mesh();
+        let query = DistanceQuery::new(&mesh);
+
+        let cp = query.closest_point(&Point::new_from_f64(0.25, 0.5, 3.)).unwrap();
+        assert_eq!(cp.point, Point::new_from_f64(0.25, 0.5, 1.));
+        assert_eq!(cp.squared_distance, Number::new(4.));
+
+        let p = Point::new_from_f64(2., 2., 2.);
+        let cp = query.closest_point(&p).unwrap();
+        assert_eq!(cp.point, Point::new_from_f64(1., 1., 1.));
+        assert_eq!(cp.squared_distance, Number::new(3.));
+        match cp.feature {
+            Feature::Vertex(_) => (),
+            _ => panic!("The closest feature has to be a vertex!")
+        }
+        // вершину (1, 1, 1) содержат несколько треугольников, возвращается треугольник с наименьшим индексом
+        let least_index = mesh.get_it_iterator().into_iter()
+            .filter(|&it| mesh.get_triangle(it).get_points_ref().contains(&cp.point))
+            .min().unwrap();
+        assert_eq!(cp.triangle_index, least_index);
+
+        let p = Point::new_from_f64(0.5, 0.75, 0.5);
+        assert_eq!(query.squared_distance(&p), Some(Number::new(1.) / Number::new(16.)));
+        assert_eq!(query.signed_squared_distance(&p), Some(Number::new(-1.) / Number::new(16.)));
+        assert_eq!(query.signed_distance(&p), Some(-0.25));
+
+        let p = Point::new_from_f64(-1., 0.5, 0.5);
+        assert_eq!(query.signed_squared_distance(&p), Some(Number::new(1.)));
+        assert_eq!(query.distance(&p), Some(1.));
+    }
+
+    #[test]
+    fn distance_to_empty_mesh() {
+        let mesh = Mesh::new();
+        assert!(find_closest_point(&mesh, &Point::new_from_f64(0., 0., 0.)).is_none());
+    }
+}
@@ -0,0 +1,305 @@
+use primitives::*;
+use matrix::{Matrix, Row};
+
+use std::collections::BTreeSet;
+
+/// This structure contains statistics of distances from points to a fitted plane or line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Residuals {
+    /// The exact sum of squared distances.
+    pub sum_of_squares: Number,
+    /// The exact maximum squared distance.
+    pub max_squared_distance: Number,
+    /// An approximate mean distance.
+    pub mean_distance: f64,
+    /// An approximate root mean square distance.
+    pub rms_distance: f64
+}
+
+impl Residuals {
+    // статистика по точным квадратам расстояний
+    fn new(squared_distances: Vec<Number>) -> Residuals {
+        let n = squared_distances.len() as f64;
+        let sum_of_squares = squared_distances.iter().fold(Number::zero(), |sum, d2| sum + d2);
+        let mean_distance = squared_distances.iter().map(|d2| d2.clone().convert_to_f64().sqrt()).sum::<f64>() / n;
+        return Residuals {
+            rms_distance: (sum_of_squares.clone().convert_to_f64() / n).sqrt(),
+            sum_of_squares: sum_of_squares,
+            max_squared_distance: squared_distances.into_iter().max().unwrap(),
+            mean_distance: mean_distance
+        };
+    }
+
+    /// This method returns an approximate maximum distance.
+    pub fn get_max_distance(&self) -> f64 {
+        return self.max_squared_distance.clone().convert_to_f64().sqrt();
+    }
+}
+
+/// This structure represents a plane fitted to points with residuals of the fitting.
+#[derive(Clone, Debug)]
+pub struct PlaneFit {
+    /// The fitted plane passing through the centroid of points.
+    pub plane: Plane,
+    /// Distances from points to the plane.
+    pub residuals: Residuals
+}
+
+/// This structure represents a line fitted to points with residuals of the fitting.
+#[derive(Clone, Debug)]
+pub struct LineFit {
+    /// The fitted line, `org` is the centroid of points.
+    pub line: Line,
+    /// Distances from points to the line.
+    pub residuals: Residuals
+}
+
+/// This function fits a plane to points by orthogonal least squares, i.e. the sum of squared distances
+/// from points to the plane is minimized. The plane passes through the centroid of points, its normal is
+/// an eigenvector of the smallest eigenvalue of the scatter matrix. If points are coplanar, the normal is found exactly,
+/// otherwise it's calculated with f64 precision. Squared residuals are exact for the returned plane.
+/// It returns an error if there are less than 3 points or all points are collinear.
+/// # Arguments
+///
+/// * `points` - Points to fit.
+pub fn fit_plane(points: &Vec<Point>) -> Result<PlaneFit, &'static str> {
+    if points.len() < 3 {
+        return Err("There are less than 3 points!");
+    }
+    let (centroid, s) = get_centroid_and_scatter(points);
+
+    let normal = match get_scatter_matrix(&s).get_rank() {
+        0 | 1 => return Err("Points are collinear!"),
+        // точки в одной плоскости: нормаль точно находится как ядро матрицы разброса
+        2 => {
+            let n = get_scatter_matrix(&s).get_nullspace().pop().unwrap();
+            Vector::new(n.get(&0), n.get(&1), n.get(&2))
+        },
+        _ => get_eigenvector(&s, false)
+    };
+
+    let length2 = normal.length2();
+    let squared_distances = points.iter()
+        .map(|p| {
+            let d = normal.dot_product(&(p - &centroid));
+            &d * &d / &length2
+        })
+        .collect();
+    return Ok(PlaneFit { plane: Plane::new(normal, centroid), residuals: Residuals::new(squared_distances) });
+}
+
+/// This function fits a line to points by orthogonal least squares, i.e. the sum of squared distances
+/// from points to the line is minimized. The line passes through the centroid of points, its direction is
+/// an eigenvector of the largest eigenvalue of the scatter matrix. If points are collinear, the direction is found exactly,
+/// otherwise it's calculated with f64 precision. Squared residuals are exact for the returned line.
+/// It returns an error if there are less than 2 points or all points coincide.
+/// # Arguments
+///
+/// * `points` - Points to fit.
+pub fn fit_line(points: &Vec<Point>) -> Result<LineFit, &'static str> {
+    if points.len() < 2 {
+        return Err("There are less than 2 points!");
+    }
+    let (centroid, s) = get_centroid_and_scatter(points);
+
+    let direction = match get_scatter_matrix(&s).get_rank() {
+        0 => return Err("Points coincide!"),
+        // точки на одной прямой: все столбцы матрицы разброса ей параллельны
+        1 => {
+            let k = (0..3).max_by(|&a, &b| s[a][a].cmp(&s[b][b])).unwrap();
+            Vector::new(s[0][k].clone(), s[1][k].clone(), s[2][k].clone())
+        },
+        _ => get_eigenvector(&s, true)
+    };
+
+    let length2 = direction.length2();
+    let squared_distances = points.iter()
+        .map(|p| direction.cross_product(&(p - &centroid)).length2() / &length2)
+        .collect();
+    let dest = &centroid + &direction;
+    return Ok(LineFit { line: Line::new(centroid, dest), residuals: Residuals::new(squared_distances) });
+}
+
+/// This function returns the flatness of triangles, i.e. the maximum distance from their vertices
+/// to the plane fitted to the vertices by `fit_plane`.
+/// It returns an error if there are less than 3 different vertices or all of them are collinear.
+/// # Arguments
+///
+/// * `triangles` - Triangles, e.g. of a scanned face.
+pub fn flatness(triangles: &Vec<Triangle>) -> Result<f64, &'static str> {
+    let vertices: BTreeSet<Point> = triangles.iter().flat_map(|t| t.get_points_ref().iter().cloned()).collect();
+    let fit = fit_plane(&vertices.into_iter().collect())?;
+    return Ok(fit.residuals.get_max_distance());
+}
+
+// центр масс точек и матрица сумм произведений отклонений от него
+fn get_centroid_and_scatter(points: &Vec<Point>) -> (Point, Vec<Vec<Number>>) {
+    let n = Number::new(points.len() as f64);
+    let mut sum = Vector::new(Number::zero(), Number::zero(), Number::zero());
+    for p in points.iter() {
+        sum = &sum + &p.get_vector();
+    }
+    let centroid = Point::new(&sum.x / &n, &sum.y / &n, &sum.z / &n);
+
+    let mut s = vec![vec![Number::zero(); 3]; 3];
+    for p in points.iter() {
+        let d: Vector = p - &centroid;
+        let c = [&d.x, &d.y, &d.z];
+        for i in 0..3 {
+            for j in 0..3 {
+                s[i][j] = &s[i][j] + &(c[i] * c[j]);
+            }
+        }
+    }
+    return (centroid, s);
+}
+
+fn get_scatter_matrix(s: &Vec<Vec<Number>>) -> Matrix<Number> {
+    return Matrix::new_from_vector(s.iter().map(|row| Row::new_from_vector(row.clone())).collect());
+}
+
+// собственный вектор наибольшего или наименьшего собственного значения матрицы разброса,
+// найденный методом вращений Якоби в f64
+fn get_eigenvector(s: &Vec<Vec<Number>>, largest: bool) -> Vector {
+    let mut a = [[0f64; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            a[i][j] = s[i][j].clone().convert_to_f64();
+        }
+    }
+    let mut v = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+
+    for _ in 0..50 {
+        let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        let diag = a[0][0] * a[0][0] + a[1][1] * a[1][1] + a[2][2] * a[2][2];
+        if off <= 1e-30 * diag {
+            break;
+        }
+
+        for &(p, q) in [(0, 1), (0, 2), (1, 2)].iter() {
+            if a[p][q] == 0. {
+                continue;
+            }
+            // поворот в плоскости (p, q), обнуляющий a[p][q]
+            let theta = (a[q][q] - a[p][p]) / (2. * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+            let c = 1. / (t * t + 1.).sqrt();
+            let sn = t * c;
+
+            for k in 0..3 {
+                let (akp, akq) = (a[k][p], a[k][q]);
+                a[k][p] = c * akp - sn * akq;
+                a[k][q] = sn * akp + c * akq;
+            }
+            for k in 0..3 {
+                let (apk, aqk) = (a[p][k], a[q][k]);
+                a[p][k] = c * apk - sn * aqk;
+                a[q][k] = sn * apk + c * aqk;
+            }
+            for k in 0..3 {
+                let (vkp, vkq) = (v[k][p], v[k][q]);
+                v[k][p] = c * vkp - sn * vkq;
+                v[k][q] = sn * vkp + c * vkq;
+            }
+        }
+    }
+
+    let k = (0..3)
+        .max_by(|&i, &j| {
+            let ord = a[i][i].partial_cmp(&a[j][j]).unwrap();
+            if largest {ord} else {ord.reverse()}
+        })
+        .unwrap();
+    return Vector::new_from_f64(v[0][k], v[1][k], v[2][k]);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use query::*;
+
+    fn p(x: f64, y: f64, z: f64) -> Point {
+        return Point::new_from_f64(x, y, z);
+    }
+
+    #[test]
+    fn exact_planes() {
+        // z = 2x - y + 3
+        let points = vec![p(0., 0., 3.), p(1., 0., 5.), p(0., 1., 2.), p(2., 3., 4.), p(-1., 5., -4.)];
+        let fit = fit_plane(&points).unwrap();
+        assert!(fit.residuals.sum_of_squares.is_it_zero());
+        assert_eq!(fit.residuals.get_max_distance(), 0.);
+        assert!(points.iter().all(|q| fit.plane.does_it_contain_point(q)));
+        assert!(fit.plane.get_ref_normal().is_collinear_to(&Vector::new_from_f64(2., -1., -1.)));
+
+        // вертикальная плоскость x = 1
+        let points = vec![p(1., 0., 0.), p(1., 2., 0.), p(1., 0., 3.), p(1., 5., 7.)];
+        let fit = fit_plane(&points).unwrap();
+        assert!(fit.residuals.sum_of_squares.is_it_zero());
+        assert!(fit.plane.get_ref_normal().is_collinear_to(&Vector::new_from_f64(1., 0., 0.)));
+    }
+
+    #[test]
+    fn residuals_and_flatness() {
+        let points = vec![p(0., 0., 0.5), p(2., 0., -0.5), p(0., 2., -0.5), p(2., 2., 0.5)];
+        let fit = fit_plane(&points).unwrap();
+        assert!(fit.plane.get_ref_normal().is_collinear_to(&Vector::new_from_f64(0., 0., 1.)));
+        assert_eq!(fit.plane.point, p(1., 1., 0.));
+        assert_eq!(fit.residuals.sum_of_squares, Number::new(1.));
+        assert_eq!(fit.residuals.max_squared_distance, Number::new(0.25));
+        assert_eq!(fit.residuals.rms_distance, 0.5);
+        assert_eq!(fit.residuals.mean_distance, 0.5);
+
+        let triangles = vec![
+            Triangle::new(vec![points[0].clone(), points[1].clone(), points[3].clone()]),
+            Triangle::new(vec![points[0].clone(), points[3].clone(), points[2].clone()])
+        ];
+        assert_eq!(flatness(&triangles).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn lines() {
+        let points = vec![p(0., 0., 3.), p(1., 2., 2.), p(-2., -4., 5.), p(4., 8., -1.)];
+        let fit = fit_line(&points).unwrap();
+        assert!(fit.residuals.sum_of_squares.is_it_zero());
+        assert!(points.iter().all(|q| fit.line.check_accessory(q)));
+
+        let points = vec![p(0., 0., 0.), p(1., 1., 0.), p(2., 0., 0.), p(3., 1., 0.)];
+        let fit = fit_line(&points).unwrap();
+        assert_eq!(fit.line.org, p(1.5, 0.5, 0.));
+        // матрица разброса ((5, 1), (1, 1)): направление (1, sqrt(5) - 2), сумма квадратов расстояний 3 - sqrt(5)
+        let dir = fit.line.get_dir_vector();
+        let slope = dir.y.clone().convert_to_f64() / dir.x.clone().convert_to_f64();
+        assert!((slope - (5f64.sqrt() - 2.)).abs() < 1e-12);
+        assert!(dir.z.is_it_zero());
+        assert!((fit.residuals.sum_of_squares.clone().convert_to_f64() - (3. - 5f64.sqrt())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn orthogonal_fit() {
+        // точки отстоят от плоскости z = x на одинаковые расстояния вдоль нормали (1, 0, -1)
+        let mut points = Vec::new();
+        for &(x, y) in [(0., 0.), (2., 0.), (0., 2.), (2., 2.)].iter() {
+            points.push(p(x + 0.5, y, x - 0.5));
+            points.push(p(x - 0.5, y, x + 0.5));
+        }
+        let fit = fit_plane(&points).unwrap();
+        let n = fit.plane.get_ref_normal();
+        let (nx, nz) = (n.x.clone().convert_to_f64(), n.z.clone().convert_to_f64());
+        assert!((nx + nz).abs() < 1e-12 * nx.abs());
+        assert!(n.y.clone().convert_to_f64().abs() < 1e-12 * nx.abs());
+        assert!((fit.residuals.get_max_distance() - 0.5f64.sqrt()).abs() < 1e-9);
+        assert!((flatness(&vec![Triangle::new(points[0..3].to_vec()), Triangle::new(points[3..6].to_vec())]).unwrap()
+            - fit_plane(&points[0..6].to_vec()).unwrap().residuals.get_max_distance()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn wrong_points() {
+        assert!(fit_plane(&vec![p(0., 0., 0.), p(1., 0., 0.)]).is_err());
+        assert!(fit_plane(&vec![p(0., 0., 0.), p(1., 1., 1.), p(2., 2., 2.), p(3., 3., 3.)]).is_err());
+        assert!(fit_line(&vec![p(1., 1., 1.)]).is_err());
+        assert!(fit_line(&vec![p(1., 1., 1.), p(1., 1., 1.)]).is_err());
+        assert!(flatness(&vec![]).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use primitives::*;
+use query::DistanceQuery;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Result, Write};
+
+/// This structure represents a pair of points realizing a Hausdorff distance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HausdorffDistance {
+    /// The exact squared Hausdorff distance.
+    pub squared_distance: Number,
+    /// The sample point, which is the most distant from the other mesh.
+    pub from_point: Point,
+    /// The closest point of the other mesh to `from_point`.
+    pub to_point: Point
+}
+
+impl HausdorffDistance {
+    /// This method returns an approximate Hausdorff distance.
+    pub fn get_distance(&self) -> f64 {
+        return self.squared_distance.clone().convert_to_f64().sqrt();
+    }
+}
+
+/// This function returns sample points of the `mesh`. Every edge of each triangle is divided into
+/// `subdivisions` equal parts and the nodes of the resulting triangular grid are taken,
+/// so the vertices are always sampled. Coincident samples are merged.
+/// # Arguments
+///
+/// * `mesh` - A mesh to sample.
+/// * `subdivisions` - A number of parts per edge. It has to be positive, otherwise an error is returned.
+pub fn sample_mesh(mesh: &Mesh, subdivisions: usize) -> ::std::result::Result<Vec<Point>, &'static str> {
+    if subdivisions == 0 {
+        return Err("The number of subdivisions has to be positive!");
+    }
+
+    let n = Number::new(subdivisions as f64);
+    let mut samples: BTreeSet<Point> = BTreeSet::new();
+
+    for it in mesh.get_it_iterator() {
+        let tr = mesh.get_triangle(it);
+        let a = tr.get_ref(0);
+        let ab = tr.get_ref(1) - a;
+        let ac = tr.get_ref(2) - a;
+
+        for i in 0..(subdivisions + 1) {
+            for j in 0..(subdivisions + 1 - i) {
+                let u = Number::new(i as f64) / &n;
+                let v = Number::new(j as f64) / &n;
+                samples.insert(&(a + &(&ab * u)) + &(&ac * v));
+            }
+        }
+    }
+
+    return Ok(samples.into_iter().collect());
+}
+
+/// This function returns the one-sided Hausdorff distance from the mesh `a` to the mesh `b`,
+/// i.e. the maximum distance from the samples of `a` to `b`.
+/// It returns an error if one of meshes is empty or `subdivisions` is zero.
+/// # Arguments
+///
+/// * `a` - A mesh to take samples from.
+/// * `b` - A mesh to calculate distances to.
+/// * `subdivisions` - A number of parts per edge used to sample triangles of `a`.
+pub fn one_sided_hausdorff_distance(a: &Mesh, b: &Mesh, subdivisions: usize) -> ::std::result::Result<HausdorffDistance, &'static str> {
+    let query = DistanceQuery::new(b);
+    let mut res: Option<HausdorffDistance> = None;
+
+    for p in sample_mesh(a, subdivisions)? {
+        let cp = match query.closest_point(&p) {
+            Some(cp) => cp,
+            None => return Err("The mesh to calculate distances to is empty!")
+        };
+
+        let is_greater = match res {
+            Some(ref hd) => cp.squared_distance > hd.squared_distance,
+            None => true
+        };
+
+        if is_greater {
+            res = Some(HausdorffDistance {
+                squared_distance: cp.squared_distance,
+                from_point: p,
+                to_point: cp.point
+            });
+        }
+    }
+
+    return res.ok_or("The mesh to take samples from is empty!");
+}
+
+/// This function returns the symmetric Hausdorff distance between meshes `a` and `b`,
+/// i.e. the maximum of two one-sided distances.
+/// It returns an error if one of meshes is empty or `subdivisions` is zero.
+/// # Arguments
+///
+/// * `a` - The first mesh.
+/// * `b` - The second mesh.
+/// * `subdivisions` - A number of parts per edge used to sample triangles.
+pub fn hausdorff_distance(a: &Mesh, b: &Mesh, subdivisions: usize) -> ::std::result::Result<HausdorffDistance, &'static str> {
+    let ab = one_sided_hausdorff_distance(a, b, subdivisions)?;
+    let ba = one_sided_hausdorff_distance(b, a, subdivisions)?;
+
+    if ba.squared_distance > ab.squared_distance {
+        return Ok(ba);
+    }
+    return Ok(ab);
+}
+
+/// This structure contains deviations of vertices of a measured mesh from a nominal mesh.
+pub struct DeviationMap {
+    /// The vertices of the measured mesh.
+    pub points: Vec<Point>,
+    /// The triangles of the measured mesh as triples of indexes in `points`.
+    pub triangles: Vec<[usize; 3]>,
+    /// The exact squared distances from the vertices to the nominal mesh.
+    pub squared_distances: Vec<Number>,
+    /// The approximate distances from the vertices to the nominal mesh.
+    /// They are negative for vertices inside the nominal mesh if signed distances are calculated.
+    pub deviations: Vec<f64>
+}
+
+impl DeviationMap {
+    /// This method calculates deviations of vertices of the `measured` mesh from the `nominal` mesh.
+    /// It returns an error if the nominal mesh is empty.
+    /// # Arguments
+    ///
+    /// * `measured` - A mesh, for example a scanned part.
+    /// * `nominal` - A mesh to calculate distances to. It has to be closed if `signed` is true.
+    /// * `signed` - If it's true, deviations are negative inside the nominal mesh.
+    pub fn new(measured: &Mesh, nominal: &Mesh, signed: bool) -> ::std::result::Result<DeviationMap, &'static str> {
+        let mut its = measured.get_it_iterator();
+        its.sort();
+
+        let mut point_to_index: BTreeMap<Point, usize> = BTreeMap::new();
+        let mut points: Vec<Point> = Vec::new();
+        let mut triangles: Vec<[usize; 3]> = Vec::new();
+
+        for it in its {
+            let mut indexes = [0; 3];
+            for (i, p) in measured.get_triangle(it).get_points().into_iter().enumerate() {
+                let next_index = points.len();
+                let index = *point_to_index.entry(p.clone()).or_insert(next_index);
+                if index == next_index {
+                    points.push(p);
+                }
+                indexes[i] = index;
+            }
+            triangles.push(indexes);
+        }
+
+        let query = DistanceQuery::new(nominal);
+        let mut squared_distances: Vec<Number> = Vec::new();
+        let mut deviations: Vec<f64> = Vec::new();
+
+        for p in points.iter() {
+            // ближайшая точка ищется один раз, знак берется из знакового квадрата расстояния
+            let o_squared_distance = if signed {query.signed_squared_distance(p)} else {query.squared_distance(p)};
+            let squared_distance = match o_squared_distance {
+                Some(squared_distance) => squared_distance,
+                None => return Err("The nominal mesh is empty!")
+            };
+            let mut deviation = squared_distance.clone().abs().convert_to_f64().sqrt();
+            if squared_distance.is_it_negative() {
+                deviation = -deviation;
+            }
+            squared_distances.push(squared_distance.abs());
+            deviations.push(deviation);
+        }
+
+        return Ok(DeviationMap { points, triangles, squared_distances, deviations });
+    }
+
+    /// This method writes vertices with deviations to the `out` in CSV format.
+    /// # Arguments
+    ///
+    /// * `out` - A type, implementing Write.
+    pub fn write_csv<T: Write>(&self, out: &mut T) -> Result<()> {
+        writeln!(out, "x,y,z,deviation")?;
+        for (p, d) in self.points.iter().zip(self.deviations.iter()) {
+            writeln!(out, "{},{},{},{}",
+                     p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64(), p.z.clone().convert_to_f64(), d)?;
+        }
+        Ok(())
+    }
+
+    /// This method writes the measured mesh to the `out` in ASCII PLY format.
+    /// Deviations are written as the vertex property `deviation`.
+    /// # Arguments
+    ///
+    /// * `out` - A type, implementing Write.
+    pub fn write_ply<T: Write>(&self, out: &mut T) -> Result<()> {
+        writeln!(out, "ply")?;
+        writeln!(out, "format ascii 1.0")?;
+        writeln!(out, "element vertex {}", self.points.len())?;
+        writeln!(out, "property double x")?;
+        writeln!(out, "property double y")?;
+        writeln!(out, "property double z")?;
+        writeln!(out, "property double deviation")?;
+        writeln!(out, "element face {}", self.triangles.len())?;
+        writeln!(out, "property list uchar int vertex_indices")?;
+        writeln!(out, "end_header")?;
+
+        for (p, d) in self.points.iter().zip(self.deviations.iter()) {
+            writeln!(out, "{} {} {} {}",
+                     p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64(), p.z.clone().convert_to_f64(), d)?;
+        }
+
+        for t in self.triangles.iter() {
+            writeln!(out, "3 {} {} {}", t[0], t[1], t[2])?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use query::*;
+
+    #[test]
+    fn sampling() {
+        let mut mesh = Mesh::new();
+        mesh.add_triangle(Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 1., 0.)
+        ])).unwrap();
+
+        assert_eq!(sample_mesh(&mesh, 1).unwrap().len(), 3);
+        assert_eq!(sample_mesh(&mesh, 2).unwrap().len(), 6);
+        assert!(sample_mesh(&mesh, 4).unwrap().contains(&Point::new_from_f64(0.25, 0.5, 0.)));
+
+        // a cube has 8 vertices, 18 edges and 12 triangles
+        assert_eq!(sample_mesh(&cube_mesh(), 1).unwrap().len(), 8);
+        assert_eq!(sample_mesh(&cube_mesh(), 2).unwrap().len(), 8 + 18);
+        assert!(sample_mesh(&mesh, 0).is_err());
+    }
+
+    #[test]
+    fn shifted_cubes() {
+        let a = cube_mesh();
+        let b = box_mesh(&Point::new_from_f64(0.5, 0., 0.), &Point::new_from_f64(1.5, 1., 1.));
+
+        let hd = one_sided_hausdorff_distance(&a, &b, 2).unwrap();
+        assert_eq!(hd.squared_distance, Number::new(0.25));
+        assert_eq!(hd.from_point.x, Number::new(0.));
+        assert_eq!(hd.get_distance(), 0.5);
+
+        let hd = hausdorff_distance(&a, &b, 2).unwrap();
+        assert_eq!(hd.squared_distance, Number::new(0.25));
+    }
+
+    #[test]
+    fn nested_cubes() {
+        // the small cube is inside the big one, so distances differ in two directions
+        let small = cube_mesh();
+        let big = box_mesh(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 2., 2.));
+
+        let hd = one_sided_hausdorff_distance(&small, &big, 2).unwrap();
+        assert_eq!(hd.squared_distance, Number::new(1.));
+
+        let hd = one_sided_hausdorff_distance(&big, &small, 2).unwrap();
+        assert_eq!(hd.squared_distance, Number::new(3.));
+        assert_eq!(hd.from_point, Point::new_from_f64(2., 2., 2.));
+        assert_eq!(hd.to_point, Point::new_from_f64(1., 1., 1.));
+
+        assert!(hausdorff_distance(&small, &Mesh::new(), 2).is_err());
+        assert!(hausdorff_distance(&Mesh::new(), &small, 2).is_err());
+        assert!(hausdorff_distance(&small, &big, 0).is_err());
+    }
+
+    #[test]
+    fn deviation_map_export() {
+        let measured = cube_mesh();
+        let nominal = box_mesh(&Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(2., 2., 2.));
+        let dm = DeviationMap::new(&measured, &nominal, true).unwrap();
+
+        assert_eq!(dm.points.len(), 8);
+        assert_eq!(dm.triangles.len(), 12);
+        for (p, d) in dm.points.iter().zip(dm.deviations.iter()) {
+            if *p == Point::new_from_f64(1., 1., 1.) {
+                assert_eq!(*d, -1.);
+            }
+            if *p == Point::new_from_f64(0., 0., 0.) {
+                assert_eq!(*d, 0.);
+            }
+        }
+
+        let mut csv: Vec<u8> = Vec::new();
+        dm.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 9);
+        assert!(csv.contains("1,1,1,-1"));
+
+        let mut ply: Vec<u8> = Vec::new();
+        dm.write_ply(&mut ply).unwrap();
+        let ply = String::from_utf8(ply).unwrap();
+        assert!(ply.contains("element vertex 8\n"));
+        assert!(ply.contains("property double deviation\n"));
+        assert_eq!(ply.lines().count(), 10 + 8 + 12);
+
+        assert!(DeviationMap::new(&measured, &Mesh::new(), false).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+mod ray_x_triangle;
+pub mod point_in_mesh;
+pub mod ray_x_mesh;
+pub mod closest_point;
+pub mod hausdorff;
+pub mod fitting;
+
+pub(crate) use self::ray_x_triangle::{RayXTriangle, intersect_ray_with_triangle};
+pub use self::point_in_mesh::{PointPosition, PointClassifier, classify_point};
+pub use self::ray_x_mesh::{RayHit, RayCaster, cast_ray};
+pub use self::closest_point::{Feature, ClosestPoint, DistanceQuery, find_closest_point};
+pub use self::hausdorff::{HausdorffDistance, DeviationMap, sample_mesh, one_sided_hausdorff_distance, hausdorff_distance};
+pub use self::fitting::{Residuals, PlaneFit, LineFit, fit_plane, fit_line, flatness};
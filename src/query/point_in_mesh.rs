@@ -0,0 +1,188 @@
+use primitives::*;
+use intersect::tuple_iter::TreeAABT;
+use query::{RayXTriangle, intersect_ray_with_triangle};
+
+/// This enum describes a position of a point relative to a closed mesh.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointPosition {
+    Inside,
+    Outside,
+    OnBoundary
+}
+
+/// This structure classifies points relative to a closed mesh.
+/// The tree of bounding boxes is built once, so it's preferable to use it for a lot of queries.
+pub struct PointClassifier<'a> {
+    mesh: &'a Mesh,
+    tree: Option<TreeAABT>
+}
+
+impl<'a> PointClassifier<'a> {
+    /// This method creates `PointClassifier` and builds the tree of bounding boxes for the `mesh`.
+    /// # Arguments
+    ///
+    /// * `mesh` - A closed mesh. The result has no meaning if the mesh is not closed.
+    pub fn new(mesh: &'a Mesh) -> PointClassifier<'a> {
+        let tree = if mesh.num_of_triangles() > 0 {
+            Some(TreeAABT::new(mesh))
+        } else {
+            None
+        };
+        return PointClassifier { mesh, tree };
+    }
+
+    pub(crate) fn get_tree(&self) -> Option<&TreeAABT> {
+        return self.tree.as_ref();
+    }
+
+    /// This method returns the exact position of the point `p` relative to the mesh.
+    /// The parity of the number of intersections of a ray with the mesh is used.
+    /// If the ray passes through an edge or a vertex or lies in a plane of a triangle,
+    /// another ray direction is chosen.
+    /// # Arguments
+    ///
+    /// * `p` - A point to classify.
+    pub fn classify(&self, p: &Point) -> PointPosition {
+        let tree = match self.tree {
+            Some(ref tree) => tree,
+            None => return PointPosition::Outside
+        };
+
+        for it in tree.find_triangles(|b| b.does_box_contain_point(p)) {
+            let tr = self.mesh.get_triangle(it);
+            if does_triangle_contain_point(&tr, p) {
+                return PointPosition::OnBoundary;
+            }
+        }
+
+        let mut attempt: usize = 0;
+        loop {
+            let dir = get_ray_direction(attempt);
+            attempt += 1;
+
+            match count_intersections(self.mesh, tree, p, &dir) {
+                Some(number) => {
+                    if number % 2 == 1 {
+                        return PointPosition::Inside;
+                    } else {
+                        return PointPosition::Outside;
+                    }
+                },
+                None => debug!("The ray {0} is degenerate, another direction is used.", dir)
+            }
+        }
+    }
+}
+
+/// This function returns the exact position of the point `p` relative to the closed `mesh`.
+/// Use `PointClassifier` to classify a lot of points.
+/// # Arguments
+///
+/// * `mesh` - A closed mesh.
+/// * `p` - A point to classify.
+pub fn classify_point(mesh: &Mesh, p: &Point) -> PointPosition {
+    return PointClassifier::new(mesh).classify(p);
+}
+
+// возвращает None, если луч касается ребра, вершины или лежит в плоскости треугольника
+fn count_intersections(mesh: &Mesh, tree: &TreeAABT, p: &Point, dir: &Vector) -> Option<usize> {
+    let mut number: usize = 0;
+    for it in tree.find_triangles(|b| b.does_ray_intersect_box(p, dir)) {
+        let tr = mesh.get_triangle(it);
+        match intersect_ray_with_triangle(p, dir, &tr) {
+            RayXTriangle::NoIntersection => (),
+            RayXTriangle::Coplanar => return None,
+            RayXTriangle::Intersection {on_boundary, ..} => {
+                if on_boundary {
+                    return None;
+                }
+                number += 1;
+            }
+        }
+    }
+    return Some(number);
+}
+
+fn does_triangle_contain_point(tr: &Triangle, p: &Point) -> bool {
+    let a = tr.get_ref(0);
+    let b = tr.get_ref(1);
+    let c = tr.get_ref(2);
+
+    let n = (b - a).cross_product(&(c - a));
+    if !n.dot_product(&(p - a)).is_it_zero() {
+        return false;
+    }
+
+    return !(c - b).cross_product(&(p - b)).dot_product(&n).is_it_negative() &&
+           !(a - c).cross_product(&(p - c)).dot_product(&n).is_it_negative() &&
+           !(b - a).cross_product(&(p - a)).dot_product(&n).is_it_negative();
+}
+
+// направления выбираются нерегулярными, чтобы вырожденные случаи были маловероятны
+fn get_ray_direction(attempt: usize) -> Vector {
+    let k = attempt as f64;
+    let sign = if attempt % 2 == 0 {1.} else {-1.};
+    return Vector::new(
+        Number::new(sign),
+        Number::new(2.*k + 3.) / Number::new(7.*k + 11.),
+        Number::new(5.*k + 2.) / Number::new(3.*k + 13.)
+    );
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use query::*;
+
+    #[test]
+    fn cube_classification() {
+        let mesh = cube_mesh();
+        let classifier = PointClassifier::new(&mesh);
+
+        assert_eq!(classifier.classify(&Point::new_from_f64(0.5, 0.5, 0.5)), PointPosition::Inside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(0.25, 0.75, 0.125)), PointPosition::Inside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(1.5, 0.5, 0.5)), PointPosition::Outside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(-1., -1., -1.)), PointPosition::Outside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(0.5, 0.5, 1.)), PointPosition::OnBoundary);
+        assert_eq!(classifier.classify(&Point::new_from_f64(1., 1., 0.)), PointPosition::OnBoundary);
+        assert_eq!(classifier.classify(&Point::new_from_f64(0., 0.5, 0.5)), PointPosition::OnBoundary);
+    }
+
+    #[test]
+    fn degenerate_rays() {
+        // the points are chosen so that rays parallel to axes pass through edges of the cube
+        let mesh = cube_mesh();
+        let classifier = PointClassifier::new(&mesh);
+
+        assert_eq!(classifier.classify(&Point::new_from_f64(0.5, 0.5, 0.)), PointPosition::OnBoundary);
+        assert_eq!(classifier.classify(&Point::new_from_f64(-0.5, 0., 0.)), PointPosition::Outside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(-0.5, 1., 1.)), PointPosition::Outside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(0.5, 0.5, 0.5 + 1e-9)), PointPosition::Inside);
+    }
+
+    #[test]
+    fn two_components() {
+        let mut mesh = cube_mesh();
+        let shifted = cube_mesh();
+        for it in shifted.get_it_iterator() {
+            let points = shifted.get_triangle(it).get_points().into_iter()
+                .map(|p| &p + &Vector::new_from_f64(3., 0., 0.))
+                .collect();
+            mesh.add_triangle(Triangle::new(points)).unwrap();
+        }
+
+        let classifier = PointClassifier::new(&mesh);
+        assert_eq!(classifier.classify(&Point::new_from_f64(0.5, 0.5, 0.5)), PointPosition::Inside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(3.5, 0.5, 0.5)), PointPosition::Inside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(2., 0.5, 0.5)), PointPosition::Outside);
+        assert_eq!(classifier.classify(&Point::new_from_f64(4., 0.5, 0.5)), PointPosition::OnBoundary);
+    }
+
+    #[test]
+    fn empty_mesh() {
+        let mesh = Mesh::new();
+        assert_eq!(classify_point(&mesh, &Point::new_from_f64(0., 0., 0.)), PointPosition::Outside);
+    }
+}
@@ -0,0 +1,172 @@
+use primitives::*;
+use intersect::tuple_iter::TreeAABT;
+use query::{RayXTriangle, intersect_ray_with_triangle};
+
+/// This structure represents an intersection of a ray `origin + t*direction` with a triangle of a mesh.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RayHit {
+    /// The index of the intersected triangle in the mesh.
+    pub triangle_index: usize,
+    /// The ray parameter of the intersection point. It's not negative.
+    pub t: Number,
+    /// The exact intersection point.
+    pub point: Point,
+    /// The barycentric coordinates of the point corresponding to the vertices of the triangle.
+    pub barycentric: [Number; 3]
+}
+
+/// This structure performs exact ray queries to a mesh.
+/// The tree of bounding boxes is built once, so it's preferable to use it for a lot of queries.
+/// Triangles containing the ray in their planes are not considered as intersected.
+pub struct RayCaster<'a> {
+    mesh: &'a Mesh,
+    tree: Option<TreeAABT>
+}
+
+impl<'a> RayCaster<'a> {
+    /// This method creates `RayCaster` and builds the tree of bounding boxes for the `mesh`.
+    /// # Arguments
+    ///
+    /// * `mesh` - A mesh to cast rays to.
+    pub fn new(mesh: &'a Mesh) -> RayCaster<'a> {
+        let tree = if mesh.num_of_triangles() > 0 {
+            Some(TreeAABT::new(mesh))
+        } else {
+            None
+        };
+        return RayCaster { mesh, tree };
+    }
+
+    /// This method returns all intersections of the ray with the mesh sorted by the ray parameter.
+    /// # Arguments
+    ///
+    /// * `origin` - An origin of the ray.
+    /// * `direction` - A non zero direction of the ray.
+    pub fn all_hits(&self, origin: &Point, direction: &Vector) -> Vec<RayHit> {
+        let tree = match self.tree {
+            Some(ref tree) => tree,
+            None => return Vec::new()
+        };
+
+        let mut hits: Vec<RayHit> = Vec::new();
+        for it in tree.find_triangles(|b| b.does_ray_intersect_box(origin, direction)) {
+            let tr = self.mesh.get_triangle(it);
+            match intersect_ray_with_triangle(origin, direction, &tr) {
+                RayXTriangle::Intersection {t, point, barycentric, ..} => {
+                    hits.push(RayHit { triangle_index: it, t, point, barycentric });
+                },
+                _ => ()
+            }
+        }
+
+        hits.sort_by(|h1, h2| h1.t.cmp(&h2.t).then(h1.triangle_index.cmp(&h2.triangle_index)));
+        return hits;
+    }
+
+    /// This method returns the nearest intersection of the ray with the mesh.
+    /// If several triangles are intersected at the nearest point, the one with the least index is returned.
+    /// # Arguments
+    ///
+    /// * `origin` - An origin of the ray.
+    /// * `direction` - A non zero direction of the ray.
+    pub fn first_hit(&self, origin: &Point, direction: &Vector) -> Option<RayHit> {
+        return self.all_hits(origin, direction).into_iter().next();
+    }
+
+    /// This method checks if the ray intersects the mesh.
+    /// # Arguments
+    ///
+    /// * `origin` - An origin of the ray.
+    /// * `direction` - A non zero direction of the ray.
+    pub fn does_hit(&self, origin: &Point, direction: &Vector) -> bool {
+        let tree = match self.tree {
+            Some(ref tree) => tree,
+            None => return false
+        };
+
+        for it in tree.find_triangles(|b| b.does_ray_intersect_box(origin, direction)) {
+            let tr = self.mesh.get_triangle(it);
+            if let RayXTriangle::Intersection {..} = intersect_ray_with_triangle(origin, direction, &tr) {
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+/// This function returns the nearest intersection of the ray with the `mesh`.
+/// Use `RayCaster` to cast a lot of rays.
+/// # Arguments
+///
+/// * `mesh` - A mesh to cast the ray to.
+/// * `origin` - An origin of the ray.
+/// * `direction` - A non zero direction of the ray.
+pub fn cast_ray(mesh: &Mesh, origin: &Point, direction: &Vector) -> Option<RayHit> {
+    return RayCaster::new(mesh).first_hit(origin, direction);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use query::*;
+
+    #[test]
+    fn ray_through_cube() {
+        let mesh = cube_mesh();
+        let caster = RayCaster::new(&mesh);
+        let origin = Point::new_from_f64(0.25, 0.75, -1.);
+        let direction = Vector::new_from_f64(0., 0., 2.);
+
+        let hits = caster.all_hits(&origin, &direction);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].t, Number::new(0.5));
+        assert_eq!(hits[0].point, Point::new_from_f64(0.25, 0.75, 0.));
+        assert_eq!(hits[1].t, Number::new(1.));
+        assert_eq!(hits[1].point, Point::new_from_f64(0.25, 0.75, 1.));
+
+        for hit in hits.iter() {
+            let tr = mesh.get_triangle(hit.triangle_index);
+            let mut v = Vector::new_from_f64(0., 0., 0.);
+            for i in 0..3 {
+                v = v + tr.get_ref(i).get_vector() * hit.barycentric[i].clone();
+            }
+            assert_eq!(v.get_point(), hit.point);
+        }
+
+        assert_eq!(caster.first_hit(&origin, &direction), Some(hits[0].clone()));
+        assert!(caster.does_hit(&origin, &direction));
+    }
+
+    #[test]
+    fn ray_misses_cube() {
+        let mesh = cube_mesh();
+        let caster = RayCaster::new(&mesh);
+        let origin = Point::new_from_f64(0.5, 0.5, -1.);
+
+        assert!(!caster.does_hit(&origin, &Vector::new_from_f64(0., 0., -1.)));
+        assert!(!caster.does_hit(&origin, &Vector::new_from_f64(1., 0., 0.)));
+        assert!(cast_ray(&mesh, &origin, &Vector::new_from_f64(2., 0., 1.)).is_none());
+    }
+
+    #[test]
+    fn ray_from_inside() {
+        let mesh = cube_mesh();
+        let hit = cast_ray(&mesh, &Point::new_from_f64(0.5, 0.25, 0.5), &Vector::new_from_f64(1., 0., 0.)).unwrap();
+        assert_eq!(hit.point, Point::new_from_f64(1., 0.25, 0.5));
+        assert_eq!(hit.t, Number::new(0.5));
+    }
+
+    #[test]
+    fn ray_through_edge() {
+        // the ray passes through the diagonal of the bottom face, so both triangles are reported
+        let mesh = cube_mesh();
+        let hits = RayCaster::new(&mesh).all_hits(
+            &Point::new_from_f64(0.5, 0.5, -1.), &Vector::new_from_f64(0., 0., 1.));
+        assert_eq!(hits.len(), 4);
+        assert_eq!(hits[0].t, hits[1].t);
+        assert!(hits[0].triangle_index < hits[1].triangle_index);
+        assert_eq!(hits[2].point, Point::new_from_f64(0.5, 0.5, 1.));
+    }
+}
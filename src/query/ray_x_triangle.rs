@@ -0,0 +1,57 @@
+use primitives::*;
+
+// результат пересечения луча org + t*dir (t >= 0) с треугольником
+pub(crate) enum RayXTriangle {
+    NoIntersection,
+    // луч лежит в плоскости треугольника
+    Coplanar,
+    // барицентрические координаты соответствуют вершинам треугольника,
+    // on_boundary = true, если луч проходит через ребро или вершину
+    Intersection {
+        t: Number,
+        point: Point,
+        barycentric: [Number; 3],
+        on_boundary: bool
+    }
+}
+
+pub(crate) fn intersect_ray_with_triangle(org: &Point, dir: &Vector, tr: &Triangle) -> RayXTriangle {
+    let a = tr.get_ref(0);
+    let b = tr.get_ref(1);
+    let c = tr.get_ref(2);
+
+    let n = (b - a).cross_product(&(c - a));
+    let denominator = n.dot_product(dir);
+
+    if denominator.is_it_zero() {
+        if n.dot_product(&(org - a)).is_it_zero() {
+            return RayXTriangle::Coplanar;
+        }
+        return RayXTriangle::NoIntersection;
+    }
+
+    let t = n.dot_product(&(a - org)) / denominator;
+    if t.is_it_negative() {
+        return RayXTriangle::NoIntersection;
+    }
+
+    let point = org + &(dir * t.clone());
+
+    let wa = (c - b).cross_product(&(&point - b)).dot_product(&n);
+    let wb = (a - c).cross_product(&(&point - c)).dot_product(&n);
+    let wc = (b - a).cross_product(&(&point - a)).dot_product(&n);
+
+    if wa.is_it_negative() || wb.is_it_negative() || wc.is_it_negative() {
+        return RayXTriangle::NoIntersection;
+    }
+
+    let on_boundary = wa.is_it_zero() || wb.is_it_zero() || wc.is_it_zero();
+    let nn = n.length2();
+
+    return RayXTriangle::Intersection {
+        t,
+        point,
+        barycentric: [wa / &nn, wb / &nn, wc / nn],
+        on_boundary
+    };
+}
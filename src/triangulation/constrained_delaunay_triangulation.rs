@@ -0,0 +1,791 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BTreeMap, HashMap, HashSet, VecDeque};
+use primitives::*;
+use primitives::predicates::{orient2d, incircle};
+
+/// This function builds the exact constrained Delaunay triangulation of `points` in the plane xy.
+/// Constraint segments become edges of the triangulation, they are split at the points lying on them
+/// and at their crossings. The triangulation covers the convex hull of all points and endpoints of constraints,
+/// all triangles are counterclockwise. It returns an error if a constraint can't be inserted into the triangulation.
+/// # Arguments
+///
+/// * `points` - Points to triangulate. Predicates ignore z coordinates, so projections of points to the plane xy have to be unique.
+/// * `constraints` - Segments which have to be edges of the triangulation.
+pub fn triangulate2d(points : Vec<Point>, constraints : Vec<Segment>) -> Result<Vec<Triangle>, &'static str> {
+    let ct = ConstrainedTriangulation::new(points, constraints)?;
+    return Ok(ct.get_triangles());
+}
+
+/*
+    Триангуляция хранится как набор треугольников (индексы вершин против часовой стрелки)
+    и отображение ориентированного ребра в треугольник, которому оно принадлежит.
+    Флипы переиспользуют слоты треугольников, поэтому треугольники никогда не удаляются.
+    Для каждой вершины хранится один из содержащих ее треугольников, для каждого исходного ограничения -
+    ориентированные ребра, на которые оно было разбито.
+*/
+pub(crate) struct ConstrainedTriangulation {
+    points : Vec<Point>,
+    triangles : Vec<[usize; 3]>,
+    edges : HashMap<(usize, usize), usize>,
+    vertex_triangles : Vec<usize>,
+    constraints : HashSet<(usize, usize)>,
+    constraint_edges : Vec<Vec<(usize, usize)>>
+}
+
+impl ConstrainedTriangulation {
+    pub(crate) fn new(points : Vec<Point>, constraints : Vec<Segment>) -> Result<ConstrainedTriangulation, &'static str> {
+        let n = constraints.len();
+        let mut segs : Vec<(Point, Point, usize)> = Vec::new();
+        for (i, s) in constraints.into_iter().enumerate() {
+            if s.org != s.dest {
+                segs.push((s.org, s.dest, i));
+            }
+        }
+        let segs = split_crossing_segments(segs);
+
+        let mut unique_points : BTreeSet<Point> = points.into_iter().collect();
+        for &(ref a, ref b, _) in segs.iter() {
+            unique_points.insert(a.clone());
+            unique_points.insert(b.clone());
+        }
+
+        let sorted_points : Vec<Point> = unique_points.into_iter().collect();
+        let indices : BTreeMap<Point, usize> = sorted_points.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+
+        let mut ct = ConstrainedTriangulation {
+            points : sorted_points,
+            triangles : Vec::new(),
+            edges : HashMap::new(),
+            vertex_triangles : Vec::new(),
+            constraints : HashSet::new(),
+            constraint_edges : vec![Vec::new(); n]
+        };
+
+        ct.build();
+        if ct.triangles.is_empty() {
+            return Ok(ct);
+        }
+
+        let mut index_segs : BTreeSet<(usize, usize)> = BTreeSet::new();
+        for (a, b, i) in segs {
+            let chain = ct.split_at_points(indices[&a], indices[&b]);
+            index_segs.extend(chain.iter().map(|&(u, v)| edge_key(u, v)));
+            ct.constraint_edges[i].extend(chain);
+        }
+        for (a, b) in index_segs {
+            ct.insert_constraint(a, b)?;
+        }
+
+        let mut all_edges : Vec<(usize, usize)> = ct.edges.keys().cloned().collect();
+        all_edges.sort();
+        ct.make_delaunay(all_edges);
+        return Ok(ct);
+    }
+
+    pub(crate) fn get_points(&self) -> &Vec<Point> {
+        return &self.points;
+    }
+
+    pub(crate) fn get_index_triangles(&self) -> &Vec<[usize; 3]> {
+        return &self.triangles;
+    }
+
+    pub(crate) fn get_triangles(&self) -> Vec<Triangle> {
+        return self.triangles.iter()
+            .map(|t| Triangle::new(vec![
+                self.points[t[0]].clone(),
+                self.points[t[1]].clone(),
+                self.points[t[2]].clone()
+            ]))
+            .collect();
+    }
+
+    /// This method returns the exact circumcenter of the triangle `t` in the plane xy, z coordinate is taken from its first vertex.
+    pub(crate) fn get_circumcenter(&self, t : &[usize; 3]) -> Point {
+        let a = &self.points[t[0]];
+        let b : Vector = &self.points[t[1]] - a;
+        let c : Vector = &self.points[t[2]] - a;
+        let d = (&b.x * &c.y - &b.y * &c.x) * Number::new(2.);
+        let lb = &b.x * &b.x + &b.y * &b.y;
+        let lc = &c.x * &c.x + &c.y * &c.y;
+        let x = (&c.y * &lb - &b.y * &lc) / d.clone();
+        let y = (&b.x * &lc - &c.x * &lb) / d;
+        return Point::new(&a.x + &x, &a.y + &y, a.z.clone());
+    }
+
+    pub(crate) fn is_constrained(&self, a : usize, b : usize) -> bool {
+        return self.constraints.contains(&edge_key(a, b));
+    }
+
+    /// This method returns directed edges, which the constraint with the index `i` was split into
+    /// when the triangulation was built. Edges are directed from the origin of the constraint to its destination.
+    pub(crate) fn get_constraint_edges(&self, i : usize) -> &Vec<(usize, usize)> {
+        return &self.constraint_edges[i];
+    }
+
+    pub(crate) fn get_constraints(&self) -> Vec<(usize, usize)> {
+        let mut constraints : Vec<(usize, usize)> = self.constraints.iter().cloned().collect();
+        constraints.sort();
+        return constraints;
+    }
+
+    /// This method marks triangles inside the region bounded by constraints: a triangle is inside, if a path from
+    /// the boundary of the triangulation to it crosses an odd number of constrained edges.
+    pub(crate) fn get_domain(&self) -> Vec<bool> {
+        let ts = &self.triangles;
+        let mut seeds : Vec<(usize, usize)> = Vec::new();
+        for (i, t) in ts.iter().enumerate() {
+            for j in 0..3 {
+                let (a, b) = (t[j], t[(j + 1) % 3]);
+                if self.get_edge_triangle(b, a).is_none() {
+                    let d = if self.is_constrained(a, b) {1} else {0};
+                    seeds.push((i, d));
+                }
+            }
+        }
+
+        return get_parity_domain(ts.len(), seeds, |i| {
+            let t = ts[i];
+            return (0..3)
+                .filter_map(|j| {
+                    let (a, b) = (t[j], t[(j + 1) % 3]);
+                    self.get_edge_triangle(b, a).map(|n| (n, self.is_constrained(a, b)))
+                })
+                .collect();
+        });
+    }
+
+    /// This method returns the index of the triangle, containing the directed edge `a`->`b`.
+    pub(crate) fn get_edge_triangle(&self, a : usize, b : usize) -> Option<usize> {
+        return self.edges.get(&(a, b)).cloned();
+    }
+
+    /// This method inserts the point `p` into the triangulation and restores the constrained Delaunay property.
+    /// If the point lies on a constrained edge, the constraint is split. It returns the index of the vertex or None,
+    /// if the point lies outside of the triangulation.
+    /// # Arguments
+    ///
+    /// * `p` - A point to insert.
+    /// * `start` - A triangle to start the search of the point from, the search is fast if it's close to the point.
+    pub(crate) fn insert_point(&mut self, p : Point, start : usize) -> Option<usize> {
+        let (ti, os) = match self.locate_point(&p, start, false) {
+            Some(l) => l,
+            None => return None
+        };
+        if let Some(&i) = self.triangles[ti].iter().find(|&&i| self.points[i].x == p.x && self.points[i].y == p.y) {
+            return Some(i);
+        }
+
+        let idx = self.points.len();
+        self.points.push(p);
+        let [a, b, c] = self.triangles[ti];
+
+        match os.iter().position(|o| *o == Ordering::Equal) {
+            None => {
+                self.set_triangle(ti, [a, b, idx]);
+                self.add_triangle([b, c, idx]);
+                self.add_triangle([c, a, idx]);
+                self.make_delaunay(vec![(a, b), (b, c), (c, a)]);
+            },
+            Some(j) => {
+                let t = self.triangles[ti];
+                self.split_edge(t[j], t[(j + 1) % 3], idx);
+            }
+        }
+
+        return Some(idx);
+    }
+
+    /// This method returns a triangle containing the point `p` and orientations of `p` relative to its edges.
+    /// Triangles are walked from `start` through edges, which separate them from the point. It returns None,
+    /// if the point lies outside of the triangulation or, if `stop_at_constraints` is true, behind a constrained edge.
+    /// # Arguments
+    ///
+    /// * `p` - A point to locate.
+    /// * `start` - A triangle to start the walk from.
+    /// * `stop_at_constraints` - If it's true, constrained edges aren't crossed.
+    pub(crate) fn locate_point(&self, p : &Point, start : usize, stop_at_constraints : bool) -> Option<(usize, Vec<Ordering>)> {
+        // в триангуляции, не являющейся триангуляцией Делоне, обход может зациклиться, поэтому первое проверяемое
+        // ребро меняется с каждым шагом, а после числа шагов, равного числу треугольников, выполняется перебор
+        let mut cur = start;
+        for step in 0..self.triangles.len() {
+            let t = self.triangles[cur];
+            let os : Vec<Ordering> = (0..3)
+                .map(|j| orient2d(&self.points[t[j]], &self.points[t[(j + 1) % 3]], p))
+                .collect();
+            let (a, b) = match (0..3).map(|k| (k + step) % 3).find(|&j| os[j] == Ordering::Less) {
+                Some(j) => (t[j], t[(j + 1) % 3]),
+                None => return Some((cur, os))
+            };
+            if stop_at_constraints && self.is_constrained(a, b) {
+                return None;
+            }
+            cur = match self.edges.get(&(b, a)) {
+                Some(&n) => n,
+                None => return None
+            };
+        }
+
+        if stop_at_constraints {
+            return None;
+        }
+        for (i, t) in self.triangles.iter().enumerate() {
+            let os : Vec<Ordering> = (0..3)
+                .map(|j| orient2d(&self.points[t[j]], &self.points[t[(j + 1) % 3]], p))
+                .collect();
+            if os.iter().all(|o| *o != Ordering::Less) {
+                return Some((i, os));
+            }
+        }
+        return None;
+    }
+
+    /// This method splits the constrained edge `u`-`v` by the point `p`, which is supposed to lie on it.
+    /// It returns the index of the new vertex.
+    /// # Arguments
+    ///
+    /// * `u`, `v` - Vertices of a constrained edge.
+    /// * `p` - A point of the edge.
+    pub(crate) fn split_constraint(&mut self, u : usize, v : usize, p : Point) -> usize {
+        assert!(self.is_constrained(u, v), "Only constrained edges can be split!");
+        let idx = self.points.len();
+        self.points.push(p);
+        if self.edges.contains_key(&(u, v)) {
+            self.split_edge(u, v, idx);
+        } else {
+            self.split_edge(v, u, idx);
+        }
+        return idx;
+    }
+
+    fn split_edge(&mut self, u : usize, v : usize, idx : usize) {
+        let t1 = self.edges[&(u, v)];
+        let c = self.third(t1, u, v);
+        let t2 = self.edges.get(&(v, u)).cloned();
+        self.edges.remove(&(u, v));
+        self.edges.remove(&(v, u));
+
+        self.set_triangle(t1, [u, idx, c]);
+        self.add_triangle([idx, v, c]);
+        let mut to_check = vec![(v, c), (c, u)];
+        if let Some(t2) = t2 {
+            let d = self.third(t2, v, u);
+            self.set_triangle(t2, [v, idx, d]);
+            self.add_triangle([idx, u, d]);
+            to_check.extend(vec![(u, d), (d, v)]);
+        }
+
+        if self.constraints.remove(&edge_key(u, v)) {
+            self.constraints.insert(edge_key(u, idx));
+            self.constraints.insert(edge_key(idx, v));
+        }
+
+        self.make_delaunay(to_check);
+    }
+
+    fn build(&mut self) {
+        // заметание по лексикографически упорядоченным точкам, оболочка хранится против часовой стрелки
+        let n = self.points.len();
+        if n < 3 {
+            return;
+        }
+
+        let mut k = 2;
+        while k < n && orient2d(&self.points[0], &self.points[1], &self.points[k]) == Ordering::Equal {
+            k += 1;
+        }
+        if k == n {
+            return;
+        }
+
+        let mut hull : Vec<usize>;
+        if orient2d(&self.points[0], &self.points[1], &self.points[k]) == Ordering::Greater {
+            for j in 0..k - 1 {
+                self.add_triangle([j, j + 1, k]);
+            }
+            hull = (0..k + 1).collect();
+        } else {
+            for j in 0..k - 1 {
+                self.add_triangle([j + 1, j, k]);
+            }
+            hull = vec![0, k];
+            hull.extend((1..k).rev());
+        }
+
+        for i in k + 1..n {
+            // предыдущая точка лежит в hull[0], видимые ребра оболочки обычно примыкают к ней,
+            // поэтому поиск начинается с них и проверяет только видимую цепочку
+            let m = hull.len();
+            let (mut s, mut e);
+            {
+                let is_visible = |j : usize| orient2d(&self.points[hull[j]], &self.points[hull[(j + 1) % m]], &self.points[i]) == Ordering::Less;
+                let start = vec![0, m - 1].into_iter().chain(1..m - 1).find(|&j| is_visible(j)).unwrap();
+                s = start;
+                while is_visible((s + m - 1) % m) {
+                    s = (s + m - 1) % m;
+                }
+                e = start;
+                while is_visible(e) {
+                    e = (e + 1) % m;
+                }
+            }
+
+            let mut j = s;
+            while j != e {
+                self.add_triangle([hull[(j + 1) % m], hull[j], i]);
+                j = (j + 1) % m;
+            }
+
+            let mut new_hull = vec![i];
+            let mut j = e;
+            loop {
+                new_hull.push(hull[j]);
+                if j == s {
+                    break;
+                }
+                j = (j + 1) % m;
+            }
+            hull = new_hull;
+        }
+    }
+
+    fn split_at_points(&self, a : usize, b : usize) -> Vec<(usize, usize)> {
+        let (pa, pb) = (&self.points[a], &self.points[b]);
+        let (lo, hi) = if pa < pb {(pa, pb)} else {(pb, pa)};
+        let mut inner : Vec<usize> = (0..self.points.len())
+            .filter(|&i| {
+                let p = &self.points[i];
+                p > lo && p < hi && orient2d(pa, pb, p) == Ordering::Equal
+            })
+            .collect();
+        inner.sort_by(|&i, &j| self.points[i].cmp(&self.points[j]));
+        if pa > pb {
+            inner.reverse();
+        }
+
+        let mut chain = vec![a];
+        chain.extend(inner);
+        chain.push(b);
+        return chain.windows(2).map(|w| (w[0], w[1])).collect();
+    }
+
+    // ошибка возвращается, если ребра, пересекающие ограничение, не удается перестроить
+    fn insert_constraint(&mut self, a : usize, b : usize) -> Result<(), &'static str> {
+        if self.edges.contains_key(&(a, b)) || self.edges.contains_key(&(b, a)) {
+            self.constraints.insert(edge_key(a, b));
+            return Ok(());
+        }
+
+        // пересекаемые ребра собираются проходом по треугольникам от a к b: первое ребро лежит напротив a
+        // в треугольнике, угол которого содержит b, дальше x остается справа от ab, y - слева
+        let (pa, pb) = (&self.points[a], &self.points[b]);
+        let first = match self.get_vertex_triangles(a).into_iter()
+            .map(|t| self.get_opposite_edge(t, a))
+            .find(|&(x, y)| orient2d(pa, &self.points[x], pb) == Ordering::Greater && orient2d(pa, &self.points[y], pb) == Ordering::Less) {
+            Some(e) => e,
+            None => return Err("Constraint can't be inserted into the triangulation!")
+        };
+        let mut crossing = vec![first];
+        let (mut x, mut y) = first;
+        loop {
+            let z = match self.edges.get(&(y, x)) {
+                Some(&t) => self.third(t, x, y),
+                None => return Err("Constraint can't be inserted into the triangulation!")
+            };
+            if z == b {
+                break;
+            }
+            if orient2d(pa, pb, &self.points[z]) == Ordering::Greater {
+                y = z;
+            } else {
+                x = z;
+            }
+            crossing.push((x, y));
+        }
+
+        // алгоритм Слоана: флипаем ребра, пересекающие ограничение, пока оно не станет ребром
+        let mut queue : VecDeque<(usize, usize)> = crossing.into_iter().collect();
+
+        let mut stall = 0;
+        while let Some((u, v)) = queue.pop_front() {
+            let c = self.third(self.edges[&(u, v)], u, v);
+            let d = self.third(self.edges[&(v, u)], v, u);
+            if self.crosses(c, d, u, v) {
+                self.flip(u, v);
+                stall = 0;
+                if self.crosses(a, b, c, d) {
+                    queue.push_back((c, d));
+                }
+            } else {
+                queue.push_back((u, v));
+                stall += 1;
+                if stall > queue.len() {
+                    return Err("Constraint can't be inserted into the triangulation!");
+                }
+            }
+        }
+
+        if !self.edges.contains_key(&(a, b)) && !self.edges.contains_key(&(b, a)) {
+            return Err("Constraint can't be inserted into the triangulation!");
+        }
+        self.constraints.insert(edge_key(a, b));
+        return Ok(());
+    }
+
+    /// This method returns triangles containing the vertex `a`. They are walked from a remembered triangle
+    /// counterclockwise and, for vertices of the convex hull, clockwise.
+    pub(crate) fn get_vertex_triangles(&self, a : usize) -> Vec<usize> {
+        let start = self.vertex_triangles[a];
+        let mut result = vec![start];
+        let mut t = start;
+        loop {
+            let (_, y) = self.get_opposite_edge(t, a);
+            match self.edges.get(&(a, y)) {
+                Some(&n) if n == start => return result,
+                Some(&n) => {
+                    result.push(n);
+                    t = n;
+                },
+                None => break
+            }
+        }
+
+        t = start;
+        loop {
+            let (x, _) = self.get_opposite_edge(t, a);
+            match self.edges.get(&(x, a)) {
+                Some(&n) => {
+                    result.push(n);
+                    t = n;
+                },
+                None => return result
+            }
+        }
+    }
+
+    // ребро треугольника t напротив вершины a, треугольник равен (a, x, y)
+    fn get_opposite_edge(&self, t : usize, a : usize) -> (usize, usize) {
+        let t = self.triangles[t];
+        let k = t.iter().position(|&v| v == a).unwrap();
+        return (t[(k + 1) % 3], t[(k + 2) % 3]);
+    }
+
+    fn make_delaunay(&mut self, mut stack : Vec<(usize, usize)>) {
+        while let Some((a, b)) = stack.pop() {
+            if self.is_constrained(a, b) {
+                continue;
+            }
+            let (t1, t2) = match (self.edges.get(&(a, b)), self.edges.get(&(b, a))) {
+                (Some(&t1), Some(&t2)) => (t1, t2),
+                _ => continue
+            };
+            let c = self.third(t1, a, b);
+            let d = self.third(t2, b, a);
+            if incircle(&self.points[a], &self.points[b], &self.points[c], &self.points[d]) == Ordering::Greater {
+                self.flip(a, b);
+                stack.extend(vec![(a, d), (d, b), (b, c), (c, a)]);
+            }
+        }
+    }
+
+    fn flip(&mut self, a : usize, b : usize) {
+        // (a, b, c) и (b, a, d) заменяются на (a, d, c) и (d, b, c)
+        let t1 = self.edges[&(a, b)];
+        let t2 = self.edges[&(b, a)];
+        let c = self.third(t1, a, b);
+        let d = self.third(t2, b, a);
+        self.edges.remove(&(a, b));
+        self.edges.remove(&(b, a));
+        self.set_triangle(t1, [a, d, c]);
+        self.set_triangle(t2, [d, b, c]);
+    }
+
+    fn crosses(&self, a : usize, b : usize, c : usize, d : usize) -> bool {
+        return segments_cross(&self.points[a], &self.points[b], &self.points[c], &self.points[d]);
+    }
+
+    fn third(&self, t : usize, a : usize, b : usize) -> usize {
+        return *self.triangles[t].iter().find(|&&v| v != a && v != b).unwrap();
+    }
+
+    fn add_triangle(&mut self, t : [usize; 3]) {
+        self.triangles.push(t);
+        let i = self.triangles.len() - 1;
+        self.set_triangle(i, t);
+    }
+
+    fn set_triangle(&mut self, i : usize, t : [usize; 3]) {
+        self.triangles[i] = t;
+        let n = self.points.len();
+        self.vertex_triangles.resize(n, 0);
+        for j in 0..3 {
+            self.edges.insert((t[j], t[(j + 1) % 3]), i);
+            self.vertex_triangles[t[j]] = i;
+        }
+    }
+}
+
+fn edge_key(a : usize, b : usize) -> (usize, usize) {
+    return if a < b {(a, b)} else {(b, a)};
+}
+
+fn segments_cross(a : &Point, b : &Point, c : &Point, d : &Point) -> bool {
+    let o1 = orient2d(a, b, c);
+    let o2 = orient2d(a, b, d);
+    let o3 = orient2d(c, d, a);
+    let o4 = orient2d(c, d, b);
+    return o1 != Ordering::Equal && o2 != Ordering::Equal && o1 != o2 &&
+        o3 != Ordering::Equal && o4 != Ordering::Equal && o3 != o4;
+}
+
+fn crossing_point(a : &Point, b : &Point, c : &Point, d : &Point) -> Point {
+    // z интерполируется вдоль первого отрезка
+    let ab : Vector = b - a;
+    let ac : Vector = c - a;
+    let cd : Vector = d - c;
+    let t = (&ac.x * &cd.y - &ac.y * &cd.x) / (&ab.x * &cd.y - &ab.y * &cd.x);
+    return a.clone() + ab * t;
+}
+
+// заметание по x: с отрезком проверяются только отрезки, проекции которых на оси x и y пересекаются с его проекциями.
+// Каждый отрезок разрезается сразу во всех точках пересечения, части сохраняют направление и номер исходного отрезка
+fn split_crossing_segments(mut segs : Vec<(Point, Point, usize)>) -> Vec<(Point, Point, usize)> {
+    loop {
+        let mut order : Vec<usize> = (0..segs.len()).collect();
+        order.sort_by(|&i, &j| get_range(&segs[i].0.x, &segs[i].1.x).0.cmp(get_range(&segs[j].0.x, &segs[j].1.x).0));
+
+        let mut cuts : Vec<Vec<Point>> = vec![Vec::new(); segs.len()];
+        let mut active : Vec<usize> = Vec::new();
+        let mut found = false;
+        for &i in order.iter() {
+            let (a, b) = (&segs[i].0, &segs[i].1);
+            let (min_x, _) = get_range(&a.x, &b.x);
+            let (min_y, max_y) = get_range(&a.y, &b.y);
+            active.retain(|&j| get_range(&segs[j].0.x, &segs[j].1.x).1 >= min_x);
+            for &j in active.iter() {
+                let (c, d) = (&segs[j].0, &segs[j].1);
+                let (c_min_y, c_max_y) = get_range(&c.y, &d.y);
+                if c_min_y <= max_y && min_y <= c_max_y && segments_cross(a, b, c, d) {
+                    let x = crossing_point(a, b, c, d);
+                    cuts[i].push(x.clone());
+                    cuts[j].push(x);
+                    found = true;
+                }
+            }
+            active.push(i);
+        }
+        if !found {
+            return segs;
+        }
+
+        let mut result : Vec<(Point, Point, usize)> = Vec::new();
+        for ((a, b, k), mut xs) in segs.into_iter().zip(cuts.into_iter()) {
+            xs.sort();
+            xs.dedup();
+            if a > b {
+                xs.reverse();
+            }
+            let mut prev = a;
+            for x in xs {
+                result.push((prev, x.clone(), k));
+                prev = x;
+            }
+            result.push((prev, b, k));
+        }
+        segs = result;
+    }
+}
+
+fn get_range<'a>(a : &'a Number, b : &'a Number) -> (&'a Number, &'a Number) {
+    return if a < b {(a, b)} else {(b, a)};
+}
+
+
+// обход в ширину 0-1 по элементам разбиения: пересечение границы меняет четность глубины,
+// элементы нечетной глубины лежат внутри области. seeds - начальные элементы с их глубинами,
+// neighbours возвращает соседей элемента и признак того, что между ними лежит граница
+pub(crate) fn get_parity_domain<F>(n : usize, seeds : Vec<(usize, usize)>, neighbours : F) -> Vec<bool>
+    where F : Fn(usize) -> Vec<(usize, bool)>
+{
+    let mut depth : Vec<Option<usize>> = vec![None; n];
+    let mut queue : VecDeque<(usize, usize)> = seeds.into_iter().collect();
+
+    while let Some((i, d)) = queue.pop_front() {
+        if depth[i].map_or(false, |cur| cur <= d) {
+            continue;
+        }
+        depth[i] = Some(d);
+        for (n, is_boundary) in neighbours(i) {
+            if is_boundary {
+                queue.push_back((n, d + 1));
+            } else {
+                queue.push_front((n, d));
+            }
+        }
+    }
+
+    return depth.into_iter().map(|d| d.map_or(false, |d| d % 2 == 1)).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use primitives::*;
+    use primitives::predicates::{orient2d, incircle};
+    use triangulation::*;
+    use triangulation::constrained_delaunay_triangulation::ConstrainedTriangulation;
+
+    fn p(x : f64, y : f64) -> Point {
+        return Point::new_from_f64(x, y, 0.);
+    }
+
+    fn has_edge(ts : &Vec<Triangle>, a : &Point, b : &Point) -> bool {
+        return ts.iter().any(|t| {
+            let ps = t.get_points_ref();
+            (0..3).any(|i| (&ps[i] == a && &ps[(i + 1) % 3] == b) || (&ps[i] == b && &ps[(i + 1) % 3] == a))
+        });
+    }
+
+    fn area2(ts : &Vec<Triangle>) -> Number {
+        let mut s = Number::zero();
+        for t in ts {
+            let ps = t.get_points_ref();
+            let u : Vector = &ps[1] - &ps[0];
+            let v : Vector = &ps[2] - &ps[0];
+            s = s + (&u.x * &v.y - &u.y * &v.x);
+        }
+        return s;
+    }
+
+    #[test]
+    fn delaunay_without_constraints() {
+        let points = vec![p(0., 0.), p(2., 0.), p(2., 2.), p(0., 2.), p(1., 1.), p(3., 1.)];
+        let ts = constrained_delaunay_triangulation::triangulate2d(points.clone(), vec![]).unwrap();
+        assert_eq!(ts.len(), 5);
+        assert_eq!(area2(&ts), Number::new(10.));
+        for t in ts.iter() {
+            let ps = t.get_points_ref();
+            assert_eq!(orient2d(&ps[0], &ps[1], &ps[2]), Ordering::Greater);
+            for q in points.iter() {
+                assert!(incircle(&ps[0], &ps[1], &ps[2], q) != Ordering::Greater);
+            }
+        }
+    }
+
+    #[test]
+    fn collinear_points() {
+        let points = vec![p(0., 0.), p(1., 1.), p(2., 2.)];
+        let ts = constrained_delaunay_triangulation::triangulate2d(points, vec![]).unwrap();
+        assert!(ts.is_empty());
+
+        let points = vec![p(0., 0.), p(1., 0.), p(2., 0.), p(3., 0.), p(1., -1.)];
+        let ts = constrained_delaunay_triangulation::triangulate2d(points, vec![]).unwrap();
+        assert_eq!(ts.len(), 3);
+        assert_eq!(area2(&ts), Number::new(3.));
+    }
+
+    #[test]
+    fn constraint_is_an_edge() {
+        // ограничение пересекает ребро Делоне
+        let points = vec![p(0., 0.), p(4., -1.), p(8., 0.), p(4., 1.)];
+        let ts = constrained_delaunay_triangulation::triangulate2d(points.clone(), vec![]).unwrap();
+        assert!(has_edge(&ts, &p(4., -1.), &p(4., 1.)));
+
+        let ts = constrained_delaunay_triangulation::triangulate2d(points, vec![Segment::new(p(0., 0.), p(8., 0.))]).unwrap();
+        assert_eq!(ts.len(), 2);
+        assert!(has_edge(&ts, &p(0., 0.), &p(8., 0.)));
+    }
+
+    #[test]
+    fn crossing_and_touching_constraints() {
+        let points = vec![p(0., 0.), p(4., 0.), p(4., 4.), p(0., 4.)];
+        let constraints = vec![
+            Segment::new(p(1., 1.), p(3., 3.)),
+            Segment::new(p(1., 3.), p(3., 1.)),
+            Segment::new(p(0., 2.), p(4., 2.)),
+        ];
+        let ts = constrained_delaunay_triangulation::triangulate2d(points, constraints).unwrap();
+        assert_eq!(area2(&ts), Number::new(32.));
+        assert!(has_edge(&ts, &p(1., 1.), &p(2., 2.)));
+        assert!(has_edge(&ts, &p(2., 2.), &p(3., 3.)));
+        assert!(has_edge(&ts, &p(1., 3.), &p(2., 2.)));
+        assert!(has_edge(&ts, &p(0., 2.), &p(2., 2.)));
+        assert!(has_edge(&ts, &p(2., 2.), &p(4., 2.)));
+        assert!(!has_edge(&ts, &p(0., 2.), &p(4., 2.)));
+    }
+
+    #[test]
+    fn insert_point_splits_constraint() {
+        let points = vec![p(0., 0.), p(4., 0.), p(2., 3.)];
+        let mut ct = ConstrainedTriangulation::new(points, vec![Segment::new(p(0., 0.), p(4., 0.))]).unwrap();
+        let i = ct.insert_point(p(2., 0.), 0).unwrap();
+        assert!(ct.is_constrained(0, i));
+        assert!(!ct.is_constrained(0, 1));
+        assert_eq!(ct.get_index_triangles().len(), 2);
+        assert_eq!(ct.insert_point(p(2., 0.), 0), Some(i));
+        assert_eq!(ct.insert_point(p(5., 5.), 0), None);
+        ct.insert_point(p(2., 1.), 0).unwrap();
+        assert_eq!(area2(&ct.get_triangles()), Number::new(12.));
+    }
+
+    #[test]
+    fn triangulate_ptree3d_constrained_delaunay() {
+        let t = Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(0., 4., 4.),
+            Point::new_from_f64(4., 0., 4.),
+        ]);
+        let normal = t.get_normal();
+        let ss = vec![
+            Segment::new(Point::new_from_f64(1., 1., 2.), Point::new_from_f64(2., 1., 3.)),
+            Segment::new(Point::new_from_f64(2., 1., 3.), Point::new_from_f64(2., 2., 4.)),
+            Segment::new(Point::new_from_f64(0., 2., 2.), Point::new_from_f64(1., 1., 2.)),
+        ];
+
+        let ts = triangulate_ptree3d(t, ss, TriangulationAlgorithm::ConstrainedDelaunay).unwrap();
+        assert!(ts.len() > 4);
+        for t in ts.iter() {
+            assert!(t.get_normal().dot_product(&normal).is_it_positive());
+        }
+        assert!(has_edge(&ts, &Point::new_from_f64(2., 1., 3.), &Point::new_from_f64(2., 2., 4.)));
+        assert!(has_edge(&ts, &Point::new_from_f64(0., 0., 0.), &Point::new_from_f64(0., 2., 2.)));
+    }
+
+    #[test]
+    fn triangulate3d_constrained_delaunay() {
+        let points = vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(1., 0., 1.),
+            Point::new_from_f64(0., 0., 1.),
+            Point::new_from_f64(0.5, 0., 0.5),
+        ];
+        let plane = Plane::new(Vector::new_from_f64(0., -1., 0.), Point::new_from_f64(0., 0., 0.));
+        let ts = triangulate3d(points, plane, TriangulationAlgorithm::ConstrainedDelaunay).unwrap();
+        assert_eq!(ts.len(), 4);
+        for t in ts.iter() {
+            assert!(t.get_normal().y.is_it_negative());
+        }
+    }
+
+    #[test]
+    fn unsupported_algorithms() {
+        let t = Triangle::new(vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(0., 1., 0.),
+        ]);
+        let ss = vec![Segment::new(Point::new_from_f64(0., 0., 0.), Point::new_from_f64(0.25, 0.25, 0.))];
+        assert!(triangulate_ptree3d(t, ss, TriangulationAlgorithm::Incremental).is_err());
+
+        let points = vec![
+            Point::new_from_f64(0., 0., 0.),
+            Point::new_from_f64(1., 0., 0.),
+            Point::new_from_f64(1., 1., 0.),
+            Point::new_from_f64(0., 1., 0.),
+        ];
+        let plane = Plane::new(Vector::new_from_f64(0., 0., 1.), Point::new_from_f64(0., 0., 0.));
+        assert!(triangulate3d(points[..2].to_vec(), plane.clone(), TriangulationAlgorithm::Incremental).is_err());
+        assert!(triangulate3d(points, plane, TriangulationAlgorithm::EarClipping).is_err());
+    }
+}
@@ -0,0 +1,410 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use primitives::*;
+use primitives::predicates::incircle;
+use triangulation::constrained_delaunay_triangulation::ConstrainedTriangulation;
+
+/// The largest minimum angle bound (in degrees), for which the refinement is guaranteed to terminate.
+pub const MAX_MIN_ANGLE : f64 = 20.7;
+
+/// This function builds a quality triangulation of the planar region bounded by `polygon_tree` in the plane xy.
+/// It's Ruppert's Delaunay refinement: Steiner points are inserted at circumcenters of bad triangles and at
+/// splitting points of encroached boundary segments of the constrained Delaunay triangulation.
+/// Segments adjacent to input vertices are split on concentric shells and triangles, whose shortest edge
+/// connects such shells at a small input angle, aren't refined by the angle criterion, so the refinement terminates
+/// for every input. Other triangles have all angles not less than `min_angle` and the area not greater than `max_area`.
+/// Predicates are exact, splitting points lie exactly on segments for rational backends of `Number`.
+/// # Arguments
+///
+/// * `polygon_tree` - The outer loop with holes, children of holes are islands of the region.
+/// * `min_angle` - Minimum angle bound in degrees, from 0 to `MAX_MIN_ANGLE`.
+/// * `max_area` - Optional maximum area of triangles.
+/// # Examples
+///
+/// ```
+/// extern crate geometry_kernel;
+/// use geometry_kernel::primitives::number::{Number, NumberTrait};
+/// use geometry_kernel::primitives::point::Point;
+/// use geometry_kernel::primitives::polygon::{Polygon, PolygonTreeNode};
+/// use geometry_kernel::primitives::vector::Vector;
+/// use geometry_kernel::refine2d;
+///
+/// fn main() {
+///   let square = Polygon::new(vec![
+///     Point::new_from_f64(0., 0., 0.),
+///     Point::new_from_f64(1., 0., 0.),
+///     Point::new_from_f64(1., 1., 0.),
+///     Point::new_from_f64(0., 1., 0.)
+///   ], Vector::new_from_f64(0., 0., 1.));
+///   let ts = refine2d(PolygonTreeNode::new(square), 20., Some(Number::new(0.01))).unwrap();
+///   assert!(ts.len() >= 100);
+///   assert!(ts.iter().all(|t| t.get_area() <= 0.01));
+/// }
+/// ```
+pub fn refine2d(polygon_tree : PolygonTreeNode, min_angle : f64, max_area : Option<Number>) -> Result<Vec<Triangle>, &'static str> {
+    if !(min_angle >= 0. && min_angle <= MAX_MIN_ANGLE) {
+        return Err("Minimum angle bound has to be between 0 and 20.7 degrees!");
+    }
+    if let Some(ref area) = max_area {
+        if !area.is_it_positive() {
+            return Err("Maximum area bound has to be positive!");
+        }
+    }
+
+    let mut points : Vec<Point> = Vec::new();
+    let mut segments : Vec<Segment> = Vec::new();
+    let mut nodes : Vec<PolygonTreeNode> = vec![polygon_tree];
+    while let Some(node) = nodes.pop() {
+        let ps = node.get_cur_polygon().get_points();
+        if ps.len() < 3 {
+            return Err("Polygon has less than 3 points!");
+        }
+        for i in 0..ps.len() {
+            segments.push(Segment::new(ps[i].clone(), ps[(i + 1) % ps.len()].clone()));
+        }
+        points.extend(ps);
+        nodes.extend(node.split_tree());
+    }
+
+    let sin_angle = min_angle.to_radians().sin();
+    let mut refiner = Refiner {
+        ct : ConstrainedTriangulation::new(points, segments)?,
+        input_count : 0,
+        origins : HashMap::new(),
+        sin2_bound : Number::new(sin_angle * sin_angle),
+        max_double_area : max_area.map(|a| a * Number::new(2.))
+    };
+    refiner.input_count = refiner.ct.get_points().len();
+    if refiner.ct.get_index_triangles().is_empty() {
+        return Err("Region is degenerate!");
+    }
+
+    refiner.refine()?;
+
+    let inside = refiner.ct.get_domain();
+    let ts = refiner.ct.get_triangles();
+    return Ok(ts.into_iter().zip(inside).filter(|&(_, inside)| inside).map(|(t, _)| t).collect());
+}
+
+struct Refiner {
+    ct : ConstrainedTriangulation,
+    // вершины с меньшими индексами заданы на входе
+    input_count : usize,
+    // исходный отрезок (пара входных вершин), на котором лежит точка Штейнера
+    origins : HashMap<usize, (usize, usize)>,
+    sin2_bound : Number,
+    max_double_area : Option<Number>
+}
+
+impl Refiner {
+    fn refine(&mut self) -> Result<(), &'static str> {
+        self.split_encroached_segments();
+        loop {
+            let inside = self.ct.get_domain();
+            let bad : Vec<[usize; 3]> = self.ct.get_index_triangles().iter()
+                .zip(inside)
+                .filter(|&(t, inside)| inside && self.is_bad(t))
+                .map(|(t, _)| *t)
+                .collect();
+
+            if bad.is_empty() {
+                return Ok(());
+            }
+
+            for t in bad {
+                // треугольник мог исчезнуть после предыдущих вставок
+                let ti = match self.ct.get_edge_triangle(t[0], t[1]) {
+                    Some(i) if self.ct.get_index_triangles()[i][..].contains(&t[2]) => i,
+                    _ => continue
+                };
+
+                let center = self.ct.get_circumcenter(&t);
+                let encroached : Vec<(usize, usize)> = self.get_segments_near(&center, ti).into_iter()
+                    .filter(|&(u, v)| self.is_in_diametral_circle(u, v, &center))
+                    .collect();
+
+                let mut inserted : Vec<usize> = Vec::new();
+                if encroached.is_empty() {
+                    let n = self.ct.get_points().len();
+                    match self.ct.insert_point(center, ti) {
+                        Some(i) if i >= n => inserted.push(i),
+                        _ => return Err("Steiner point can't be inserted into the triangulation!")
+                    }
+                } else {
+                    for (u, v) in encroached {
+                        if self.ct.is_constrained(u, v) {
+                            inserted.push(self.split_segment(u, v));
+                        }
+                    }
+                }
+                self.split_segments_encroached_by(inserted);
+            }
+        }
+    }
+
+    fn split_encroached_segments(&mut self) {
+        let mut inserted : Vec<usize> = Vec::new();
+        for (u, v) in self.ct.get_constraints() {
+            if self.ct.is_constrained(u, v) && self.is_encroached(u, v) {
+                inserted.push(self.split_segment(u, v));
+            }
+        }
+        self.split_segments_encroached_by(inserted);
+    }
+
+    // после вставки вершины меняются только треугольники вокруг неё, поэтому новые вершины
+    // могут угрожать лишь отрезкам, противолежащим им в этих треугольниках
+    fn split_segments_encroached_by(&mut self, mut vertices : Vec<usize>) {
+        while let Some(w) = vertices.pop() {
+            let segments : Vec<(usize, usize)> = self.ct.get_vertex_triangles(w).into_iter()
+                .map(|i| self.ct.get_index_triangles()[i])
+                .filter_map(|t| {
+                    let j = t.iter().position(|&a| a == w).unwrap();
+                    let (u, v) = (t[(j + 1) % 3], t[(j + 2) % 3]);
+                    if self.ct.is_constrained(u, v) {Some((u, v))} else {None}
+                })
+                .collect();
+            for (u, v) in segments {
+                if self.ct.is_constrained(u, v) && self.is_encroached(u, v) {
+                    vertices.push(self.split_segment(u, v));
+                    vertices.push(w);
+                    break;
+                }
+            }
+        }
+    }
+
+    // ограничения на границе полости точки p - треугольников, описанные окружности которых её содержат.
+    // Если p не видна из треугольника ti, она может угрожать любому ограничению
+    fn get_segments_near(&self, p : &Point, ti : usize) -> Vec<(usize, usize)> {
+        let start = match self.ct.locate_point(p, ti, true) {
+            Some((i, _)) => i,
+            None => return self.ct.get_constraints()
+        };
+
+        let points = self.ct.get_points();
+        let triangles = self.ct.get_index_triangles();
+        let mut segments : Vec<(usize, usize)> = Vec::new();
+        let mut visited : HashSet<usize> = HashSet::new();
+        visited.insert(start);
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            let t = triangles[i];
+            for j in 0..3 {
+                let (a, b) = (t[j], t[(j + 1) % 3]);
+                if self.ct.is_constrained(a, b) {
+                    segments.push((a, b));
+                    continue;
+                }
+                if let Some(n) = self.ct.get_edge_triangle(b, a) {
+                    let tn = triangles[n];
+                    if !visited.contains(&n) && incircle(&points[tn[0]], &points[tn[1]], &points[tn[2]], p) == Ordering::Greater {
+                        visited.insert(n);
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        return segments;
+    }
+
+    fn is_encroached(&self, u : usize, v : usize) -> bool {
+        let points = self.ct.get_points();
+        for &(a, b) in [(u, v), (v, u)].iter() {
+            if let Some(i) = self.ct.get_edge_triangle(a, b) {
+                let t = self.ct.get_index_triangles()[i];
+                let w = *t.iter().find(|&&w| w != a && w != b).unwrap();
+                if self.is_in_diametral_circle(u, v, &points[w]) {
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+
+    fn is_in_diametral_circle(&self, u : usize, v : usize, p : &Point) -> bool {
+        let points = self.ct.get_points();
+        let a : Vector = &points[u] - p;
+        let b : Vector = &points[v] - p;
+        return (&a.x * &b.x + &a.y * &b.y).is_it_negative();
+    }
+
+    fn split_segment(&mut self, u : usize, v : usize) -> usize {
+        let (u_input, v_input) = (u < self.input_count, v < self.input_count);
+        let origin = if u_input && v_input {
+            (u, v)
+        } else if u_input {
+            self.origins[&v]
+        } else {
+            self.origins[&u]
+        };
+
+        let p = {
+            let points = self.ct.get_points();
+            if u_input != v_input {
+                // концентрические оболочки: расстояние от входной вершины равно степени двойки
+                let (a, b) = if u_input {(&points[u], &points[v])} else {(&points[v], &points[u])};
+                let e : Vector = b - a;
+                let len = (&e.x * &e.x + &e.y * &e.y).convert_to_f64().sqrt();
+                let mut shell = 2f64.powi(len.log2().floor() as i32);
+                while shell > len * 2. / 3. {
+                    shell /= 2.;
+                }
+                while shell < len / 3. {
+                    shell *= 2.;
+                }
+                a.clone() + e * Number::new(shell / len)
+            } else {
+                let e : Vector = &points[v] - &points[u];
+                points[u].clone() + e * Number::new(0.5)
+            }
+        };
+
+        let i = self.ct.split_constraint(u, v, p);
+        self.origins.insert(i, origin);
+        return i;
+    }
+
+    fn is_bad(&self, t : &[usize; 3]) -> bool {
+        let ps = self.ct.get_points();
+        let (a, b, c) = (&ps[t[0]], &ps[t[1]], &ps[t[2]]);
+        let ab : Vector = b - a;
+        let ac : Vector = c - a;
+        let bc : Vector = c - b;
+        let cross = &ab.x * &ac.y - &ab.y * &ac.x;
+
+        if let Some(ref max_double_area) = self.max_double_area {
+            if &cross > max_double_area {
+                return true;
+            }
+        }
+
+        // sin(min angle) = l_min * cross / (l_a * l_b * l_c)
+        let squared = |v : &Vector| &v.x * &v.x + &v.y * &v.y;
+        let lens = [squared(&bc), squared(&ac), squared(&ab)];
+        let (k, l_min) = lens.iter().enumerate().min_by(|x, y| x.1.cmp(y.1)).unwrap();
+        let skinny = l_min * &(&cross * &cross) < &self.sin2_bound * &(&(&lens[0] * &lens[1]) * &lens[2]);
+        if !skinny {
+            return false;
+        }
+
+        // кратчайшее ребро противолежит вершине k
+        return !self.is_seditious(t[(k + 1) % 3], t[(k + 2) % 3]);
+    }
+
+    fn is_seditious(&self, p : usize, q : usize) -> bool {
+        // ребро между точками на одной оболочке двух отрезков, сходящихся под углом меньше 60 градусов
+        let (s1, s2) = match (self.origins.get(&p), self.origins.get(&q)) {
+            (Some(s1), Some(s2)) => (*s1, *s2),
+            _ => return false
+        };
+        if s1 == s2 || s1 == (s2.1, s2.0) {
+            return false;
+        }
+
+        let apex = if s1.0 == s2.0 || s1.0 == s2.1 {
+            s1.0
+        } else if s1.1 == s2.0 || s1.1 == s2.1 {
+            s1.1
+        } else {
+            return false;
+        };
+        let other1 = if s1.0 == apex {s1.1} else {s1.0};
+        let other2 = if s2.0 == apex {s2.1} else {s2.0};
+
+        let ps = self.ct.get_points();
+        let e1 : Vector = &ps[other1] - &ps[apex];
+        let e2 : Vector = &ps[other2] - &ps[apex];
+        let dot = &e1.x * &e2.x + &e1.y * &e2.y;
+        let squared = |v : &Vector| &v.x * &v.x + &v.y * &v.y;
+        if !dot.is_it_positive() || &dot * &dot * Number::new(4.) <= squared(&e1) * squared(&e2) {
+            return false;
+        }
+
+        let d1 = squared(&(&ps[p] - &ps[apex])).convert_to_f64();
+        let d2 = squared(&(&ps[q] - &ps[apex])).convert_to_f64();
+        return (d1 - d2).abs() <= 1e-6 * d1.max(d2);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use triangulation::*;
+
+    fn p(x : f64, y : f64) -> Point {
+        return Point::new_from_f64(x, y, 0.);
+    }
+
+    fn polygon(ps : Vec<Point>) -> Polygon {
+        return Polygon::new(ps, Vector::new_from_f64(0., 0., 1.));
+    }
+
+    fn min_angle(t : &Triangle) -> f64 {
+        let ps : Vec<(f64, f64)> = t.get_points_ref().iter()
+            .map(|p| (p.x.clone().convert_to_f64(), p.y.clone().convert_to_f64()))
+            .collect();
+        let mut res : f64 = 180.;
+        for i in 0..3 {
+            let (a, b, c) = (ps[i], ps[(i + 1) % 3], ps[(i + 2) % 3]);
+            let (ux, uy, vx, vy) = (b.0 - a.0, b.1 - a.1, c.0 - a.0, c.1 - a.1);
+            let cos = (ux * vx + uy * vy) / ((ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt());
+            res = res.min(cos.acos().to_degrees());
+        }
+        return res;
+    }
+
+    fn total_area(ts : &Vec<Triangle>) -> Number {
+        let mut s = Number::zero();
+        for t in ts {
+            let ps = t.get_points_ref();
+            let u : Vector = &ps[1] - &ps[0];
+            let v : Vector = &ps[2] - &ps[0];
+            s = s + (&u.x * &v.y - &u.y * &v.x) * Number::new(0.5);
+        }
+        return s;
+    }
+
+    #[test]
+    fn refinement_of_square() {
+        let tree = PolygonTreeNode::new(polygon(vec![p(0., 0.), p(1., 0.), p(1., 1.), p(0., 1.)]));
+        let ts = delaunay_refinement::refine2d(tree, 20., Some(Number::new(0.01))).unwrap();
+        assert!(ts.len() >= 100);
+        assert_eq!(total_area(&ts), Number::new(1.));
+        for t in ts.iter() {
+            assert!(min_angle(t) >= 20. - 1e-6);
+            assert!(t.get_normal().z.is_it_positive());
+        }
+    }
+
+    #[test]
+    fn refinement_of_square_with_hole_and_island() {
+        let mut tree = PolygonTreeNode::new(polygon(vec![p(0., 0.), p(4., 0.), p(4., 4.), p(0., 4.)]));
+        let mut hole = PolygonTreeNode::new(polygon(vec![p(1., 1.), p(1., 3.), p(3., 3.), p(3., 1.)]));
+        hole.add_child(PolygonTreeNode::new(polygon(vec![p(1.5, 1.5), p(2.5, 1.5), p(2., 2.5)])));
+        tree.add_child(hole);
+
+        let ts = delaunay_refinement::refine2d(tree, 20.7, None).unwrap();
+        assert_eq!(total_area(&ts), Number::new(12.5));
+        for t in ts.iter() {
+            assert!(min_angle(t) >= 20.7 - 1e-6);
+        }
+    }
+
+    #[test]
+    fn refinement_with_small_input_angle() {
+        // угол 5 градусов при вершине (0, 0)
+        let tree = PolygonTreeNode::new(polygon(vec![p(0., 0.), p(10., 0.), p(10., 0.875)]));
+        let ts = delaunay_refinement::refine2d(tree, 20., Some(Number::new(0.5))).unwrap();
+        assert!(ts.len() > 10);
+        assert_eq!(total_area(&ts), Number::new(4.375));
+    }
+
+    #[test]
+    fn unsupported_angle_bound() {
+        let tree = PolygonTreeNode::new(polygon(vec![p(0., 0.), p(1., 0.), p(0., 1.)]));
+        assert!(delaunay_refinement::refine2d(tree.clone(), 30., None).is_err());
+        assert!(delaunay_refinement::refine2d(tree, 20., Some(Number::new(0.))).is_err());
+    }
+}
@@ -35,6 +35,23 @@ pub fn triangulate2d(polygon_tree : PolygonTreeNode) -> Vec<Triangle> {
 }
 
 
+// триангуляция области, ограниченной внешним полигоном boundary с дырками holes (без вложенных островов),
+// направления обхода полигонов приводятся к ожидаемым: против часовой стрелки в плоскости xy
+pub(crate) fn triangulate_polygon_with_holes(mut boundary: Polygon, holes: Vec<Polygon>) -> Vec<Triangle> {
+    if boundary.signed_area().is_it_negative() {
+        boundary.reverse_order();
+    }
+
+    let holes = holes.into_iter().map(|mut hole| {
+        if hole.signed_area().is_it_negative() {
+            hole.reverse_order();
+        }
+        hole
+    }).collect();
+
+    return simple_triangulation(boundary, holes);
+}
+
 fn update_convex_and_reflex_vs(
     cur_index: &usize,
     convex_vs : &mut BTreeSet<usize>,
@@ -140,7 +140,7 @@ fn get_segment_normal(s : &Segment, plane: &Plane) -> Line {
     let normal = plane.get_ref_normal();
     let d = plane.get_ref_d();
 
-    let mut a : Matrix<Number> = Matrix::new_from_vector(
+    let a : Matrix<Number> = Matrix::new_from_vector(
         vec![Row::new_from_vector(vec![normal.x.clone(), normal.y.clone(), normal.z.clone()]),
              Row::new_from_vector(vec![e.x.clone(), e.y.clone(), e.z.clone()]),
              Row::new_from_vector(vec![-e.y.clone(), e.x.clone(), Number::new(0.)])]);
@@ -150,7 +150,7 @@ fn get_segment_normal(s : &Segment, plane: &Plane) -> Line {
              &e.x*&point_m.x + &e.y*&point_m.y + &e.z*&point_m.z,
              &e.x*&s.org.y - &e.y*&s.org.x - Number::new(1.)]);
 
-    let x = a.solve(y);
+    let x = a.solve(y).expect("Singular matrix!");
     //println!("{}", x);
     let mut vec_l : Vec<Number> = x.convert_to_vec();
     let point_l = Point::new(vec_l.remove(0), vec_l.remove(0), vec_l.remove(0));
@@ -1,7 +1,9 @@
 
 pub mod incremental_triangulation;
 pub mod ear_clipping_triangulation;
+pub mod constrained_delaunay_triangulation;
+pub mod delaunay_refinement;
 pub mod triangulation3d;
 
-pub use self::triangulation3d::{triangulate3d, triangulate_ptree3d};
+pub use self::triangulation3d::{triangulate3d, triangulate_ptree3d, triangulate_polygon3d};
 pub use self::triangulation3d::TriangulationAlgorithm;
\ No newline at end of file
@@ -1,17 +1,39 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
 use primitives::*;
+use primitives::predicates::orient2d;
 use triangulation::incremental_triangulation;
 use triangulation::ear_clipping_triangulation;
+use triangulation::constrained_delaunay_triangulation;
+use triangulation::constrained_delaunay_triangulation::ConstrainedTriangulation;
 
 use log::LogLevel;
 // use time::PreciseTime;
 
+/// Algorithms of planar triangulation.
+/// `Incremental` triangulates a set of points, `EarClipping` triangulates polygons formed by a triangle and segments,
+/// `ConstrainedDelaunay` does both with the exact constrained Delaunay triangulation.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TriangulationAlgorithm {
-    Incremental
+    Incremental,
+    EarClipping,
+    ConstrainedDelaunay
 }
 
-pub fn triangulate_ptree3d(mut t: Triangle, mut ss: Vec<Segment>) -> Vec<Triangle> {
+/// This function triangulates the triangle `t` cut by the segments `ss`. The segments become edges of the result.
+/// Triangles have the same orientation as `t`. It returns an error for `Incremental`, which doesn't support segments.
+/// # Arguments
+///
+/// * `t` - A triangle to triangulate.
+/// * `ss` - Segments lying in `t`.
+/// * `alg` - `EarClipping` or `ConstrainedDelaunay`.
+pub fn triangulate_ptree3d(mut t: Triangle, mut ss: Vec<Segment>, alg: TriangulationAlgorithm) -> Result<Vec<Triangle>, &'static str> {
+    if let &TriangulationAlgorithm::Incremental = &alg {
+        return Err("Incremental triangulation doesn't support segments!");
+    }
+
     if ss.len() == 0 {
-        return vec![t];
+        return Ok(vec![t]);
     }
 
     let mut plane: Plane = Plane::new(t.get_normal(), t.get(0));
@@ -35,13 +57,26 @@ pub fn triangulate_ptree3d(mut t: Triangle, mut ss: Vec<Segment>) -> Vec<Triangl
     }
 
 
-    let p_trees: Vec<PolygonTreeNode> = PolygonTreeNode::new_trees(t, ss);
     let mut mapped_ts : Vec<Triangle> = Vec::new();
-    for p_tree in p_trees {
-        mapped_ts.extend(ear_clipping_triangulation::triangulate2d(p_tree));
-    }
+    match &alg {
+        &TriangulationAlgorithm::EarClipping => {
+            let p_trees: Vec<PolygonTreeNode> = PolygonTreeNode::new_trees(t, ss);
+            for p_tree in p_trees {
+                mapped_ts.extend(ear_clipping_triangulation::triangulate2d(p_tree));
+            }
+        },
+        &TriangulationAlgorithm::ConstrainedDelaunay => {
+            // стороны треугольника тоже ограничения: на них лежат концы отрезков
+            let ps = t.get_points_ref().clone();
+            for i in 0..3 {
+                ss.push(Segment::new(ps[i].clone(), ps[(i + 1) % 3].clone()));
+            }
+            mapped_ts = constrained_delaunay_triangulation::triangulate2d(ps, ss)?;
+        },
+        &TriangulationAlgorithm::Incremental => return Err("Incremental triangulation doesn't support segments!")
+    };
 
-    return unmap_ts_new(orientation, normal_type, mapped_ts);
+    return Ok(unmap_ts_new(orientation, normal_type, mapped_ts));
 }
 
 fn unmap_ts_new(orientation : Number, normal_type : NormalType, mapped_ts : Vec<Triangle>) -> Vec<Triangle> {
@@ -90,12 +125,25 @@ fn unmap_ts(orientation : Number, normal_type : NormalType, mapped_ts : Vec<Tria
     return unmapped_ts;
 }
 
-pub fn triangulate3d(mut points : Vec<Point>, mut plane: Plane, alg: TriangulationAlgorithm) -> Vec<Triangle> {
+/// This function triangulates the set of points lying in the `plane`. Triangles are oriented as the `plane`.
+/// It returns an error if there are less than 3 points or `alg` is `EarClipping`, which requires a polygon.
+/// # Arguments
+///
+/// * `points` - Unique points lying in the `plane`.
+/// * `plane` - A plane of points.
+/// * `alg` - `Incremental` or `ConstrainedDelaunay`.
+pub fn triangulate3d(mut points : Vec<Point>, mut plane: Plane, alg: TriangulationAlgorithm) -> Result<Vec<Triangle>, &'static str> {
     // all points should be unique! Otherwise algorithm will hang out!
     debug!("Triangulation was started for points:");
     debug!("{:?}", points);
 
-    assert!(points.len() >= 3, "Not enough points. Only {0} points were supplied!", points.len());
+    if let &TriangulationAlgorithm::EarClipping = &alg {
+        return Err("Ear clipping triangulation requires a polygon!");
+    }
+
+    if points.len() < 3 {
+        return Err("Not enough points!");
+    }
 
     if points.len() ==  3 {
         let mut t = Triangle::new(points);
@@ -103,7 +151,7 @@ pub fn triangulate3d(mut points : Vec<Point>, mut plane: Plane, alg: Triangulati
         if dp.is_it_negative() {
             t.reverse();
         }
-        return vec![t];
+        return Ok(vec![t]);
     }
 
     if log_enabled!(LogLevel::Debug) {
@@ -126,12 +174,155 @@ pub fn triangulate3d(mut points : Vec<Point>, mut plane: Plane, alg: Triangulati
         check_points(&plane, &points);
     }
 
-    let mapped_ts : Vec<Triangle> = match &alg {
-        &TriangulationAlgorithm::Incremental => incremental_triangulation::triangulate2d(points, plane),
-        // _ => panic!("Wrong triangulation algorithm!")
+    return match &alg {
+        &TriangulationAlgorithm::Incremental => {
+            let mapped_ts = incremental_triangulation::triangulate2d(points, plane);
+            Ok(unmap_ts(orientation, normal_type, mapped_ts))
+        },
+        &TriangulationAlgorithm::ConstrainedDelaunay => {
+            // треугольники против часовой стрелки, как в triangulate_ptree3d
+            let mapped_ts = constrained_delaunay_triangulation::triangulate2d(points, Vec::new())?;
+            Ok(unmap_ts_new(orientation, normal_type, mapped_ts))
+        },
+        &TriangulationAlgorithm::EarClipping => Err("Ear clipping triangulation requires a polygon!")
     };
+}
+
+/// This function triangulates the planar polygon with holes, given by loops of 3D points, with the exact constrained
+/// Delaunay triangulation. Loops may have any orientation, they have to be simple and must not touch each other,
+/// holes have to lie inside the outer loop and outside each other. All points have to lie exactly in the plane
+/// through the first point of `outer` with the normal `normal`. Vertices of triangles are vertices of loops,
+/// triangles are counterclockwise looking from the end of `normal`, i.e. their normals are codirectional with it.
+/// # Arguments
+///
+/// * `outer` - Vertices of the outer loop.
+/// * `holes` - Vertices of holes.
+/// * `normal` - A normal of the plane of the polygon.
+pub fn triangulate_polygon3d(outer: &Vec<Point>, holes: &Vec<Vec<Point>>, normal: &Vector) -> Result<Vec<Triangle>, &'static str> {
+    if normal.is_zero() {
+        return Err("Normal vector cannot be zero!");
+    }
+    if outer.len() < 3 || holes.iter().any(|hole| hole.len() < 3) {
+        return Err("A loop has to have at least 3 points!");
+    }
+
+    let mut loops: Vec<Vec<Point>> = vec![outer.clone()];
+    loops.extend(holes.iter().cloned());
+
+    let plane = Plane::new(normal.clone(), outer[0].clone());
+    if loops.iter().any(|l| l.iter().any(|p| !plane.does_it_contain_point(p))) {
+        return Err("Points are not coplanar!");
+    }
+    let unique: BTreeSet<&Point> = loops.iter().flat_map(|l| l.iter()).collect();
+    if unique.len() != loops.iter().map(|l| l.len()).sum::<usize>() {
+        return Err("Loops have repeated points!");
+    }
+
+    // проекция вдоль оси, не параллельной плоскости, взаимно однозначна на плоскости
+    let normal_type : NormalType = classify_normal(normal);
+    for l in loops.iter_mut() {
+        map_points_to_2d(l, &normal_type);
+    }
+    let mut mapped_normal = normal.clone();
+    map_to_2d(&mut mapped_normal, &normal_type);
+
+    if !are_loops_simple(&loops) {
+        return Err("Loops have to be simple and must not touch each other!");
+    }
+    for i in 1..loops.len() {
+        if !is_inside_loop(&loops[i][0], &loops[0]) {
+            return Err("Holes have to lie inside the outer loop!");
+        }
+        if (1..loops.len()).any(|j| j != i && is_inside_loop(&loops[i][0], &loops[j])) {
+            return Err("Holes must not lie inside each other!");
+        }
+    }
 
-    return unmap_ts(orientation, normal_type, mapped_ts);
+    let mut constraints: Vec<Segment> = Vec::new();
+    for l in loops.iter() {
+        for i in 0..l.len() {
+            constraints.push(Segment::new(l[i].clone(), l[(i + 1) % l.len()].clone()));
+        }
+    }
+    let ct = ConstrainedTriangulation::new(Vec::new(), constraints)?;
+    let ps = ct.get_points();
+    let mapped_ts : Vec<Triangle> = ct.get_index_triangles().iter().zip(ct.get_domain())
+        .filter(|&(_, inside)| inside)
+        .map(|(t, _)| Triangle::new(vec![ps[t[0]].clone(), ps[t[1]].clone(), ps[t[2]].clone()]))
+        .collect();
+
+    let iv = Vector::new_from_f64(1., 0., 0.);
+    let jv = Vector::new_from_f64(0., 1., 0.);
+    let orientation : Number = iv.mixed_product(&jv, &mapped_normal);
+    return Ok(unmap_ts_new(orientation, normal_type, mapped_ts));
+}
+
+// отрезки петель не пересекаются и не касаются, кроме соседних в одной петле, которые не налегают друг на друга
+fn are_loops_simple(loops: &Vec<Vec<Point>>) -> bool {
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (i, l) in loops.iter().enumerate() {
+        edges.extend((0..l.len()).map(|j| (i, j)));
+    }
+    let get_ends = |&(i, j): &(usize, usize)| (&loops[i][j], &loops[i][(j + 1) % loops[i].len()]);
+
+    for k in 0..edges.len() {
+        for m in (k + 1)..edges.len() {
+            let ((a, b), (c, d)) = (get_ends(&edges[k]), get_ends(&edges[m]));
+            if edges[k].0 == edges[m].0 {
+                let n = loops[edges[k].0].len();
+                let (j1, j2) = (edges[k].1, edges[m].1);
+                if (j1 + 1) % n == j2 {
+                    if is_folded(a, b, d) {
+                        return false;
+                    }
+                    continue;
+                }
+                if (j2 + 1) % n == j1 {
+                    if is_folded(c, d, b) {
+                        return false;
+                    }
+                    continue;
+                }
+            }
+            if do_segments_intersect(a, b, c, d) {
+                return false;
+            }
+        }
+    }
+    return true;
+}
+
+// соседние отрезки a-b и b-c налегают друг на друга
+fn is_folded(a: &Point, b: &Point, c: &Point) -> bool {
+    let dot = (&a.x - &b.x) * (&c.x - &b.x) + (&a.y - &b.y) * (&c.y - &b.y);
+    return orient2d(a, b, c) == Ordering::Equal && dot.is_it_positive();
+}
+
+fn do_segments_intersect(a: &Point, b: &Point, c: &Point, d: &Point) -> bool {
+    let (o1, o2) = (orient2d(a, b, c), orient2d(a, b, d));
+    let (o3, o4) = (orient2d(c, d, a), orient2d(c, d, b));
+    if (o1 == Ordering::Equal && is_in_box(a, b, c)) || (o2 == Ordering::Equal && is_in_box(a, b, d)) ||
+        (o3 == Ordering::Equal && is_in_box(c, d, a)) || (o4 == Ordering::Equal && is_in_box(c, d, b)) {
+        return true;
+    }
+    return [o1, o2, o3, o4].iter().all(|&o| o != Ordering::Equal) && o1 != o2 && o3 != o4;
+}
+
+fn is_in_box(a: &Point, b: &Point, p: &Point) -> bool {
+    return a.x.clone().min(b.x.clone()) <= p.x && p.x <= a.x.clone().max(b.x.clone()) &&
+        a.y.clone().min(b.y.clone()) <= p.y && p.y <= a.y.clone().max(b.y.clone());
+}
+
+// четность числа пересечений луча вправо от точки, не лежащей на петле
+fn is_inside_loop(p: &Point, l: &Vec<Point>) -> bool {
+    let mut inside = false;
+    for i in 0..l.len() {
+        let (a, b) = (&l[i], &l[(i + 1) % l.len()]);
+        if (a.y > p.y) != (b.y > p.y) && (orient2d(a, b, p) == Ordering::Greater) == (b.y > a.y) {
+            inside = !inside;
+        }
+    }
+    return inside;
 }
 
 fn map_point_to_3d(p : &Point, normal_type : &NormalType) -> Point {
@@ -268,7 +459,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &d);
         let ps : Vec<Point> = vec![a, b, c, d, e, f];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -301,7 +492,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &c);
         let ps : Vec<Point> = vec![a, b, c, d, e];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -338,7 +529,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &d);
         let ps : Vec<Point> = vec![a, b, c, d, e];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -371,7 +562,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &c);
         let ps : Vec<Point> = vec![a, b, c, d, e];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);;
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -404,7 +595,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &c);
         let ps : Vec<Point> = vec![a, b, c, d, e];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -440,7 +631,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &c);
         let ps : Vec<Point> = vec![a, b, c, d, e];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -474,7 +665,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &c);
         let ps : Vec<Point> = vec![a, b, c, d, e, f];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -509,7 +700,7 @@ mod tests {
         let plane = Plane::new_3p(&a, &b, &c);
         let ps : Vec<Point> = vec![a, b, c, d, e, f, g];
 
-        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental);
+        let ts = triangulate3d(ps.clone(), plane, TriangulationAlgorithm::Incremental).unwrap();
 
         info!("len: {} \n", ts.len());
         info!("vec: {:?} \n", ts);
@@ -529,4 +720,80 @@ mod tests {
         };
     }
 
+    fn p(x : f64, y : f64, z : f64) -> Point {
+        return Point::new_from_f64(x, y, z);
+    }
+
+    #[test]
+    fn triangulation_of_polygon_with_holes() {
+        // плоскость z = x + y
+        let normal = Vector::new_from_f64(-1., -1., 1.);
+        let outer = vec![p(0., 0., 0.), p(4., 0., 4.), p(4., 4., 8.), p(0., 4., 4.)];
+        let holes = vec![
+            vec![p(1., 1., 2.), p(1., 2., 3.), p(2., 2., 4.), p(2., 1., 3.)],
+            vec![p(3., 3., 6.), p(2.5, 3.5, 6.), p(3.5, 3.5, 7.)]
+        ];
+        let ts = triangulate_polygon3d(&outer, &holes, &normal).unwrap();
+        assert_eq!(ts.len(), 11 + 2 * 2 - 2);
+
+        // удвоенная площадь проекции на плоскость xy
+        let mut double_area = Number::new(0.);
+        for t in ts.iter() {
+            assert!(t.get_normal().dot_product(&normal).is_it_positive());
+            double_area = double_area + t.get_normal().z;
+        }
+        assert_eq!(double_area, Number::new(2. * (16. - 1. - 0.25)));
+
+        // обратная нормаль меняет ориентацию треугольников
+        let reversed = Vector::new_from_f64(1., 1., -1.);
+        let ts = triangulate_polygon3d(&outer, &holes, &reversed).unwrap();
+        assert!(ts.iter().all(|t| t.get_normal().dot_product(&reversed).is_it_positive()));
+    }
+
+    #[test]
+    fn triangulation_of_nonconvex_polygon() {
+        // L-образный многоугольник в плоскости x = 1
+        let normal = Vector::new_from_f64(-1., 0., 0.);
+        let outer = vec![p(1., 0., 0.), p(1., 0., 2.), p(1., 1., 2.), p(1., 1., 1.), p(1., 2., 1.), p(1., 2., 0.)];
+        let ts = triangulate_polygon3d(&outer, &vec![], &normal).unwrap();
+        assert_eq!(ts.len(), 4);
+
+        let mut double_area = Number::new(0.);
+        for t in ts.iter() {
+            assert!(t.get_normal().dot_product(&normal).is_it_positive());
+            for v in t.get_points_ref() {
+                assert!(outer.contains(v));
+            }
+            double_area = double_area - t.get_normal().x;
+        }
+        assert_eq!(double_area, Number::new(6.));
+    }
+
+    #[test]
+    fn wrong_polygons() {
+        let normal = Vector::new_from_f64(0., 0., 1.);
+        let square = vec![p(0., 0., 0.), p(4., 0., 0.), p(4., 4., 0.), p(0., 4., 0.)];
+        let hole = vec![p(1., 1., 0.), p(2., 1., 0.), p(1., 2., 0.)];
+        assert!(triangulate_polygon3d(&square, &vec![hole.clone()], &normal).is_ok());
+
+        assert!(triangulate_polygon3d(&square, &vec![], &Vector::new_from_f64(0., 0., 0.)).is_err());
+        assert!(triangulate_polygon3d(&square[..2].to_vec(), &vec![], &normal).is_err());
+
+        let mut bent = square.clone();
+        bent[2] = p(4., 4., 1.);
+        assert!(triangulate_polygon3d(&bent, &vec![], &normal).is_err());
+
+        let bow_tie = vec![p(0., 0., 0.), p(4., 4., 0.), p(4., 0., 0.), p(0., 4., 0.)];
+        assert!(triangulate_polygon3d(&bow_tie, &vec![], &normal).is_err());
+
+        let outside = vec![p(5., 5., 0.), p(6., 5., 0.), p(5., 6., 0.)];
+        assert!(triangulate_polygon3d(&square, &vec![outside], &normal).is_err());
+
+        let touching = vec![p(2., 0., 0.), p(3., 1., 0.), p(2., 1., 0.)];
+        assert!(triangulate_polygon3d(&square, &vec![touching], &normal).is_err());
+
+        let nested = vec![p(1.2, 1.2, 0.), p(1.5, 1.2, 0.), p(1.2, 1.5, 0.)];
+        assert!(triangulate_polygon3d(&square, &vec![hole, nested], &normal).is_err());
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod voronoi_diagram;
+
+pub use self::voronoi_diagram::{VoronoiDiagram, VoronoiCell, CellBoundary};
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use primitives::*;
+use bool_op::intersection2d;
+use triangulation::constrained_delaunay_triangulation::ConstrainedTriangulation;
+
+/// The boundary of a Voronoi cell, vertices are counterclockwise.
+#[derive(Clone, Debug)]
+pub enum CellBoundary {
+    /// A bounded cell is a convex polygon.
+    Bounded(Polygon),
+    /// An unbounded cell comes from infinity along the ray `incoming` to the first vertex, passes the vertices
+    /// and goes to infinity from the last vertex along the ray `outgoing`. Directions point to infinity.
+    Unbounded {
+        vertices: Vec<Point>,
+        incoming: Vector,
+        outgoing: Vector
+    }
+}
+
+/// The Voronoi cell of a site.
+#[derive(Clone, Debug)]
+pub struct VoronoiCell {
+    pub site: Point,
+    pub boundary: CellBoundary
+}
+
+impl VoronoiCell {
+    pub fn is_bounded(&self) -> bool {
+        return match self.boundary {
+            CellBoundary::Bounded(_) => true,
+            CellBoundary::Unbounded { .. } => false
+        };
+    }
+}
+
+/// Voronoi diagram of points in the plane xy. It's built as the dual of the exact Delaunay triangulation,
+/// so vertices of cells are exact circumcenters of Delaunay triangles.
+pub struct VoronoiDiagram {
+    cells: Vec<VoronoiCell>
+}
+
+impl VoronoiDiagram {
+    /// This method builds the Voronoi diagram. Duplicated sites are merged, cells are ordered lexicographically by sites.
+    /// It returns an error if there are less than 3 sites or all of them are collinear.
+    /// # Arguments
+    ///
+    /// * `sites` - Points, z coordinates are ignored.
+    pub fn new(sites: Vec<Point>) -> Result<VoronoiDiagram, &'static str> {
+        let ct = ConstrainedTriangulation::new(sites, Vec::new())?;
+        if ct.get_index_triangles().is_empty() {
+            return Err("Sites are collinear or there are less than 3 sites!");
+        }
+
+        let ps = ct.get_points();
+        let ts = ct.get_index_triangles();
+
+        let mut triangle_of_vertex: HashMap<usize, usize> = HashMap::new();
+        let mut hull_next: HashMap<usize, usize> = HashMap::new();
+        for (i, t) in ts.iter().enumerate() {
+            for j in 0..3 {
+                let (a, b) = (t[j], t[(j + 1) % 3]);
+                triangle_of_vertex.insert(a, i);
+                if ct.get_edge_triangle(b, a).is_none() {
+                    hull_next.insert(a, b);
+                }
+            }
+        }
+
+        let mut cells: Vec<VoronoiCell> = Vec::new();
+        for v in 0..ps.len() {
+            // обход треугольников вокруг вершины против часовой стрелки
+            let start = match hull_next.get(&v) {
+                Some(&w) => ct.get_edge_triangle(v, w).unwrap(),
+                None => triangle_of_vertex[&v]
+            };
+            let mut vertices: Vec<Point> = Vec::new();
+            let mut cur = start;
+            let last_vertex = loop {
+                let t = ts[cur];
+                vertices.push(ct.get_circumcenter(&t));
+                let k = t.iter().position(|&x| x == v).unwrap();
+                let x = t[(k + 2) % 3];
+                match ct.get_edge_triangle(v, x) {
+                    Some(next) if next == start => break x,
+                    Some(next) => cur = next,
+                    None => break x
+                }
+            };
+
+            // совпадающие центры у соседних треугольников с вершинами на одной окружности
+            vertices.dedup();
+            let boundary = match hull_next.get(&v) {
+                Some(&w) => CellBoundary::Unbounded {
+                    vertices: vertices,
+                    incoming: get_outward_normal(&ps[v], &ps[w]),
+                    outgoing: get_outward_normal(&ps[last_vertex], &ps[v])
+                },
+                None => {
+                    if vertices.len() > 1 && vertices.first() == vertices.last() {
+                        vertices.pop();
+                    }
+                    CellBoundary::Bounded(Polygon::new(vertices, Vector::new_from_f64(0., 0., 1.)))
+                }
+            };
+            cells.push(VoronoiCell { site: ps[v].clone(), boundary: boundary });
+        }
+
+        return Ok(VoronoiDiagram { cells: cells });
+    }
+
+    pub fn get_cells(&self) -> &Vec<VoronoiCell> {
+        return &self.cells;
+    }
+
+    /// This method clips cells by the polygon `boundary`. It returns regions for each cell in the same order as `get_cells`,
+    /// a region is empty, if the cell doesn't intersect the polygon, and can have several parts for a non-convex polygon.
+    /// # Arguments
+    ///
+    /// * `boundary` - A simple polygon in the plane xy.
+    pub fn clip(&self, boundary: &Polygon) -> Result<Vec<Vec<PolygonTreeNode>>, &'static str> {
+        let clip_region = vec![PolygonTreeNode::new(boundary.clone())];
+
+        // лучи заменяются точками, лежащими далеко за пределами многоугольника
+        let mut size: f64 = 0.;
+        for p in boundary.get_points_ref().iter().chain(self.cells.iter().map(|c| &c.site)) {
+            size = size.max(p.x.clone().abs().convert_to_f64()).max(p.y.clone().abs().convert_to_f64());
+        }
+        for cell in self.cells.iter() {
+            let vs = match cell.boundary {
+                CellBoundary::Bounded(ref polygon) => polygon.get_points_ref(),
+                CellBoundary::Unbounded { ref vertices, .. } => vertices
+            };
+            for p in vs.iter() {
+                size = size.max(p.x.clone().abs().convert_to_f64()).max(p.y.clone().abs().convert_to_f64());
+            }
+        }
+        let far = 16. * size + 1.;
+
+        let mut res: Vec<Vec<PolygonTreeNode>> = Vec::new();
+        for cell in self.cells.iter() {
+            let polygon = match cell.boundary {
+                CellBoundary::Bounded(ref polygon) => polygon.clone(),
+                CellBoundary::Unbounded { ref vertices, ref incoming, ref outgoing } => {
+                    let first = vertices.first().unwrap();
+                    let last = vertices.last().unwrap();
+                    // дальние точки обходятся против часовой стрелки от луча outgoing к лучу incoming
+                    let cross = &outgoing.x * &incoming.y - &outgoing.y * &incoming.x;
+                    let dot = &outgoing.x * &incoming.x + &outgoing.y * &incoming.y;
+                    let middle = if cross.is_it_positive() {
+                        let (o, i) = (get_unit(outgoing), get_unit(incoming));
+                        Some(Vector::new(&o.x + &i.x, &o.y + &i.y, Number::new(0.)))
+                    } else if dot.is_it_negative() {
+                        Some(Vector::new(-outgoing.y.clone(), outgoing.x.clone(), Number::new(0.)))
+                    } else {
+                        None
+                    };
+
+                    let mut points = vertices.clone();
+                    points.push(get_far_point(last, outgoing, far));
+                    if let Some(middle) = middle {
+                        points.push(get_far_point(last, &middle, far));
+                    }
+                    points.push(get_far_point(first, incoming, far));
+                    Polygon::new(points, Vector::new_from_f64(0., 0., 1.))
+                }
+            };
+            res.push(intersection2d(&[PolygonTreeNode::new(polygon)], &clip_region)?);
+        }
+        return Ok(res);
+    }
+}
+
+fn get_outward_normal(a: &Point, b: &Point) -> Vector {
+    // оболочка обходится против часовой стрелки, поэтому внешняя нормаль направлена вправо
+    return Vector::new(&b.y - &a.y, &a.x - &b.x, Number::new(0.));
+}
+
+fn get_unit(v: &Vector) -> Vector {
+    let (x, y) = (v.x.clone().convert_to_f64(), v.y.clone().convert_to_f64());
+    let len = (x * x + y * y).sqrt();
+    return Vector::new(Number::new(x / len), Number::new(y / len), Number::new(0.));
+}
+
+// точка лежит точно на луче: приближенно вычисляется только рациональный множитель направления
+fn get_far_point(p: &Point, direction: &Vector, distance: f64) -> Point {
+    let (x, y) = (direction.x.clone().convert_to_f64(), direction.y.clone().convert_to_f64());
+    let k = Number::new(distance / (x * x + y * y).sqrt());
+    return Point::new(&p.x + &(&direction.x * &k), &p.y + &(&direction.y * &k), p.z.clone());
+}
+
+
+#[cfg(test)]
+mod tests {
+    use primitives::*;
+    use primitives::fixtures::*;
+    use voronoi::*;
+
+    fn p(x: f64, y: f64) -> Point {
+        return Point::new_from_f64(x, y, 0.);
+    }
+
+    #[test]
+    fn voronoi_of_triangle() {
+        let diagram = VoronoiDiagram::new(vec![p(0., 0.), p(3., 0.), p(0., 1.)]).unwrap();
+        let cells = diagram.get_cells();
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[0].site, p(0., 0.));
+        match cells[0].boundary {
+            CellBoundary::Unbounded { ref vertices, ref incoming, ref outgoing } => {
+                assert_eq!(vertices, &vec![p(1.5, 0.5)]);
+                assert_eq!(incoming, &Vector::new_from_f64(0., -3., 0.));
+                assert_eq!(outgoing, &Vector::new_from_f64(-1., 0., 0.));
+            },
+            _ => panic!("Cell has to be unbounded!")
+        }
+        assert!(cells.iter().all(|c| !c.is_bounded()));
+    }
+
+    #[test]
+    fn voronoi_of_grid() {
+        let mut sites = Vec::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                sites.push(p(i as f64, j as f64));
+            }
+        }
+        let diagram = VoronoiDiagram::new(sites).unwrap();
+        let cells = diagram.get_cells();
+        assert_eq!(cells.len(), 9);
+
+        let center = cells.iter().find(|c| c.site == p(1., 1.)).unwrap();
+        match center.boundary {
+            CellBoundary::Bounded(ref polygon) => {
+                assert_eq!(polygon.get_points_ref().len(), 4);
+                assert_eq!(polygon.signed_area(), Number::new(1.));
+            },
+            _ => panic!("Cell has to be bounded!")
+        }
+        assert_eq!(cells.iter().filter(|c| c.is_bounded()).count(), 1);
+
+        let boundary = Polygon::new(vec![p(-0.5, -0.5), p(2.5, -0.5), p(2.5, 2.5), p(-0.5, 2.5)], Vector::new_from_f64(0., 0., 1.));
+        let clipped = diagram.clip(&boundary).unwrap();
+        assert_eq!(clipped.len(), 9);
+        for region in clipped.iter() {
+            assert_eq!(region.len(), 1);
+            assert_eq!(forest_area(region), Number::new(1.));
+        }
+    }
+
+    #[test]
+    fn clip_by_triangle() {
+        let diagram = VoronoiDiagram::new(vec![p(0., 0.), p(4., 0.), p(0., 4.), p(4., 4.), p(2., 1.)]).unwrap();
+        let boundary = Polygon::new(vec![p(-1., -1.), p(5., -1.), p(-1., 5.)], Vector::new_from_f64(0., 0., 1.));
+        let clipped = diagram.clip(&boundary).unwrap();
+        let total = clipped.iter().fold(Number::new(0.), |s, region| s + forest_area(region));
+        assert_eq!(total, Number::new(18.));
+        assert!(clipped.iter().filter(|region| !region.is_empty()).count() >= 3);
+    }
+
+    #[test]
+    fn clip_with_oblique_rays() {
+        // лучи не параллельны осям, поэтому их направления не нормируются точно
+        let diagram = VoronoiDiagram::new(vec![p(0., 0.), p(3., 1.), p(1., 4.), p(5., 5.), p(2., 2.)]).unwrap();
+        let boundary = Polygon::new(vec![p(-3., -2.), p(9., -1.), p(2., 10.)], Vector::new_from_f64(0., 0., 1.));
+        let clipped = diagram.clip(&boundary).unwrap();
+        let total = clipped.iter().fold(Number::new(0.), |s, region| s + forest_area(region));
+        assert_eq!(total, boundary.signed_area());
+
+        // вершины частей лежат в своих ячейках точно: ни один другой центр не ближе
+        let sites: Vec<Point> = diagram.get_cells().iter().map(|c| c.site.clone()).collect();
+        let squared_distance = |a: &Point, b: &Point| (&a.x - &b.x) * (&a.x - &b.x) + (&a.y - &b.y) * (&a.y - &b.y);
+        for (site, region) in sites.iter().zip(clipped.iter()) {
+            assert_eq!(region.len(), 1);
+            for q in region[0].get_cur_polygon().get_points_ref().iter() {
+                let own = squared_distance(q, site);
+                assert!(sites.iter().all(|other| squared_distance(q, other) >= own));
+            }
+        }
+    }
+
+    #[test]
+    fn collinear_sites() {
+        assert!(VoronoiDiagram::new(vec![p(0., 0.), p(1., 1.), p(2., 2.)]).is_err());
+        assert!(VoronoiDiagram::new(vec![p(0., 0.), p(1., 1.)]).is_err());
+    }
+}